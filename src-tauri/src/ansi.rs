@@ -0,0 +1,266 @@
+use serde::Serialize;
+
+/// A terminal color: a palette index, or `#rrggbb` for true color
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Color {
+    Indexed(u8),
+    Rgb(String),
+}
+
+/// Text attributes set by SGR sequences. Unset fields are left out of the
+/// JSON to keep captures small.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Style {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fg: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bg: Option<Color>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dim: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub underline: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub blink: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub reverse: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub strikethrough: bool,
+}
+
+impl Style {
+    /// Apply an SGR parameter list, e.g. `1;38;5;208` or `38:2::255:0:0`
+    fn apply(&mut self, params: &str) {
+        let params: Vec<Vec<u32>> = params
+            .split(';')
+            .map(|param| param.split(':').map(|n| n.parse().unwrap_or(0)).collect())
+            .collect();
+        let mut i = 0;
+        while i < params.len() {
+            let param = &params[i];
+            match param[0] {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                // `4:0` turns underline off; other styles are still underline
+                4 => self.underline = param.get(1) != Some(&0),
+                5 | 6 => self.blink = true,
+                7 => self.reverse = true,
+                8 => self.hidden = true,
+                9 => self.strikethrough = true,
+                21 => self.underline = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                25 => self.blink = false,
+                27 => self.reverse = false,
+                28 => self.hidden = false,
+                29 => self.strikethrough = false,
+                n @ 30..=37 => self.fg = Some(Color::Indexed((n - 30) as u8)),
+                39 => self.fg = None,
+                n @ 40..=47 => self.bg = Some(Color::Indexed((n - 40) as u8)),
+                49 => self.bg = None,
+                n @ 90..=97 => self.fg = Some(Color::Indexed((n - 90 + 8) as u8)),
+                n @ 100..=107 => self.bg = Some(Color::Indexed((n - 100 + 8) as u8)),
+                // Extended colors; 58 (underline color) is parsed but not kept
+                n @ (38 | 48 | 58) => {
+                    let (color, used) = extended_color(param, &params[i + 1..]);
+                    match n {
+                        38 => self.fg = color,
+                        48 => self.bg = color,
+                        _ => {}
+                    }
+                    i += used;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+/// Parse `38;5;n`, `38;2;r;g;b` or their colon forms. Returns the color
+/// and how many of the following `;` parameters it used.
+fn extended_color(param: &[u32], rest: &[Vec<u32>]) -> (Option<Color>, usize) {
+    let (args, used): (Vec<u32>, usize) = if param.len() > 1 {
+        (param[1..].to_vec(), 0)
+    } else {
+        let kind = rest.first().map(|p| p[0]);
+        let count = match kind {
+            Some(5) => 2,
+            Some(2) => 4,
+            _ => 1,
+        };
+        let count = count.min(rest.len());
+        (rest[..count].iter().map(|p| p[0]).collect(), count)
+    };
+    let color = match args.as_slice() {
+        [5, n, ..] => Some(Color::Indexed(*n as u8)),
+        // The colon form may carry a color space ID before the channels
+        [2, _, r, g, b] | [2, r, g, b] => Some(Color::Rgb(format!("#{:02x}{:02x}{:02x}", r, g, b))),
+        _ => None,
+    };
+    (color, used)
+}
+
+/// A run of text in one style
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StyledSpan {
+    pub text: String,
+    #[serde(flatten)]
+    pub style: Style,
+}
+
+/// One screen cell. A wide character's second cell has empty `ch`;
+/// combining marks join the cell before them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cell {
+    pub ch: String,
+    #[serde(flatten)]
+    pub style: Style,
+}
+
+/// Split `capture-pane -e` output into lines of styled spans. Styles carry
+/// over from one line to the next, as tmux only writes changes. Escape
+/// sequences other than SGR are dropped.
+pub fn parse_spans(content: &str) -> Vec<Vec<StyledSpan>> {
+    let mut lines = Vec::new();
+    let mut spans: Vec<StyledSpan> = Vec::new();
+    let mut style = Style::default();
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => lines.push(std::mem::take(&mut spans)),
+            '\x1b' => match chars.next() {
+                // CSI: parameters, then a final byte
+                Some('[') => {
+                    let mut params = String::new();
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            if c == 'm' {
+                                style.apply(&params);
+                            }
+                            break;
+                        }
+                        params.push(c);
+                    }
+                }
+                // OSC (e.g. hyperlinks): up to BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            c if c.is_control() && c != '\t' => {}
+            c => match spans.last_mut() {
+                Some(span) if span.style == style => span.text.push(c),
+                _ => spans.push(StyledSpan {
+                    text: c.to_string(),
+                    style: style.clone(),
+                }),
+            },
+        }
+    }
+    if !spans.is_empty() || !(content.is_empty() || content.ends_with('\n')) {
+        lines.push(spans);
+    }
+    lines
+}
+
+/// Lay `capture-pane -e` output out as rows of `width` cells
+pub fn parse_cells(content: &str, width: usize) -> Vec<Vec<Cell>> {
+    parse_spans(content)
+        .into_iter()
+        .map(|spans| {
+            let mut row: Vec<Cell> = Vec::with_capacity(width);
+            for span in spans {
+                for c in span.text.chars() {
+                    match char_width(c) {
+                        0 => match row.last_mut() {
+                            Some(cell) => cell.ch.push(c),
+                            None => row.push(Cell {
+                                ch: c.to_string(),
+                                style: span.style.clone(),
+                            }),
+                        },
+                        n => {
+                            row.push(Cell {
+                                ch: c.to_string(),
+                                style: span.style.clone(),
+                            });
+                            if n == 2 {
+                                row.push(Cell {
+                                    ch: String::new(),
+                                    style: span.style.clone(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            row.truncate(width);
+            row.resize(
+                width,
+                Cell {
+                    ch: " ".to_string(),
+                    style: Style::default(),
+                },
+            );
+            row
+        })
+        .collect()
+}
+
+/// Columns a character takes in a terminal: 0 for combining marks, 2 for
+/// East Asian wide characters and emoji, else 1
+fn char_width(c: char) -> usize {
+    const ZERO: &[(u32, u32)] = &[
+        (0x0300, 0x036f),
+        (0x1ab0, 0x1aff),
+        (0x1dc0, 0x1dff),
+        (0x200b, 0x200f),
+        (0x20d0, 0x20ff),
+        (0xfe00, 0xfe0f),
+        (0xfe20, 0xfe2f),
+    ];
+    const WIDE: &[(u32, u32)] = &[
+        (0x1100, 0x115f),
+        (0x2e80, 0x303e),
+        (0x3041, 0x33ff),
+        (0x3400, 0x4dbf),
+        (0x4e00, 0x9fff),
+        (0xa000, 0xa4cf),
+        (0xac00, 0xd7a3),
+        (0xf900, 0xfaff),
+        (0xfe30, 0xfe4f),
+        (0xff00, 0xff60),
+        (0xffe0, 0xffe6),
+        (0x1f300, 0x1f64f),
+        (0x1f900, 0x1f9ff),
+        (0x20000, 0x3fffd),
+    ];
+    let c = c as u32;
+    let within = |ranges: &[(u32, u32)]| ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&c));
+    if within(ZERO) {
+        0
+    } else if within(WIDE) {
+        2
+    } else {
+        1
+    }
+}
@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+use crate::client::CliError;
+use crate::event_store::{format_timestamp, parse_timestamp};
+use crate::executor::{self, ExecutorState, RiskClass};
+use crate::gastown::{self, CommandResult};
+use crate::runner::{ProcessOutput, RunnerState};
+
+/// File in the app data dir the audit trail is appended to
+const AUDIT_FILE: &str = "audit.jsonl";
+
+/// Entries returned by a query when no limit is given
+const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Where a command request came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandOrigin {
+    #[default]
+    Ui,
+    Voice,
+    SelfTest,
+}
+
+/// How a command request ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// Ran; see the exit code
+    Ran,
+    /// Refused by the allowlist or for lack of confirmation
+    Denied,
+    /// Allowed, but couldn't be started or was killed by a timeout or cancel
+    Failed,
+}
+
+/// One command request, as recorded in the audit trail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Pass to `rerun_audit_entry` to run the same argv again
+    pub id: String,
+    pub timestamp: String,
+    pub origin: CommandOrigin,
+    pub argv: Vec<String>,
+    pub risk: Option<RiskClass>,
+    pub outcome: AuditOutcome,
+    pub exit_code: Option<i32>,
+    pub duration_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(
+        origin: CommandOrigin,
+        argv: Vec<String>,
+        risk: Option<RiskClass>,
+        outcome: AuditOutcome,
+    ) -> Self {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: format_timestamp(now_ms),
+            origin,
+            argv,
+            risk,
+            outcome,
+            exit_code: None,
+            duration_ms: None,
+            error: None,
+        }
+    }
+
+    /// Entry for a command that was refused before it ran
+    pub fn denied(
+        origin: CommandOrigin,
+        argv: Vec<String>,
+        risk: Option<RiskClass>,
+        error: &str,
+    ) -> Self {
+        let mut entry = Self::new(origin, argv, risk, AuditOutcome::Denied);
+        entry.error = Some(error.to_string());
+        entry
+    }
+
+    /// Entry for a command that was started, however it ended
+    pub fn finished(
+        origin: CommandOrigin,
+        argv: Vec<String>,
+        risk: Option<RiskClass>,
+        result: &Result<ProcessOutput, String>,
+        elapsed: Duration,
+    ) -> Self {
+        let mut entry = match result {
+            Ok(output) => {
+                let mut entry = Self::new(origin, argv, risk, AuditOutcome::Ran);
+                entry.exit_code = Some(output.exit_code);
+                entry
+            }
+            Err(e) => {
+                let mut entry = Self::new(origin, argv, risk, AuditOutcome::Failed);
+                entry.error = Some(e.clone());
+                entry
+            }
+        };
+        entry.duration_ms = Some(elapsed.as_millis() as u64);
+        entry
+    }
+}
+
+/// Filter for `query_audit_log`; every condition that is set must match
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditQuery {
+    /// RFC 3339 start of the range, inclusive
+    pub since: Option<String>,
+    /// RFC 3339 end of the range, exclusive
+    pub until: Option<String>,
+    pub origin: Option<CommandOrigin>,
+    /// Program name, e.g. `gt`
+    pub program: Option<String>,
+    pub outcome: Option<AuditOutcome>,
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry, since: Option<i64>, until: Option<i64>) -> bool {
+        if since.is_some() || until.is_some() {
+            let Some(ts) = parse_timestamp(&entry.timestamp) else {
+                return false;
+            };
+            if since.is_some_and(|since| ts < since) || until.is_some_and(|until| ts >= until) {
+                return false;
+            }
+        }
+        if self.origin.is_some_and(|origin| origin != entry.origin) {
+            return false;
+        }
+        if let Some(program) = &self.program {
+            if entry.argv.first() != Some(program) {
+                return false;
+            }
+        }
+        if self.outcome.is_some_and(|outcome| outcome != entry.outcome) {
+            return false;
+        }
+        true
+    }
+}
+
+fn audit_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(dir.join(AUDIT_FILE))
+}
+
+/// Append an entry to the audit trail. Failing to audit is logged, not fatal.
+pub fn record(app: &AppHandle, entry: &AuditEntry) {
+    if let Err(e) = append(app, entry) {
+        log::warn!("Failed to write audit entry for {:?}: {}", entry.argv, e);
+    }
+}
+
+fn append(app: &AppHandle, entry: &AuditEntry) -> Result<(), String> {
+    let path = audit_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create audit directory: {}", e))?;
+    }
+    let line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open audit log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write audit log: {}", e))
+}
+
+/// Every entry in the audit trail, oldest first. Unreadable lines are skipped.
+fn read_entries(app: &AppHandle) -> Result<Vec<AuditEntry>, String> {
+    let path = audit_path(app)?;
+    let file = match fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(format!("Failed to open audit log: {}", e)),
+    };
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect())
+}
+
+/// Time a command and record how it went
+pub async fn run_recorded<F>(
+    app: &AppHandle,
+    origin: CommandOrigin,
+    argv: Vec<String>,
+    risk: Option<RiskClass>,
+    run: F,
+) -> Result<ProcessOutput, String>
+where
+    F: std::future::Future<Output = Result<ProcessOutput, String>>,
+{
+    let started = Instant::now();
+    let result = run.await;
+    record(
+        app,
+        &AuditEntry::finished(origin, argv, risk, &result, started.elapsed()),
+    );
+    result
+}
+
+/// Time a typed CLI call and record how it went, with the risk the
+/// executor gives its argv
+pub async fn run_cli_recorded<T, F>(
+    app: &AppHandle,
+    origin: CommandOrigin,
+    argv: Vec<String>,
+    run: F,
+) -> Result<T, CliError>
+where
+    F: std::future::Future<Output = Result<T, CliError>>,
+{
+    let started = Instant::now();
+    let result = run.await;
+    let (outcome, exit_code) = match &result {
+        Ok(_) => (AuditOutcome::Ran, Some(0)),
+        Err(CliError::Failed { exit_code, .. }) => (AuditOutcome::Ran, Some(*exit_code)),
+        Err(_) => (AuditOutcome::Failed, None),
+    };
+    let risk = argv
+        .split_first()
+        .and_then(|(program, args)| executor::classify(program, args).ok());
+    let mut entry = AuditEntry::new(origin, argv, risk, outcome);
+    entry.exit_code = exit_code;
+    entry.duration_ms = Some(started.elapsed().as_millis() as u64);
+    entry.error = result.as_ref().err().map(|e| e.to_string());
+    record(app, &entry);
+    result
+}
+
+/// Query the command audit log, newest first
+#[tauri::command]
+pub async fn query_audit_log(
+    app: AppHandle,
+    query: Option<AuditQuery>,
+) -> Result<Vec<AuditEntry>, String> {
+    let query = query.unwrap_or_default();
+    let parse = |ts: &Option<String>| -> Result<Option<i64>, String> {
+        ts.as_deref()
+            .map(|ts| parse_timestamp(ts).ok_or(format!("Invalid timestamp: {}", ts)))
+            .transpose()
+    };
+    let since = parse(&query.since)?;
+    let until = parse(&query.until)?;
+    let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+
+    Ok(read_entries(&app)?
+        .into_iter()
+        .rev()
+        .filter(|entry| query.matches(entry, since, until))
+        .take(limit)
+        .collect())
+}
+
+/// Run an audited command again, through the same allowlist and confirmation
+/// checks as `run_gt_command`. The re-run is audited as coming from the UI.
+/// Installs (go, brew) aren't on the allowlist; run `install_dependency`
+/// again for those.
+#[tauri::command]
+pub async fn rerun_audit_entry(
+    app: AppHandle,
+    executor_state: State<'_, ExecutorState>,
+    runner_state: State<'_, RunnerState>,
+    id: String,
+    confirmation_token: Option<String>,
+    job_id: Option<String>,
+    town: Option<String>,
+) -> Result<CommandResult, String> {
+    let entry = read_entries(&app)?
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| format!("No audit entry with id {}", id))?;
+    let mut argv = entry.argv.into_iter();
+    let program = argv.next().ok_or("Audit entry has no command")?;
+    if !executor::ALLOWED_PROGRAMS.contains(&program.as_str()) {
+        return Err(format!(
+            "`{}` was run to install a dependency and can't be re-run from the audit log; install it again instead",
+            program
+        ));
+    }
+
+    gastown::run_gt_command(
+        app,
+        executor_state,
+        runner_state,
+        Some(program),
+        argv.collect(),
+        confirmation_token,
+        job_id,
+        None,
+        Some(CommandOrigin::Ui),
+        town,
+    )
+    .await
+}
@@ -0,0 +1,929 @@
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::convoy;
+use crate::refinery;
+use crate::tail::JsonlTail;
+use crate::towns;
+
+/// A dependency edge between two beads (`issue_id` depends on `depends_on_id`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dependency {
+    pub issue_id: String,
+    pub depends_on_id: String,
+    #[serde(rename = "type", default = "default_dependency_type")]
+    pub dep_type: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub created_by: Option<String>,
+}
+
+fn default_dependency_type() -> String {
+    "blocks".to_string()
+}
+
+/// Treat `null` the same as a missing field for list-valued columns
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// A single bead from issues.jsonl
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Issue {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub priority: Option<i64>,
+    #[serde(default)]
+    pub issue_type: Option<String>,
+    #[serde(default)]
+    pub assignee: Option<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub labels: Vec<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub dependencies: Vec<Dependency>,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub updated_at: String,
+    #[serde(default)]
+    pub closed_at: Option<String>,
+    #[serde(default)]
+    pub close_reason: Option<String>,
+    /// Fields this version of the UI doesn't model, kept so nothing is lost
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Issue {
+    /// Rig this bead belongs to: an explicit `rig` field, or the `rig:` line
+    /// that gt writes into merge-request and agent descriptions
+    pub fn rig(&self) -> Option<String> {
+        if let Some(rig) = self.extra.get("rig").and_then(|v| v.as_str()) {
+            return Some(rig.to_string());
+        }
+        self.description_field("rig")
+    }
+
+    /// Read a `key: value` line from the description (gt's metadata convention)
+    pub fn description_field(&self, key: &str) -> Option<String> {
+        let description = self.description.as_deref()?;
+        description.lines().find_map(|line| {
+            let (k, v) = line.split_once(':')?;
+            let v = v.trim();
+            if k.trim() == key && !v.is_empty() && v != "null" {
+                Some(v.to_string())
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// A line in issues.jsonl that could not be parsed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineError {
+    pub line: usize,
+    pub message: String,
+    pub content: String,
+}
+
+/// Max characters of a bad line echoed back in a `LineError`
+const ERROR_CONTENT_LIMIT: usize = 200;
+
+/// Activity events an index keeps; the oldest are dropped past this
+pub const HISTORY_LIMIT: usize = 10_000;
+
+/// A single entry in the beads activity history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    /// Order in which the index recorded the event; breaks timestamp ties
+    #[serde(default)]
+    pub seq: u64,
+    pub timestamp: String,
+    pub event_type: String, // "created", "claimed", "closed", "merged", "reopened", ...
+    pub actor: Option<String>, // Who did it
+    pub target_id: String,  // Issue ID
+    pub target_title: String, // Issue title
+    pub details: Option<String>, // Extra info like close_reason
+    /// Field that changed, for transition events
+    #[serde(default)]
+    pub field: Option<String>,
+    #[serde(default)]
+    pub old_value: Option<String>,
+    #[serde(default)]
+    pub new_value: Option<String>,
+}
+
+impl ActivityEvent {
+    fn new(issue: &Issue, event_type: &str, timestamp: &str) -> Self {
+        Self {
+            seq: 0,
+            timestamp: timestamp.to_string(),
+            event_type: event_type.to_string(),
+            actor: issue.assignee.clone().or_else(|| issue.created_by.clone()),
+            target_id: issue.id.clone(),
+            target_title: issue.title.clone(),
+            details: None,
+            field: None,
+            old_value: None,
+            new_value: None,
+        }
+    }
+
+    fn change(mut self, field: &str, old: Option<String>, new: Option<String>) -> Self {
+        self.field = Some(field.to_string());
+        self.old_value = old;
+        self.new_value = new;
+        self
+    }
+
+    /// Cursor position of this event in the newest-first feed
+    fn cursor(&self) -> String {
+        format!("{}#{}", self.timestamp, self.seq)
+    }
+}
+
+/// Statuses that mean an agent has picked the work up
+fn is_claimed_status(status: &str) -> bool {
+    matches!(status, "in_progress" | "hooked")
+}
+
+/// Events for the first snapshot we see of an issue
+fn initial_events(issue: &Issue) -> Vec<ActivityEvent> {
+    let mut events = vec![ActivityEvent {
+        actor: issue.created_by.clone(),
+        ..ActivityEvent::new(issue, "created", &issue.created_at)
+    }];
+
+    if is_claimed_status(&issue.status) && issue.assignee.is_some() {
+        events.push(
+            ActivityEvent::new(issue, "claimed", &issue.updated_at).change(
+                "status",
+                None,
+                Some(issue.status.clone()),
+            ),
+        );
+    }
+
+    if issue.status == "closed" {
+        events.extend(close_events(issue, None));
+    }
+
+    events
+}
+
+/// Closed (and for merge requests, merged) events for an issue that just closed
+fn close_events(issue: &Issue, old_status: Option<String>) -> Vec<ActivityEvent> {
+    let timestamp = issue.closed_at.as_deref().unwrap_or(&issue.updated_at);
+    let mut events = vec![ActivityEvent {
+        details: issue.close_reason.clone(),
+        ..ActivityEvent::new(issue, "closed", timestamp).change(
+            "status",
+            old_status,
+            Some(issue.status.clone()),
+        )
+    }];
+
+    if issue.issue_type.as_deref() == Some("merge-request") {
+        events.push(ActivityEvent {
+            actor: Some("refinery".to_string()),
+            ..ActivityEvent::new(issue, "merged", timestamp)
+        });
+    }
+
+    events
+}
+
+/// Field-by-field transitions between two successive snapshots of an issue
+fn transition_events(old: &Issue, new: &Issue) -> Vec<ActivityEvent> {
+    let mut events = Vec::new();
+    let ts = new.updated_at.as_str();
+
+    if old.status != new.status {
+        let from = Some(old.status.clone());
+        if new.status == "closed" {
+            events.extend(close_events(new, from));
+        } else if old.status == "closed" {
+            events.push(ActivityEvent::new(new, "reopened", ts).change(
+                "status",
+                from,
+                Some(new.status.clone()),
+            ));
+        } else if is_claimed_status(&new.status) && new.assignee.is_some() {
+            events.push(ActivityEvent::new(new, "claimed", ts).change(
+                "status",
+                from,
+                Some(new.status.clone()),
+            ));
+        } else {
+            events.push(ActivityEvent::new(new, "status_changed", ts).change(
+                "status",
+                from,
+                Some(new.status.clone()),
+            ));
+        }
+    }
+
+    if old.assignee != new.assignee {
+        let event_type = if new.assignee.is_some() {
+            "assigned"
+        } else {
+            "unassigned"
+        };
+        events.push(ActivityEvent::new(new, event_type, ts).change(
+            "assignee",
+            old.assignee.clone(),
+            new.assignee.clone(),
+        ));
+    }
+
+    if old.priority != new.priority {
+        events.push(ActivityEvent::new(new, "priority_changed", ts).change(
+            "priority",
+            old.priority.map(|p| p.to_string()),
+            new.priority.map(|p| p.to_string()),
+        ));
+    }
+
+    if old.title != new.title {
+        events.push(ActivityEvent::new(new, "retitled", ts).change(
+            "title",
+            Some(old.title.clone()),
+            Some(new.title.clone()),
+        ));
+    }
+
+    for label in new.labels.iter().filter(|l| !old.labels.contains(l)) {
+        events.push(ActivityEvent::new(new, "labeled", ts).change(
+            "labels",
+            None,
+            Some(label.clone()),
+        ));
+    }
+    for label in old.labels.iter().filter(|l| !new.labels.contains(l)) {
+        events.push(ActivityEvent::new(new, "unlabeled", ts).change(
+            "labels",
+            Some(label.clone()),
+            None,
+        ));
+    }
+
+    events
+}
+
+/// First string value among `keys` in a JSON object
+fn str_field(value: &serde_json::Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|k| match value.get(*k)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Null => None,
+        other => Some(other.to_string()),
+    })
+}
+
+/// Read an interactions.jsonl entry as an activity event. bd versions disagree
+/// on field names, so accept the common spellings.
+fn interaction_event(value: &serde_json::Value) -> Option<ActivityEvent> {
+    Some(ActivityEvent {
+        seq: 0,
+        timestamp: str_field(value, &["timestamp", "created_at", "ts"])?,
+        event_type: str_field(value, &["event_type", "kind", "type", "action"])?,
+        actor: str_field(value, &["actor", "created_by", "agent"]),
+        target_id: str_field(value, &["target_id", "issue_id", "bead_id"])?,
+        target_title: str_field(value, &["target_title", "title"]).unwrap_or_default(),
+        details: str_field(value, &["details", "comment", "reason", "message"]),
+        field: str_field(value, &["field"]),
+        old_value: str_field(value, &["old_value", "from"]),
+        new_value: str_field(value, &["new_value", "to"]),
+    })
+}
+
+/// Change to an indexed beads directory, pushed to the frontend as `beads-changed`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BeadsDelta {
+    pub beads_dir: String,
+    /// issues.jsonl was rewritten and re-read from scratch
+    pub reset: bool,
+    pub added: Vec<Issue>,
+    pub updated: Vec<Issue>,
+    pub removed: Vec<String>,
+    /// History events recorded by this change
+    pub activity: Vec<ActivityEvent>,
+    pub errors: Vec<LineError>,
+}
+
+impl BeadsDelta {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.updated.is_empty()
+            && self.removed.is_empty()
+            && self.activity.is_empty()
+            && self.errors.is_empty()
+    }
+}
+
+/// Issues one refresh has touched, to work out the delta
+#[derive(Default)]
+struct Touched {
+    /// Each touched issue as it was before the refresh
+    before: Vec<(String, Option<Issue>)>,
+    seen: HashSet<String>,
+    /// Issues dropped by a rewrite, until the rewritten file mentions them
+    stale: HashMap<String, Issue>,
+}
+
+/// What makes two activity events the same: when, what, to which bead, and
+/// which change (two labels added in one update are two events)
+type HistoryKey = (
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+fn history_key(event: &ActivityEvent) -> HistoryKey {
+    (
+        event.timestamp.clone(),
+        event.event_type.clone(),
+        event.target_id.clone(),
+        event.field.clone(),
+        event.old_value.clone(),
+        event.new_value.clone(),
+    )
+}
+
+/// In-memory index of one beads directory, kept current by tailing its JSONL files
+pub struct BeadsIndex {
+    beads_dir: PathBuf,
+    issues_tail: JsonlTail,
+    interactions_tail: JsonlTail,
+    issues: Vec<Issue>,
+    by_id: HashMap<String, usize>,
+    /// The newest lifecycle events recorded since the index was opened,
+    /// by (timestamp, seq)
+    history: BTreeMap<(String, u64), ActivityEvent>,
+    history_keys: HashSet<HistoryKey>,
+    next_seq: u64,
+    errors: Vec<LineError>,
+    unknown_fields: BTreeSet<String>,
+    issue_lines: usize,
+}
+
+impl BeadsIndex {
+    /// Load a beads directory and index everything currently in it
+    pub fn open(beads_dir: &Path) -> Result<Self, String> {
+        let mut index = Self {
+            beads_dir: beads_dir.to_path_buf(),
+            issues_tail: JsonlTail::new(beads_dir.join("issues.jsonl")),
+            interactions_tail: JsonlTail::new(beads_dir.join("interactions.jsonl")),
+            issues: Vec::new(),
+            by_id: HashMap::new(),
+            history: BTreeMap::new(),
+            history_keys: HashSet::new(),
+            next_seq: 0,
+            errors: Vec::new(),
+            unknown_fields: BTreeSet::new(),
+            issue_lines: 0,
+        };
+        index.refresh()?;
+        Ok(index)
+    }
+
+    pub fn beads_dir(&self) -> &Path {
+        &self.beads_dir
+    }
+
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Issue> {
+        self.by_id.get(id).map(|&pos| &self.issues[pos])
+    }
+
+    /// A page of history, newest first, starting after `cursor` if given
+    pub fn history_page(
+        &self,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> (Vec<ActivityEvent>, Option<String>) {
+        // Cursors are "<timestamp>#<seq>"; resume strictly after that position
+        let before = match cursor.and_then(|c| c.rsplit_once('#')) {
+            Some((ts, seq)) => {
+                let seq: u64 = seq.parse().unwrap_or(u64::MAX);
+                Bound::Excluded((ts.to_string(), seq))
+            }
+            None => Bound::Unbounded,
+        };
+
+        let mut page: Vec<ActivityEvent> = self
+            .history
+            .range((Bound::Unbounded, before))
+            .rev()
+            .take(limit + 1)
+            .map(|(_, e)| e.clone())
+            .collect();
+        let next_cursor = if page.len() > limit {
+            page.truncate(limit);
+            page.last().map(|e| e.cursor())
+        } else {
+            None
+        };
+
+        (page, next_cursor)
+    }
+
+    /// Lines that could not be parsed, across the life of the index
+    pub fn errors(&self) -> &[LineError] {
+        &self.errors
+    }
+
+    /// Top-level field names present in the file that `Issue` doesn't model
+    pub fn unknown_fields(&self) -> Vec<String> {
+        self.unknown_fields.iter().cloned().collect()
+    }
+
+    /// Pick up appended lines or a rewrite of the underlying files
+    pub fn refresh(&mut self) -> Result<BeadsDelta, String> {
+        let mut delta = BeadsDelta {
+            beads_dir: self.beads_dir.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        let chunk = self
+            .issues_tail
+            .poll()
+            .map_err(|e| format!("Failed to read issues.jsonl: {}", e))?;
+
+        // Remember the prior version of every issue this refresh touches
+        let mut touched = Touched::default();
+
+        // Lines written to a rotated-away file came before the rewrite
+        for line in &chunk.previous {
+            self.apply_issue_line(line, &mut touched, &mut delta);
+        }
+
+        if chunk.reset {
+            delta.reset = true;
+            for issue in self.issues.drain(..) {
+                if touched.seen.insert(issue.id.clone()) {
+                    touched.before.push((issue.id.clone(), Some(issue.clone())));
+                }
+                // The pre-rewrite snapshots still count as "previous"
+                touched.stale.insert(issue.id.clone(), issue);
+            }
+            self.by_id.clear();
+            self.errors.clear();
+            self.unknown_fields.clear();
+            self.issue_lines = 0;
+        }
+
+        for line in &chunk.lines {
+            self.apply_issue_line(line, &mut touched, &mut delta);
+        }
+
+        for (id, before) in touched.before {
+            match (before, self.get(&id)) {
+                (None, Some(after)) => delta.added.push(after.clone()),
+                (Some(before), Some(after)) if &before != after => {
+                    delta.updated.push(after.clone())
+                }
+                (Some(_), None) => delta.removed.push(id),
+                _ => {}
+            }
+        }
+
+        let chunk = self
+            .interactions_tail
+            .poll()
+            .map_err(|e| format!("Failed to read interactions.jsonl: {}", e))?;
+        for line in chunk.previous.iter().chain(chunk.lines.iter()) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Interactions are history, so a rewrite only adds what we haven't seen
+            if let Some(event) = serde_json::from_str::<serde_json::Value>(line)
+                .ok()
+                .as_ref()
+                .and_then(interaction_event)
+            {
+                self.record(event, &mut delta);
+            }
+        }
+
+        Ok(delta)
+    }
+
+    /// Index one issues.jsonl line, recording the transitions it makes
+    fn apply_issue_line(&mut self, line: &str, touched: &mut Touched, delta: &mut BeadsDelta) {
+        self.issue_lines += 1;
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+
+        let issue = match serde_json::from_str::<Issue>(line) {
+            Ok(issue) => issue,
+            Err(e) => {
+                let error = LineError {
+                    line: self.issue_lines,
+                    message: e.to_string(),
+                    content: line.chars().take(ERROR_CONTENT_LIMIT).collect(),
+                };
+                log::warn!(
+                    "Skipping malformed line {} in {:?}: {}",
+                    error.line,
+                    self.issues_tail.path(),
+                    error.message
+                );
+                self.errors.push(error.clone());
+                delta.errors.push(error);
+                return;
+            }
+        };
+
+        let previous = match self.get(&issue.id) {
+            Some(existing) => Some(existing.clone()),
+            None => touched.stale.remove(&issue.id),
+        };
+        if touched.seen.insert(issue.id.clone()) {
+            touched.before.push((issue.id.clone(), previous.clone()));
+        }
+
+        if issue.issue_type.as_deref() != Some("event") {
+            let events = match previous {
+                Some(ref old) => transition_events(old, &issue),
+                None => initial_events(&issue),
+            };
+            for event in events {
+                self.record(event, delta);
+            }
+        }
+        self.upsert(issue);
+    }
+
+    /// Add an event to the history unless the same one is already there.
+    /// Past `HISTORY_LIMIT` the oldest event is dropped, and an event older
+    /// than all of those kept isn't added.
+    fn record(&mut self, mut event: ActivityEvent, delta: &mut BeadsDelta) {
+        if self.history.len() >= HISTORY_LIMIT
+            && self
+                .history
+                .keys()
+                .next()
+                .is_some_and(|(oldest, _)| event.timestamp < *oldest)
+        {
+            return;
+        }
+        if !self.history_keys.insert(history_key(&event)) {
+            return;
+        }
+        event.seq = self.next_seq;
+        self.next_seq += 1;
+        self.history
+            .insert((event.timestamp.clone(), event.seq), event.clone());
+        delta.activity.push(event);
+
+        if self.history.len() > HISTORY_LIMIT {
+            if let Some((_, oldest)) = self.history.pop_first() {
+                self.history_keys.remove(&history_key(&oldest));
+            }
+        }
+    }
+
+    /// Insert or replace an issue; later lines for the same ID win
+    fn upsert(&mut self, issue: Issue) {
+        self.unknown_fields.extend(issue.extra.keys().cloned());
+        match self.by_id.get(&issue.id) {
+            Some(&pos) => self.issues[pos] = issue,
+            None => {
+                self.by_id.insert(issue.id.clone(), self.issues.len());
+                self.issues.push(issue);
+            }
+        }
+    }
+}
+
+/// Resolve a `.beads` directory, following its `redirect` file if present
+pub fn resolve_redirect(beads_dir: &Path) -> PathBuf {
+    let redirect_file = beads_dir.join("redirect");
+    if let Ok(redirect_path) = std::fs::read_to_string(&redirect_file) {
+        let redirect_path = redirect_path.trim();
+        // Handle relative redirects
+        let target = if redirect_path.starts_with('/') {
+            PathBuf::from(redirect_path)
+        } else {
+            beads_dir.join(redirect_path)
+        };
+        if target.exists() {
+            return target;
+        }
+    }
+    beads_dir.to_path_buf()
+}
+
+/// Find the beads directory, following redirect if present
+pub fn find_beads_dir() -> Option<PathBuf> {
+    // Try current directory first
+    let cwd = std::env::current_dir().ok()?;
+
+    // Look for .beads in current dir or parent dirs
+    let mut dir = cwd.as_path();
+    loop {
+        let beads_dir = dir.join(".beads");
+        if beads_dir.exists() {
+            return Some(resolve_redirect(&beads_dir));
+        }
+
+        // Move up to parent
+        match dir.parent() {
+            Some(parent) => dir = parent,
+            None => break,
+        }
+    }
+
+    // Try ~/gt/.beads as fallback
+    if let Some(home) = dirs::home_dir() {
+        let gt_beads = home.join("gt").join(".beads");
+        if gt_beads.exists() {
+            return Some(gt_beads);
+        }
+    }
+
+    None
+}
+
+/// Use an explicit beads directory if given, otherwise discover one
+pub fn resolve_beads_dir(beads_dir: Option<String>) -> Result<PathBuf, String> {
+    match beads_dir {
+        Some(dir) => Ok(resolve_redirect(Path::new(&dir))),
+        None => find_beads_dir().ok_or_else(|| "Could not find .beads directory".to_string()),
+    }
+}
+
+/// Filter for bead queries. All set fields must match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueQuery {
+    pub status: Option<String>,
+    pub assignee: Option<String>,
+    pub rig: Option<String>,
+    pub issue_type: Option<String>,
+    pub label: Option<String>,
+}
+
+impl IssueQuery {
+    pub fn matches(&self, issue: &Issue) -> bool {
+        if let Some(ref status) = self.status {
+            if &issue.status != status {
+                return false;
+            }
+        }
+        if let Some(ref assignee) = self.assignee {
+            if issue.assignee.as_ref() != Some(assignee) {
+                return false;
+            }
+        }
+        if let Some(ref rig) = self.rig {
+            if issue.rig().as_ref() != Some(rig) {
+                return false;
+            }
+        }
+        if let Some(ref issue_type) = self.issue_type {
+            if issue.issue_type.as_ref() != Some(issue_type) {
+                return false;
+            }
+        }
+        if let Some(ref label) = self.label {
+            if !issue.labels.contains(label) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Incoming and outgoing dependency edges for one bead
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IssueDependencies {
+    pub issue_id: String,
+    /// Edges from this bead to the beads it depends on
+    pub depends_on: Vec<Dependency>,
+    /// Edges from other beads that depend on this one
+    pub dependents: Vec<Dependency>,
+}
+
+/// Collect both directions of dependency edges for `issue_id`
+pub fn dependencies_for(issues: &[Issue], issue_id: &str) -> IssueDependencies {
+    let mut depends_on = Vec::new();
+    let mut dependents = Vec::new();
+
+    for issue in issues {
+        for dep in &issue.dependencies {
+            if dep.issue_id == issue_id {
+                depends_on.push(dep.clone());
+            } else if dep.depends_on_id == issue_id {
+                dependents.push(dep.clone());
+            }
+        }
+    }
+
+    IssueDependencies {
+        issue_id: issue_id.to_string(),
+        depends_on,
+        dependents,
+    }
+}
+
+/// Beads indexes shared across commands, one per beads directory
+#[derive(Default)]
+pub struct BeadsStoreState {
+    indexes: Arc<Mutex<HashMap<PathBuf, BeadsIndex>>>,
+    watchers: Mutex<HashMap<PathBuf, RecommendedWatcher>>,
+}
+
+impl BeadsStoreState {
+    /// Run `f` against the index for `beads_dir`, loading and watching it on first use.
+    /// Existing indexes are refreshed first so a missed file event never leaves
+    /// a query reading stale data.
+    pub fn with_index<R>(
+        &self,
+        app: &AppHandle,
+        beads_dir: &Path,
+        f: impl FnOnce(&BeadsIndex) -> R,
+    ) -> Result<R, String> {
+        let mut indexes = self.indexes.lock().map_err(|e| e.to_string())?;
+
+        match indexes.get_mut(beads_dir) {
+            Some(index) => {
+                let delta = index.refresh()?;
+                emit_delta(app, index, &delta);
+            }
+            None => {
+                let index = BeadsIndex::open(beads_dir)?;
+                log::info!(
+                    "Indexed {} beads from {:?}",
+                    index.issues().len(),
+                    beads_dir
+                );
+                indexes.insert(beads_dir.to_path_buf(), index);
+                self.watch(app, beads_dir);
+            }
+        }
+
+        let index = indexes
+            .get(beads_dir)
+            .ok_or_else(|| "Beads index missing".to_string())?;
+        Ok(f(index))
+    }
+
+    /// Watch the beads directory (not the files, since bd replaces them on export)
+    fn watch(&self, app: &AppHandle, beads_dir: &Path) {
+        let indexes = Arc::clone(&self.indexes);
+        let app_clone = app.clone();
+        let dir = beads_dir.to_path_buf();
+
+        let watcher = RecommendedWatcher::new(
+            move |res: Result<notify::Event, notify::Error>| {
+                let Ok(event) = res else { return };
+                let relevant = event.paths.iter().any(|p| {
+                    matches!(
+                        p.file_name().and_then(|n| n.to_str()),
+                        Some("issues.jsonl") | Some("interactions.jsonl")
+                    )
+                });
+                if !relevant {
+                    return;
+                }
+
+                let Ok(mut indexes) = indexes.lock() else {
+                    return;
+                };
+                if let Some(index) = indexes.get_mut(&dir) {
+                    match index.refresh() {
+                        Ok(delta) => emit_delta(&app_clone, index, &delta),
+                        Err(e) => log::warn!("Failed to refresh beads index: {}", e),
+                    }
+                }
+            },
+            Config::default().with_poll_interval(Duration::from_secs(1)),
+        )
+        .and_then(|mut watcher| {
+            watcher
+                .watch(beads_dir, RecursiveMode::NonRecursive)
+                .map(|_| watcher)
+        });
+
+        match watcher {
+            Ok(watcher) => {
+                if let Ok(mut watchers) = self.watchers.lock() {
+                    watchers.insert(beads_dir.to_path_buf(), watcher);
+                }
+            }
+            Err(e) => log::warn!(
+                "Failed to watch {:?}, falling back to refresh on query: {}",
+                beads_dir,
+                e
+            ),
+        }
+    }
+}
+
+fn emit_delta(app: &AppHandle, index: &BeadsIndex, delta: &BeadsDelta) {
+    if !delta.is_empty() {
+        let _ = app.emit("beads-changed", delta);
+        // Convoys are summarized against the active town's beads; any other
+        // index (a rig's own, another town's) doesn't hold their members
+        if towns::beads_dir(app, None, None).is_ok_and(|dir| dir == index.beads_dir()) {
+            convoy::beads_changed(app, index, delta);
+        }
+        refinery::beads_changed(app, delta);
+    }
+}
+
+/// Result of a bead query, with any parse problems found along the way
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeadsQueryResult {
+    pub issues: Vec<Issue>,
+    pub errors: Vec<LineError>,
+    pub unknown_fields: Vec<String>,
+}
+
+/// Query the beads of `town` (default the active town) by status, assignee,
+/// rig, type and label
+#[tauri::command]
+pub async fn query_beads(
+    app: AppHandle,
+    state: State<'_, BeadsStoreState>,
+    query: Option<IssueQuery>,
+    beads_dir: Option<String>,
+    town: Option<String>,
+) -> Result<BeadsQueryResult, String> {
+    let beads_dir = towns::beads_dir(&app, town.as_deref(), beads_dir)?;
+    let query = query.unwrap_or_default();
+
+    state.with_index(&app, &beads_dir, |index| BeadsQueryResult {
+        issues: index
+            .issues()
+            .iter()
+            .filter(|i| query.matches(i))
+            .cloned()
+            .collect(),
+        errors: index.errors().to_vec(),
+        unknown_fields: index.unknown_fields(),
+    })
+}
+
+/// Get a single bead of `town` (default the active town) by ID
+#[tauri::command]
+pub async fn get_bead(
+    app: AppHandle,
+    state: State<'_, BeadsStoreState>,
+    id: String,
+    beads_dir: Option<String>,
+    town: Option<String>,
+) -> Result<Issue, String> {
+    let beads_dir = towns::beads_dir(&app, town.as_deref(), beads_dir)?;
+    state
+        .with_index(&app, &beads_dir, |index| index.get(&id).cloned())?
+        .ok_or_else(|| format!("Bead not found: {}", id))
+}
+
+/// Get the dependency edges into and out of a bead of `town` (default the
+/// active town)
+#[tauri::command]
+pub async fn get_bead_dependencies(
+    app: AppHandle,
+    state: State<'_, BeadsStoreState>,
+    id: String,
+    beads_dir: Option<String>,
+    town: Option<String>,
+) -> Result<IssueDependencies, String> {
+    let beads_dir = towns::beads_dir(&app, town.as_deref(), beads_dir)?;
+    state
+        .with_index(&app, &beads_dir, |index| {
+            index
+                .get(&id)
+                .map(|_| dependencies_for(index.issues(), &id))
+        })?
+        .ok_or_else(|| format!("Bead not found: {}", id))
+}
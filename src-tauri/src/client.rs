@@ -0,0 +1,366 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::beads::Issue;
+use crate::gastown::Molecule;
+use crate::runner::{self, ProcessOutput};
+
+/// Why a gt or bd call failed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CliError {
+    /// The program couldn't be started, or timed out or was cancelled
+    Run { program: String, message: String },
+    /// The program ran and exited non-zero
+    Failed {
+        program: String,
+        args: Vec<String>,
+        exit_code: i32,
+        stderr: String,
+    },
+    /// The program's `--json` output wasn't what we expected
+    Parse { program: String, message: String },
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Run { program, message } => {
+                write!(f, "Failed to run {}: {}", program, message)
+            }
+            CliError::Failed {
+                program,
+                args,
+                exit_code,
+                stderr,
+            } => write!(
+                f,
+                "{} {} exited with {}: {}",
+                program,
+                args.join(" "),
+                exit_code,
+                stderr.trim()
+            ),
+            CliError::Parse { program, message } => {
+                write!(f, "Failed to parse {} output: {}", program, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<CliError> for String {
+    fn from(e: CliError) -> Self {
+        e.to_string()
+    }
+}
+
+/// A convoy as reported by `gt convoy list --json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Convoy {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    /// Percent complete, 0-100
+    pub progress: f64,
+    pub beads: Vec<String>,
+    pub polecats: Vec<String>,
+    pub active_polecats: u32,
+    pub steps_complete: u32,
+    pub steps_total: u32,
+    pub created: String,
+    pub eta: Option<String>,
+}
+
+/// A polecat as reported by `gt polecat list --json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Polecat {
+    pub name: String,
+    pub rig: String,
+    pub status: String,
+    /// Bead the polecat is working on
+    #[serde(alias = "task")]
+    pub current_work: Option<String>,
+    pub last_activity: Option<String>,
+}
+
+/// A merge request waiting in a rig's refinery, as reported by
+/// `gt refinery queue --json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MergeRequest {
+    /// The merge request's bead ID
+    pub id: String,
+    pub rig: String,
+    pub branch: String,
+    pub target: Option<String>,
+    /// Polecat that submitted the branch
+    #[serde(alias = "worker", alias = "polecat")]
+    pub author: Option<String>,
+    /// Bead the branch implements
+    pub source_issue: Option<String>,
+    /// Place in the queue, 0 being next to merge
+    pub position: u32,
+    /// Bead priority; the refinery merges P0 first
+    pub priority: Option<i64>,
+    pub status: String,
+    /// Whether the branch conflicts with its target; unknown until checked
+    #[serde(alias = "conflicts")]
+    pub has_conflicts: Option<bool>,
+    /// e.g. `pending`, `passed`, `failed`
+    #[serde(alias = "ci")]
+    pub ci_status: Option<String>,
+    pub created: String,
+}
+
+/// Typed access to the `gt` CLI
+pub trait GtClient: Send + Sync {
+    fn convoy_list(&self) -> impl Future<Output = Result<Vec<Convoy>, CliError>> + Send;
+
+    /// Polecats in one rig, or in every rig
+    fn polecat_list(
+        &self,
+        rig: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<Polecat>, CliError>> + Send;
+
+    /// Assign a bead to a rig or agent
+    fn sling(
+        &self,
+        bead_id: &str,
+        target: &str,
+    ) -> impl Future<Output = Result<(), CliError>> + Send;
+
+    /// Send a message to an agent's session
+    fn nudge(
+        &self,
+        target: &str,
+        message: &str,
+    ) -> impl Future<Output = Result<(), CliError>> + Send;
+
+    fn mol_progress(
+        &self,
+        issue_id: &str,
+    ) -> impl Future<Output = Result<Molecule, CliError>> + Send;
+
+    /// Merge queue of one rig, or of every rig, in merge order
+    fn refinery_queue(
+        &self,
+        rig: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<MergeRequest>, CliError>> + Send;
+}
+
+/// Typed access to the `bd` CLI
+pub trait BdClient: Send + Sync {
+    /// Open issues with no open blockers
+    fn ready(&self) -> impl Future<Output = Result<Vec<Issue>, CliError>> + Send;
+
+    /// Open issues waiting on a blocker
+    fn blocked(&self) -> impl Future<Output = Result<Vec<Issue>, CliError>> + Send;
+
+    /// Issues filtered by status and type
+    fn list(
+        &self,
+        status: Option<&str>,
+        issue_type: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<Issue>, CliError>> + Send;
+}
+
+/// Runs one CLI program with a timeout
+#[derive(Debug, Clone)]
+pub struct Cli {
+    program: String,
+    timeout: Duration,
+    town: Option<PathBuf>,
+}
+
+impl Cli {
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            timeout: runner::DEFAULT_TIMEOUT,
+            town: None,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run in a town's root with `GT_ROOT` set to it, rather than wherever
+    /// the app was started
+    pub fn in_town(mut self, town: Option<PathBuf>) -> Self {
+        self.town = town;
+        self
+    }
+
+    /// Run with `args`, failing on a non-zero exit
+    pub async fn run(&self, args: &[&str]) -> Result<ProcessOutput, CliError> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let town = self.town.as_deref();
+        let output = runner::run(&self.program, &args, town, self.timeout, None, |_, _| {})
+            .await
+            .map_err(|message| CliError::Run {
+                program: self.program.clone(),
+                message,
+            })?;
+        if !output.success {
+            return Err(CliError::Failed {
+                program: self.program.clone(),
+                args,
+                exit_code: output.exit_code,
+                stderr: output.stderr,
+            });
+        }
+        Ok(output)
+    }
+
+    /// Run with `args` and parse stdout as JSON
+    pub async fn json<T: DeserializeOwned>(&self, args: &[&str]) -> Result<T, CliError> {
+        let output = self.run(args).await?;
+        serde_json::from_str(&output.stdout).map_err(|e| CliError::Parse {
+            program: self.program.clone(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Like `json`, but a list command that prints nothing has no items
+    async fn json_list<T: DeserializeOwned>(&self, args: &[&str]) -> Result<Vec<T>, CliError> {
+        let output = self.run(args).await?;
+        if output.stdout.trim().is_empty() {
+            return Ok(vec![]);
+        }
+        serde_json::from_str(&output.stdout).map_err(|e| CliError::Parse {
+            program: self.program.clone(),
+            message: e.to_string(),
+        })
+    }
+}
+
+/// `gt convoy list --json` prints either a bare list or `{"convoys": [...]}`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ConvoyListOutput {
+    List(Vec<Convoy>),
+    Wrapped { convoys: Vec<Convoy> },
+}
+
+/// The `gt` binary on PATH
+#[derive(Debug, Clone)]
+pub struct GtCli {
+    cli: Cli,
+}
+
+impl GtCli {
+    pub fn new(cli: Cli) -> Self {
+        Self { cli }
+    }
+}
+
+impl Default for GtCli {
+    fn default() -> Self {
+        Self::new(Cli::new("gt"))
+    }
+}
+
+impl GtClient for GtCli {
+    async fn convoy_list(&self) -> Result<Vec<Convoy>, CliError> {
+        let output = self.cli.run(&["convoy", "list", "--json"]).await?;
+        if output.stdout.trim().is_empty() {
+            return Ok(vec![]);
+        }
+        match serde_json::from_str(&output.stdout) {
+            Ok(ConvoyListOutput::List(convoys)) | Ok(ConvoyListOutput::Wrapped { convoys }) => {
+                Ok(convoys)
+            }
+            Err(e) => Err(CliError::Parse {
+                program: self.cli.program.clone(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    async fn polecat_list(&self, rig: Option<&str>) -> Result<Vec<Polecat>, CliError> {
+        let mut args = vec!["polecat", "list"];
+        args.extend(rig);
+        args.push("--json");
+        self.cli.json_list(&args).await
+    }
+
+    async fn sling(&self, bead_id: &str, target: &str) -> Result<(), CliError> {
+        // `--` so a bead ID starting with `-` isn't taken for a flag
+        self.cli
+            .run(&["sling", "--", bead_id, target])
+            .await
+            .map(|_| ())
+    }
+
+    async fn nudge(&self, target: &str, message: &str) -> Result<(), CliError> {
+        // `--` so a message starting with `-` isn't taken for a flag
+        self.cli
+            .run(&["nudge", target, "--", message])
+            .await
+            .map(|_| ())
+    }
+
+    async fn mol_progress(&self, issue_id: &str) -> Result<Molecule, CliError> {
+        self.cli
+            .json(&["mol", "progress", issue_id, "--json"])
+            .await
+    }
+
+    async fn refinery_queue(&self, rig: Option<&str>) -> Result<Vec<MergeRequest>, CliError> {
+        let mut args = vec!["refinery", "queue"];
+        args.extend(rig);
+        args.push("--json");
+        self.cli.json_list(&args).await
+    }
+}
+
+/// The `bd` binary on PATH
+#[derive(Debug, Clone)]
+pub struct BdCli {
+    cli: Cli,
+}
+
+impl BdCli {
+    pub fn new(cli: Cli) -> Self {
+        Self { cli }
+    }
+}
+
+impl Default for BdCli {
+    fn default() -> Self {
+        Self::new(Cli::new("bd"))
+    }
+}
+
+impl BdClient for BdCli {
+    async fn ready(&self) -> Result<Vec<Issue>, CliError> {
+        self.cli.json_list(&["ready", "--json"]).await
+    }
+
+    async fn blocked(&self) -> Result<Vec<Issue>, CliError> {
+        self.cli.json_list(&["blocked", "--json"]).await
+    }
+
+    async fn list(
+        &self,
+        status: Option<&str>,
+        issue_type: Option<&str>,
+    ) -> Result<Vec<Issue>, CliError> {
+        let status = status.map(|s| format!("--status={}", s));
+        let issue_type = issue_type.map(|t| format!("--type={}", t));
+        let mut args = vec!["list", "--json"];
+        args.extend(status.as_deref());
+        args.extend(issue_type.as_deref());
+        self.cli.json_list(&args).await
+    }
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::digest::DigestConfig;
+use crate::events::GasTownEvent;
+use crate::voice::AgentPersona;
+
+/// File in the app config dir commentary settings are saved to
+const COMMENTARY_FILE: &str = "commentary.json";
+
+/// Built-in commentary: (event type, persona, template)
+const BUILTIN_TEMPLATES: &[(&str, AgentPersona, &str)] = &[
+    (
+        "mail",
+        AgentPersona::Default,
+        "Mail from {actor} to {to|someone}: {subject|message}",
+    ),
+    (
+        "session_start",
+        AgentPersona::Default,
+        "{actor} has joined the party.",
+    ),
+    (
+        "session_end",
+        AgentPersona::Default,
+        "{actor} has left. Hopefully they finished something.",
+    ),
+    (
+        "complete",
+        AgentPersona::Default,
+        "{actor} completed {task|something}. Miracles do happen.",
+    ),
+    (
+        "complete",
+        AgentPersona::Polecat,
+        "{name} finished {task|the thing}! It's DONE! Mostly!",
+    ),
+    (
+        "stuck",
+        AgentPersona::Default,
+        "Heads up: {actor} appears to be stuck. Might need a nudge.",
+    ),
+    (
+        "stuck",
+        AgentPersona::Witness,
+        "{actor} hasn't moved. I'm watching. Someone should check on that.",
+    ),
+    (
+        "error",
+        AgentPersona::Default,
+        "Error from {actor}: {message|unknown error}",
+    ),
+    (
+        "nudge",
+        AgentPersona::Default,
+        "{actor} nudged {target|someone}.",
+    ),
+    (
+        "merge",
+        AgentPersona::Default,
+        "Work from {actor} has been merged. Progress!",
+    ),
+    (
+        "merge",
+        AgentPersona::Refinery,
+        "Merged {branch|another one}. Clean. Next.",
+    ),
+    (
+        "convoy_start",
+        AgentPersona::Default,
+        "Convoy {name|convoy} is rolling out.",
+    ),
+    (
+        "convoy_start",
+        AgentPersona::Mayor,
+        "Convoy {name|convoy} is underway, as scheduled.",
+    ),
+    (
+        "convoy_complete",
+        AgentPersona::Default,
+        "Convoy {name|convoy} has reached its destination.",
+    ),
+    (
+        "escalation",
+        AgentPersona::Deacon,
+        "Something has been escalated to {to|the mayor}... it begins.",
+    ),
+];
+
+/// Templates keyed by event type, then persona
+pub type Templates = HashMap<String, HashMap<AgentPersona, String>>;
+
+/// Spoken summaries of batches of events, produced by the voice server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummaryConfig {
+    pub enabled: bool,
+    /// Summarize once this many events have been collected
+    pub batch_size: usize,
+    /// Summarize a partial batch once its first event is this old
+    pub max_wait_secs: u64,
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            batch_size: 5,
+            max_wait_secs: 30,
+        }
+    }
+}
+
+/// Commentary settings saved in the app config
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommentaryConfig {
+    /// Narrate every event as this persona. When unset, each event is
+    /// narrated by the persona of its actor's role.
+    pub persona: Option<AgentPersona>,
+    /// Templates overriding the built-in ones. `{actor}`, `{name}`, `{rig}`,
+    /// `{source}`, `{type}` and payload fields like `{subject}` are filled
+    /// in; `{field|fallback}` gives text for a missing field.
+    pub templates: Templates,
+    pub summaries: SummaryConfig,
+    /// Grouping and rate limiting of spoken announcements
+    pub digest: DigestConfig,
+}
+
+impl CommentaryConfig {
+    /// Persona the event is narrated in
+    pub fn persona_for(&self, event: &GasTownEvent) -> AgentPersona {
+        self.persona
+            .clone()
+            .unwrap_or_else(|| persona_for_actor(&event.actor))
+    }
+
+    /// Commentary for an event: the template for its type in the narrating
+    /// persona, else the type's default template. `None` if neither exists.
+    pub fn render(&self, event: &GasTownEvent) -> Option<String> {
+        let persona = self.persona_for(event);
+        let event_type = event.kind.type_name();
+        let template = self
+            .template(event_type, &persona)
+            .or_else(|| self.template(event_type, &AgentPersona::Default))?;
+        Some(render_template(template, event))
+    }
+
+    /// A configured template, falling back to the built-in one
+    fn template(&self, event_type: &str, persona: &AgentPersona) -> Option<&str> {
+        if let Some(template) = self
+            .templates
+            .get(event_type)
+            .and_then(|by_persona| by_persona.get(persona))
+        {
+            return Some(template);
+        }
+        BUILTIN_TEMPLATES
+            .iter()
+            .find(|(t, p, _)| *t == event_type && p == persona)
+            .map(|(_, _, template)| *template)
+    }
+}
+
+/// Persona for an actor's Gas Town role, e.g. `gastown/refinery` is the Refinery
+pub fn persona_for_actor(actor: &str) -> AgentPersona {
+    let segments: Vec<&str> = actor.split('/').collect();
+    match segments.as_slice() {
+        [.., "mayor"] => AgentPersona::Mayor,
+        [.., "deacon"] => AgentPersona::Deacon,
+        [.., "witness"] => AgentPersona::Witness,
+        [.., "refinery"] => AgentPersona::Refinery,
+        [.., "polecats", _] => AgentPersona::Polecat,
+        [.., "crew", _] => AgentPersona::Crew,
+        _ => AgentPersona::Default,
+    }
+}
+
+/// Fill in a template's `{field}` and `{field|fallback}` placeholders
+fn render_template(template: &str, event: &GasTownEvent) -> String {
+    let payload = serde_json::to_value(&event.kind)
+        .ok()
+        .and_then(|kind| kind.get("payload").cloned())
+        .unwrap_or_default();
+
+    let value = |key: &str| -> Option<String> {
+        match key {
+            "actor" => Some(event.actor.clone()),
+            "name" => event.actor.rsplit('/').next().map(|s| s.to_string()),
+            "rig" => event.rig().map(|s| s.to_string()),
+            "source" => Some(event.source.clone()),
+            "type" => Some(event.kind.type_name().to_string()),
+            _ => match payload.get(key)? {
+                serde_json::Value::String(s) => Some(s.clone()),
+                serde_json::Value::Null => None,
+                other => Some(other.to_string()),
+            },
+        }
+    };
+
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let placeholder = &rest[start + 1..start + len];
+        let (key, fallback) = match placeholder.split_once('|') {
+            Some((key, fallback)) => (key, fallback),
+            None => (placeholder, ""),
+        };
+        out.push_str(&value(key.trim()).unwrap_or_else(|| fallback.to_string()));
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config directory: {}", e))?;
+    Ok(dir.join(COMMENTARY_FILE))
+}
+
+/// Load saved commentary settings; missing or unreadable config means defaults
+pub fn load(app: &AppHandle) -> CommentaryConfig {
+    let Ok(path) = config_path(app) else {
+        return CommentaryConfig::default();
+    };
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid commentary config in {:?}: {}", path, e);
+            CommentaryConfig::default()
+        }),
+        Err(_) => CommentaryConfig::default(),
+    }
+}
+
+/// Save commentary settings to the app config dir
+pub fn save(app: &AppHandle, config: &CommentaryConfig) -> Result<(), String> {
+    let path = config_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize commentary config: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save commentary config: {}", e))
+}
@@ -0,0 +1,363 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::beads::{BeadsDelta, BeadsIndex, BeadsStoreState, Issue};
+use crate::client::{Convoy, GtClient};
+use crate::event_store::{format_timestamp, parse_timestamp};
+use crate::towns;
+
+/// Event pushed with a `ConvoyDetail` whenever a convoy or one of its beads changes
+pub const CONVOY_CHANNEL: &str = "convoy-updated";
+
+/// Recently closed beads used to estimate how long a bead takes
+const CYCLE_TIME_SAMPLE: usize = 50;
+
+/// A convoy member bead and where it stands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvoyBead {
+    pub id: String,
+    pub title: String,
+    /// Bead status, or `unknown` if it isn't in the beads index
+    pub status: String,
+    pub assignee: Option<String>,
+    pub closed_at: Option<String>,
+}
+
+/// A convoy with its member beads, progress and estimated completion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvoyDetail {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    pub created: String,
+    pub beads: Vec<ConvoyBead>,
+    /// Polecats gt lists for the convoy plus assignees of its open beads
+    pub polecats: Vec<String>,
+    pub active_polecats: u32,
+    pub beads_closed: usize,
+    pub beads_total: usize,
+    /// Percent complete, 0-100
+    pub progress: f64,
+    /// RFC 3339 estimate of when the last bead closes; none once the convoy
+    /// is done or when there is no close history to go on
+    pub eta: Option<String>,
+}
+
+/// Convoys from the last `gt convoy list`, so bead changes can be traced
+/// back to the convoys they belong to
+#[derive(Default)]
+pub struct ConvoyState {
+    convoys: Mutex<Vec<Convoy>>,
+}
+
+impl ConvoyState {
+    /// Replace the known convoys, returning those that are new or changed
+    fn remember(&self, convoys: &[Convoy]) -> Vec<Convoy> {
+        let Ok(mut known) = self.convoys.lock() else {
+            return vec![];
+        };
+        let changed = convoys
+            .iter()
+            .filter(|c| !known.contains(c))
+            .cloned()
+            .collect();
+        *known = convoys.to_vec();
+        changed
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+/// Join a convoy from gt with its member beads from the index
+pub fn summarize(convoy: &Convoy, index: Option<&BeadsIndex>, now_ms: i64) -> ConvoyDetail {
+    let members: Vec<Option<&Issue>> = convoy
+        .beads
+        .iter()
+        .map(|id| index.and_then(|index| index.get(id)))
+        .collect();
+
+    let beads: Vec<ConvoyBead> = convoy
+        .beads
+        .iter()
+        .zip(&members)
+        .map(|(id, issue)| match issue {
+            Some(issue) => ConvoyBead {
+                id: id.clone(),
+                title: issue.title.clone(),
+                status: issue.status.clone(),
+                assignee: issue.assignee.clone(),
+                closed_at: issue.closed_at.clone(),
+            },
+            None => ConvoyBead {
+                id: id.clone(),
+                title: String::new(),
+                status: "unknown".to_string(),
+                assignee: None,
+                closed_at: None,
+            },
+        })
+        .collect();
+
+    let mut polecats: BTreeSet<String> = convoy.polecats.iter().cloned().collect();
+    polecats.extend(
+        beads
+            .iter()
+            .filter(|b| b.status != "closed")
+            .filter_map(|b| b.assignee.clone()),
+    );
+    let working: HashSet<&str> = beads
+        .iter()
+        .filter(|b| matches!(b.status.as_str(), "in_progress" | "hooked"))
+        .filter_map(|b| b.assignee.as_deref())
+        .collect();
+    let active_polecats = if working.is_empty() {
+        convoy.active_polecats
+    } else {
+        working.len() as u32
+    };
+
+    let beads_total = beads.len();
+    let beads_closed = beads.iter().filter(|b| b.status == "closed").count();
+    let known = members.iter().any(|m| m.is_some());
+    let progress = if known && beads_total > 0 {
+        beads_closed as f64 * 100.0 / beads_total as f64
+    } else {
+        convoy.progress
+    };
+    let status = if known && beads_total > 0 && beads_closed == beads_total {
+        "completed".to_string()
+    } else {
+        convoy.status.clone()
+    };
+
+    let eta = if known {
+        estimate_eta(convoy, &members, index, active_polecats, now_ms).map(format_timestamp)
+    } else {
+        convoy.eta.clone()
+    };
+
+    ConvoyDetail {
+        id: convoy.id.clone(),
+        name: convoy.name.clone(),
+        status,
+        created: convoy.created.clone(),
+        beads,
+        polecats: polecats.into_iter().collect(),
+        active_polecats,
+        beads_closed,
+        beads_total,
+        progress,
+        eta,
+    }
+}
+
+/// When the remaining beads should all be closed. Once members have closed,
+/// the convoy's own close rate is extrapolated; before that, the median
+/// create-to-close time of recently closed beads is spread across the
+/// polecats working on it.
+fn estimate_eta(
+    convoy: &Convoy,
+    members: &[Option<&Issue>],
+    index: Option<&BeadsIndex>,
+    active_polecats: u32,
+    now_ms: i64,
+) -> Option<i64> {
+    let open = members
+        .iter()
+        .filter(|m| !matches!(m, Some(issue) if issue.status == "closed"))
+        .count() as i64;
+    if open == 0 {
+        return None;
+    }
+
+    let closes: Vec<i64> = members
+        .iter()
+        .flatten()
+        .filter(|issue| issue.status == "closed")
+        .filter_map(|issue| issue.closed_at.as_deref().and_then(parse_timestamp))
+        .collect();
+
+    if let Some(&last_close) = closes.iter().max() {
+        let start = parse_timestamp(&convoy.created).or_else(|| {
+            members
+                .iter()
+                .flatten()
+                .filter_map(|issue| parse_timestamp(&issue.created_at))
+                .min()
+        })?;
+        let per_bead = (last_close - start).max(1) / closes.len() as i64;
+        return Some(now_ms.max(last_close + per_bead * open));
+    }
+
+    let cycle = median_cycle_time(index?)?;
+    let waves = (open + active_polecats.max(1) as i64 - 1) / active_polecats.max(1) as i64;
+    Some(now_ms + cycle * waves)
+}
+
+/// Median create-to-close time of the most recently closed beads
+fn median_cycle_time(index: &BeadsIndex) -> Option<i64> {
+    let mut closed: Vec<(i64, i64)> = index
+        .issues()
+        .iter()
+        .filter(|issue| issue.status == "closed")
+        .filter_map(|issue| {
+            let created = parse_timestamp(&issue.created_at)?;
+            let closed = parse_timestamp(issue.closed_at.as_deref()?)?;
+            (closed > created).then_some((closed, closed - created))
+        })
+        .collect();
+    closed.sort_unstable_by_key(|&(closed_at, _)| std::cmp::Reverse(closed_at));
+    closed.truncate(CYCLE_TIME_SAMPLE);
+
+    let mut durations: Vec<i64> = closed.into_iter().map(|(_, d)| d).collect();
+    durations.sort_unstable();
+    durations.get(durations.len() / 2).copied()
+}
+
+/// Summarize convoys against the beads index, when there is one to read
+fn summarize_all(
+    app: &AppHandle,
+    beads_state: &BeadsStoreState,
+    beads_dir: Option<PathBuf>,
+    convoys: &[Convoy],
+) -> Result<Vec<ConvoyDetail>, String> {
+    let now = now_ms();
+    match beads_dir {
+        Some(dir) => beads_state.with_index(app, &dir, |index| {
+            convoys
+                .iter()
+                .map(|convoy| summarize(convoy, Some(index), now))
+                .collect()
+        }),
+        None => Ok(convoys
+            .iter()
+            .map(|convoy| summarize(convoy, None, now))
+            .collect()),
+    }
+}
+
+/// Convoys of `town`. Only the active town's are remembered, since those
+/// are the ones the beads watcher pushes updates for.
+async fn fetch_convoys(
+    app: &AppHandle,
+    state: &ConvoyState,
+    town: Option<&str>,
+) -> Result<Vec<Convoy>, String> {
+    let convoys = towns::gt(app, town)?
+        .convoy_list()
+        .await
+        .map_err(|e| format!("Failed to list convoys: {}", e))?;
+    if towns::is_active(app, town) {
+        state.remember(&convoys);
+    }
+    Ok(convoys)
+}
+
+/// List the convoys of `town` (default the active town) with member bead
+/// status, assigned polecats, progress and ETA
+#[tauri::command]
+pub async fn list_convoys(
+    app: AppHandle,
+    beads_state: State<'_, BeadsStoreState>,
+    convoy_state: State<'_, ConvoyState>,
+    beads_dir: Option<String>,
+    town: Option<String>,
+) -> Result<Vec<ConvoyDetail>, String> {
+    let convoys = fetch_convoys(&app, &convoy_state, town.as_deref()).await?;
+    let beads_dir = towns::beads_dir(&app, town.as_deref(), beads_dir).ok();
+    summarize_all(&app, &beads_state, beads_dir, &convoys)
+}
+
+/// Get one convoy of `town` (default the active town) by ID
+#[tauri::command]
+pub async fn get_convoy(
+    app: AppHandle,
+    beads_state: State<'_, BeadsStoreState>,
+    convoy_state: State<'_, ConvoyState>,
+    id: String,
+    beads_dir: Option<String>,
+    town: Option<String>,
+) -> Result<ConvoyDetail, String> {
+    let convoy = fetch_convoys(&app, &convoy_state, town.as_deref())
+        .await?
+        .into_iter()
+        .find(|convoy| convoy.id == id)
+        .ok_or_else(|| format!("Convoy not found: {}", id))?;
+    let beads_dir = towns::beads_dir(&app, town.as_deref(), beads_dir).ok();
+    summarize_all(&app, &beads_state, beads_dir, std::slice::from_ref(&convoy))?
+        .pop()
+        .ok_or_else(|| format!("Convoy not found: {}", id))
+}
+
+/// Push updates for known convoys with a member bead in `delta`. Called by
+/// the beads watcher with the active town's index it just refreshed.
+pub fn beads_changed(app: &AppHandle, index: &BeadsIndex, delta: &BeadsDelta) {
+    let Some(state) = app.try_state::<ConvoyState>() else {
+        return;
+    };
+    let Ok(convoys) = state.convoys.lock() else {
+        return;
+    };
+
+    let changed: HashSet<&str> = delta
+        .added
+        .iter()
+        .chain(&delta.updated)
+        .map(|issue| issue.id.as_str())
+        .chain(delta.removed.iter().map(String::as_str))
+        .collect();
+    let now = now_ms();
+    for convoy in convoys.iter() {
+        if delta.reset || convoy.beads.iter().any(|id| changed.contains(id.as_str())) {
+            let _ = app.emit(CONVOY_CHANNEL, summarize(convoy, Some(index), now));
+        }
+    }
+}
+
+/// Re-read the convoy list after a convoy event and push the convoys that changed
+pub fn spawn_refresh(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app.try_state::<ConvoyState>() else {
+            return;
+        };
+        let gt = match towns::gt(&app, None) {
+            Ok(gt) => gt,
+            Err(e) => {
+                log::warn!("Failed to refresh convoys: {}", e);
+                return;
+            }
+        };
+        let convoys = match gt.convoy_list().await {
+            Ok(convoys) => convoys,
+            Err(e) => {
+                log::warn!("Failed to refresh convoys: {}", e);
+                return;
+            }
+        };
+        let changed = state.remember(&convoys);
+        if changed.is_empty() {
+            return;
+        }
+
+        let beads_state = app.state::<BeadsStoreState>();
+        let beads_dir = towns::beads_dir(&app, None, None).ok();
+        match summarize_all(&app, &beads_state, beads_dir, &changed) {
+            Ok(details) => {
+                for detail in details {
+                    let _ = app.emit(CONVOY_CHANNEL, detail);
+                }
+            }
+            Err(e) => log::warn!("Failed to summarize convoys: {}", e),
+        }
+    });
+}
@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::commentary::persona_for_actor;
+use crate::events::EnrichedEvent;
+use crate::voice::AgentPersona;
+
+/// Window the per-minute announcement cap is counted over
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// How spoken announcements are grouped and limited
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestConfig {
+    /// Events arriving within this many seconds are announced together
+    pub window_secs: u64,
+    /// Most non-critical announcements per minute; later ones are held and
+    /// folded into the next announcement
+    pub max_per_minute: usize,
+    /// Event types announced immediately, bypassing the window and the cap
+    pub critical_types: Vec<String>,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 10,
+            max_per_minute: 6,
+            critical_types: vec![
+                "error".to_string(),
+                "stuck".to_string(),
+                "escalation".to_string(),
+            ],
+        }
+    }
+}
+
+impl DigestConfig {
+    pub fn is_critical(&self, event: &EnrichedEvent) -> bool {
+        let event_type = event.event.kind.type_name();
+        self.critical_types.iter().any(|t| t == event_type)
+    }
+}
+
+/// A spoken announcement covering one or more events
+#[derive(Debug, Clone, Serialize)]
+pub struct Announcement {
+    pub text: String,
+    pub event_count: usize,
+    pub critical: bool,
+}
+
+impl Announcement {
+    /// Announce a single event by its commentary
+    pub fn single(event: &EnrichedEvent, critical: bool) -> Option<Self> {
+        Some(Self {
+            text: event.commentary.clone()?,
+            event_count: 1,
+            critical,
+        })
+    }
+
+    /// Announce a batch: one event keeps its commentary, more are counted
+    /// by type, e.g. "5 merges, 2 polecats stuck."
+    pub fn digest(events: &[EnrichedEvent]) -> Option<Self> {
+        match events {
+            [] => None,
+            [event] => Self::single(event, false),
+            _ => Some(Self {
+                text: digest_text(events),
+                event_count: events.len(),
+                critical: false,
+            }),
+        }
+    }
+}
+
+/// Events waiting to be announced, and when recent announcements were made
+#[derive(Default)]
+pub struct Digest {
+    pending: Vec<EnrichedEvent>,
+    announced: VecDeque<Instant>,
+}
+
+impl Digest {
+    /// Queue an event; true if it starts a new window that needs flushing
+    pub fn queue(&mut self, event: EnrichedEvent) -> bool {
+        self.pending.push(event);
+        self.pending.len() == 1
+    }
+
+    /// Take the pending events if another announcement fits under the cap,
+    /// otherwise how long until one does
+    pub fn take(
+        &mut self,
+        now: Instant,
+        max_per_minute: usize,
+    ) -> Result<Vec<EnrichedEvent>, Duration> {
+        while self
+            .announced
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= RATE_WINDOW)
+        {
+            self.announced.pop_front();
+        }
+        if self.announced.len() >= max_per_minute.max(1) {
+            let oldest = self.announced[0];
+            return Err(RATE_WINDOW.saturating_sub(now.duration_since(oldest)));
+        }
+        self.announced.push_back(now);
+        Ok(std::mem::take(&mut self.pending))
+    }
+}
+
+/// "5 merges, 2 polecats stuck." with types in order of first appearance
+fn digest_text(events: &[EnrichedEvent]) -> String {
+    let mut groups: Vec<(&str, Vec<&EnrichedEvent>)> = Vec::new();
+    for event in events {
+        let event_type = event.event.kind.type_name();
+        match groups.iter_mut().find(|(t, _)| *t == event_type) {
+            Some((_, group)) => group.push(event),
+            None => groups.push((event_type, vec![event])),
+        }
+    }
+
+    let parts: Vec<String> = groups
+        .iter()
+        .map(|(event_type, group)| describe_group(event_type, group))
+        .collect();
+    format!("{}.", parts.join(", "))
+}
+
+/// Count phrase for a group of same-type events
+fn describe_group(event_type: &str, group: &[&EnrichedEvent]) -> String {
+    let n = group.len();
+    let plural = |one: &str, many: &str| {
+        if n == 1 {
+            one.to_string()
+        } else {
+            many.to_string()
+        }
+    };
+    let agents = if group
+        .iter()
+        .all(|e| persona_for_actor(&e.event.actor) == AgentPersona::Polecat)
+    {
+        plural("polecat", "polecats")
+    } else {
+        plural("agent", "agents")
+    };
+
+    let what = match event_type {
+        "mail" => plural("new message", "new messages"),
+        "session_start" => format!("{} joined", agents),
+        "session_end" => format!("{} left", agents),
+        "complete" => format!("{} finished", agents),
+        "assignment" => plural("assignment", "assignments"),
+        "stuck" => format!("{} stuck", agents),
+        "blocked" => plural("blocked task", "blocked tasks"),
+        "error" => plural("error", "errors"),
+        "nudge" => plural("nudge", "nudges"),
+        "merge" => plural("merge", "merges"),
+        "convoy_start" => plural("convoy rolling out", "convoys rolling out"),
+        "convoy_complete" => plural("convoy arrived", "convoys arrived"),
+        "escalation" => plural("escalation", "escalations"),
+        other => format!("{} {}", other.replace('_', " "), plural("event", "events")),
+    };
+    format!("{} {}", n, what)
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::events::GasTownEvent;
+
+/// File in the app config dir the filter rules are saved to
+const FILTERS_FILE: &str = "event_filters.json";
+
+/// What a matching rule does with an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    Allow,
+    Deny,
+}
+
+/// A user-defined event filter rule. Every condition that is set must match;
+/// an empty rule matches every event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterRule {
+    #[serde(default)]
+    pub name: String,
+    pub action: FilterAction,
+    /// Higher priority rules are checked first
+    #[serde(default)]
+    pub priority: i32,
+    /// Event types to match, e.g. `error`; empty matches any type
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    /// Glob over the actor, e.g. `*/polecats/*`
+    pub actor: Option<String>,
+    /// Glob over the event's rig
+    pub rig: Option<String>,
+    /// Glob over the event source
+    pub source: Option<String>,
+    pub visibility: Option<String>,
+}
+
+impl FilterRule {
+    fn matches(&self, event: &GasTownEvent) -> bool {
+        let type_name = event.kind.type_name();
+        if !self.event_types.is_empty() && !self.event_types.iter().any(|t| t == type_name) {
+            return false;
+        }
+        if let Some(pattern) = &self.actor {
+            if !glob_match(pattern, &event.actor) {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.rig {
+            if !event.rig().is_some_and(|rig| glob_match(pattern, rig)) {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.source {
+            if !glob_match(pattern, &event.source) {
+                return false;
+            }
+        }
+        if let Some(visibility) = &self.visibility {
+            if event.visibility.as_ref() != Some(visibility) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Saved filter rules
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventFilters {
+    #[serde(default)]
+    pub rules: Vec<FilterRule>,
+}
+
+impl EventFilters {
+    /// Decide an event by the highest priority matching rule; deny wins a tie.
+    /// `None` when no rule matches, leaving it to the verbosity level.
+    pub fn decide(&self, event: &GasTownEvent) -> Option<bool> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.matches(event))
+            .max_by_key(|rule| (rule.priority, rule.action == FilterAction::Deny))
+            .map(|rule| rule.action == FilterAction::Allow)
+    }
+}
+
+/// Match `text` against a glob where `*` matches any run of characters and
+/// `?` matches exactly one
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Last `*` seen and the text position it is currently matched up to
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        // `*` is always a wildcard, even against a literal `*` in the text
+        if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` swallow one more character
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn filters_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config directory: {}", e))?;
+    Ok(dir.join(FILTERS_FILE))
+}
+
+/// Load saved filter rules; missing or unreadable config means no rules
+pub fn load(app: &AppHandle) -> EventFilters {
+    let Ok(path) = filters_path(app) else {
+        return EventFilters::default();
+    };
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid event filters in {:?}: {}", path, e);
+            EventFilters::default()
+        }),
+        Err(_) => EventFilters::default(),
+    }
+}
+
+/// Save filter rules to the app config dir
+pub fn save(app: &AppHandle, filters: &EventFilters) -> Result<(), String> {
+    let path = filters_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(filters)
+        .map_err(|e| format!("Failed to serialize event filters: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save event filters: {}", e))
+}
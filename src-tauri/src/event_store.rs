@@ -0,0 +1,637 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File, Metadata, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::event_filters::glob_match;
+use crate::events::GasTownEvent;
+
+/// Size of one index record: timestamp (ms) and data file offset
+const INDEX_RECORD_LEN: usize = 16;
+
+/// Events returned by a history query when no limit is given
+const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Events this close to the newest stored one are checked for duplicates
+/// in memory; older ones against the stored lines sharing their timestamp
+const DEDUPE_WINDOW_MS: i64 = 60 * 60 * 1000;
+
+/// File in the store's directory remembering how far `catch_up` has read
+const CATCH_UP_FILE: &str = "catch_up.json";
+
+#[derive(Debug, Clone, Copy)]
+struct IndexEntry {
+    ts: i64,
+    offset: u64,
+}
+
+/// How far `catch_up` got through an events log
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct CatchUpPosition {
+    path: PathBuf,
+    /// Device and inode of the file, to notice it being replaced
+    file_id: Option<(u64, u64)>,
+    offset: u64,
+}
+
+/// Append-only on-disk event history: raw event lines in `events.jsonl`
+/// in the order they arrived, and a sidecar index of (timestamp, offset)
+/// records. The index is kept sorted by time in memory for range lookups,
+/// so events that arrive late still land in order.
+pub struct EventStore {
+    dir: PathBuf,
+    data_path: PathBuf,
+    data: File,
+    index_file: File,
+    index: Vec<IndexEntry>,
+    data_len: u64,
+    /// (timestamp, line hash) of the stored events within the dedupe window
+    /// of the newest one, so the same event read twice isn't stored twice
+    recent: HashSet<(i64, u64)>,
+}
+
+/// Filter for history queries; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryQuery {
+    /// RFC 3339 start of the range, inclusive
+    pub since: Option<String>,
+    /// RFC 3339 end of the range, exclusive
+    pub until: Option<String>,
+    pub event_types: Vec<String>,
+    /// Glob over the actor
+    pub actor: Option<String>,
+    /// Glob over the event's rig
+    pub rig: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    fn matches(&self, event: &GasTownEvent) -> bool {
+        let type_name = event.kind.type_name();
+        if !self.event_types.is_empty() && !self.event_types.iter().any(|t| t == type_name) {
+            return false;
+        }
+        if let Some(pattern) = &self.actor {
+            if !glob_match(pattern, &event.actor) {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.rig {
+            if !event.rig().is_some_and(|rig| glob_match(pattern, rig)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Index range of the query's time bounds
+    fn time_range(&self) -> Result<(Option<i64>, Option<i64>), String> {
+        let parse = |ts: &Option<String>| -> Result<Option<i64>, String> {
+            ts.as_deref()
+                .map(|ts| parse_timestamp(ts).ok_or(format!("Invalid timestamp: {}", ts)))
+                .transpose()
+        };
+        Ok((parse(&self.since)?, parse(&self.until)?))
+    }
+}
+
+/// Number of matching events in one time bucket
+#[derive(Debug, Clone, Serialize)]
+pub struct EventBucket {
+    /// Bucket start, RFC 3339
+    pub start: String,
+    pub count: usize,
+    pub by_type: BTreeMap<String, usize>,
+}
+
+impl EventStore {
+    /// Open (or create) the store in `dir`, repairing the index if the app
+    /// stopped between writing an event and indexing it
+    pub fn open(dir: &Path) -> Result<Self, String> {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create event history directory: {}", e))?;
+        let data_path = dir.join("events.jsonl");
+        let index_path = dir.join("events.idx");
+
+        let data = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&data_path)
+            .map_err(|e| format!("Failed to open event history: {}", e))?;
+        let data_len = complete_len(&data_path)?;
+        // Drop a line torn by a crash mid-write, so the next event starts on
+        // a line of its own
+        data.set_len(data_len)
+            .map_err(|e| format!("Failed to repair event history: {}", e))?;
+
+        let mut index = read_index(&index_path)?;
+        let indexed = index.len();
+        index.retain(|entry| entry.offset < data_len);
+        let mut rewrite = index.len() != indexed;
+
+        // Index any lines written after the last indexed one. Records are
+        // appended in data file order, so that's the highest offset.
+        let resume = match index.iter().map(|entry| entry.offset).max() {
+            Some(offset) => line_end(&data_path, offset)?,
+            None => 0,
+        };
+        let missing = index_lines(&data_path, resume)?;
+        rewrite |= !missing.is_empty();
+        index.extend(missing);
+
+        if rewrite {
+            let bytes: Vec<u8> = index.iter().flat_map(encode_entry).collect();
+            fs::write(&index_path, bytes)
+                .map_err(|e| format!("Failed to write event history index: {}", e))?;
+        }
+        index.sort_by_key(|entry| (entry.ts, entry.offset));
+
+        let index_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&index_path)
+            .map_err(|e| format!("Failed to open event history index: {}", e))?;
+
+        let mut store = Self {
+            dir: dir.to_path_buf(),
+            data_path,
+            data,
+            index_file,
+            index,
+            data_len,
+            recent: HashSet::new(),
+        };
+        store.recent = store.hash_recent_lines()?;
+        Ok(store)
+    }
+
+    /// The directory the store lives in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Store one raw line from the events log. Returns false for lines that
+    /// aren't events, and for events already stored. Events older than the
+    /// newest stored one are kept in time order.
+    pub fn ingest(&mut self, line: &str) -> Result<bool, String> {
+        let line = line.trim();
+        let Ok(event) = serde_json::from_str::<GasTownEvent>(line) else {
+            return Ok(false);
+        };
+        let Some(ts) = parse_timestamp(&event.ts) else {
+            return Ok(false);
+        };
+
+        let hash = hash_line(line);
+        if self.is_stored(ts, hash)? {
+            return Ok(false);
+        }
+
+        let entry = IndexEntry {
+            ts,
+            offset: self.data_len,
+        };
+        self.data
+            .write_all(format!("{}\n", line).as_bytes())
+            .map_err(|e| format!("Failed to write event history: {}", e))?;
+        self.data_len += line.len() as u64 + 1;
+        self.index_file
+            .write_all(&encode_entry(&entry))
+            .map_err(|e| format!("Failed to write event history index: {}", e))?;
+
+        let newest = self.newest().unwrap_or(ts);
+        let at = self.index.partition_point(|e| e.ts <= ts);
+        self.index.insert(at, entry);
+        if ts > newest {
+            let cutoff = ts - DEDUPE_WINDOW_MS;
+            self.recent.retain(|&(recent_ts, _)| recent_ts >= cutoff);
+        }
+        if ts >= self.newest().unwrap_or(ts) - DEDUPE_WINDOW_MS {
+            self.recent.insert((ts, hash));
+        }
+        Ok(true)
+    }
+
+    /// Whether the line with this timestamp and hash is already stored
+    fn is_stored(&self, ts: i64, hash: u64) -> Result<bool, String> {
+        let Some(newest) = self.newest() else {
+            return Ok(false);
+        };
+        if ts >= newest - DEDUPE_WINDOW_MS {
+            return Ok(self.recent.contains(&(ts, hash)));
+        }
+
+        // Older than the window: compare with the lines sharing its timestamp
+        let start = self.index.partition_point(|e| e.ts < ts);
+        let end = self.index.partition_point(|e| e.ts <= ts);
+        if start == end {
+            return Ok(false);
+        }
+        let mut reader = BufReader::new(self.reader()?);
+        let mut line = String::new();
+        for entry in &self.index[start..end] {
+            read_line_at(&mut reader, entry.offset, &mut line)?;
+            if hash_line(line.trim()) == hash {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn newest(&self) -> Option<i64> {
+        self.index.last().map(|e| e.ts)
+    }
+
+    /// Store every event in an events log not already stored. Reading
+    /// resumes where the last catch-up of the same file stopped; a
+    /// replaced or truncated file is read from the start.
+    pub fn catch_up(&mut self, events_path: &Path) -> Result<usize, String> {
+        let mut file =
+            File::open(events_path).map_err(|e| format!("Failed to open events file: {}", e))?;
+        let metadata = file
+            .metadata()
+            .map_err(|e| format!("Failed to open events file: {}", e))?;
+        let file_id = file_id(&metadata);
+
+        let saved = self.catch_up_position();
+        let start = match saved {
+            Some(saved)
+                if saved.path == events_path
+                    && saved.file_id == file_id
+                    && saved.offset <= metadata.len() =>
+            {
+                saved.offset
+            }
+            _ => 0,
+        };
+        file.seek(SeekFrom::Start(start))
+            .map_err(|e| format!("Failed to read events file: {}", e))?;
+
+        let mut reader = BufReader::new(file);
+        let mut offset = start;
+        let mut added = 0;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader
+                .read_line(&mut line)
+                .map_err(|e| format!("Failed to read events file: {}", e))?;
+            // Stop before a line still being written
+            if read == 0 || !line.ends_with('\n') {
+                break;
+            }
+            offset += read as u64;
+            if self.ingest(&line)? {
+                added += 1;
+            }
+        }
+
+        self.save_catch_up_position(&CatchUpPosition {
+            path: events_path.to_path_buf(),
+            file_id,
+            offset,
+        })?;
+        Ok(added)
+    }
+
+    fn catch_up_position(&self) -> Option<CatchUpPosition> {
+        let content = fs::read_to_string(self.dir.join(CATCH_UP_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save_catch_up_position(&self, position: &CatchUpPosition) -> Result<(), String> {
+        let content = serde_json::to_string(position)
+            .map_err(|e| format!("Failed to serialize catch-up position: {}", e))?;
+        fs::write(self.dir.join(CATCH_UP_FILE), content)
+            .map_err(|e| format!("Failed to save catch-up position: {}", e))
+    }
+
+    /// Matching events in the query's time range, newest first
+    pub fn query(&self, query: &HistoryQuery) -> Result<Vec<GasTownEvent>, String> {
+        let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+        let mut events = Vec::new();
+        if limit > 0 {
+            self.scan_back(query, |event| {
+                events.push(event);
+                events.len() < limit
+            })?;
+        }
+        Ok(events)
+    }
+
+    /// Visit matching events in the query's time range from newest to
+    /// oldest, until `visit` returns false
+    pub fn scan_back(
+        &self,
+        query: &HistoryQuery,
+        mut visit: impl FnMut(GasTownEvent) -> bool,
+    ) -> Result<(), String> {
+        let (start, end) = self.index_range(query)?;
+        let mut reader = BufReader::new(self.reader()?);
+        let mut line = String::new();
+        for entry in self.index[start..end].iter().rev() {
+            read_line_at(&mut reader, entry.offset, &mut line)?;
+            if let Ok(event) = serde_json::from_str::<GasTownEvent>(&line) {
+                if query.matches(&event) && !visit(event) {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Count matching events per `bucket_secs` bucket, oldest first. Only
+    /// buckets with events are returned.
+    pub fn count(
+        &self,
+        query: &HistoryQuery,
+        bucket_secs: u64,
+    ) -> Result<Vec<EventBucket>, String> {
+        let bucket_ms = (bucket_secs.max(1) * 1000) as i64;
+        let (start, end) = self.index_range(query)?;
+
+        let mut reader = BufReader::new(self.reader()?);
+        let mut buckets: BTreeMap<i64, EventBucket> = BTreeMap::new();
+        let mut line = String::new();
+        for entry in &self.index[start..end] {
+            read_line_at(&mut reader, entry.offset, &mut line)?;
+            let Ok(event) = serde_json::from_str::<GasTownEvent>(&line) else {
+                continue;
+            };
+            if !query.matches(&event) {
+                continue;
+            }
+            let bucket_start = entry.ts.div_euclid(bucket_ms) * bucket_ms;
+            let bucket = buckets.entry(bucket_start).or_insert_with(|| EventBucket {
+                start: format_timestamp(bucket_start),
+                count: 0,
+                by_type: BTreeMap::new(),
+            });
+            bucket.count += 1;
+            *bucket
+                .by_type
+                .entry(event.kind.type_name().to_string())
+                .or_default() += 1;
+        }
+        Ok(buckets.into_values().collect())
+    }
+
+    fn index_range(&self, query: &HistoryQuery) -> Result<(usize, usize), String> {
+        let (since, until) = query.time_range()?;
+        let start = since.map_or(0, |since| self.index.partition_point(|e| e.ts < since));
+        let end = until.map_or(self.index.len(), |until| {
+            self.index.partition_point(|e| e.ts < until)
+        });
+        Ok((start, end.max(start)))
+    }
+
+    fn reader(&self) -> Result<File, String> {
+        File::open(&self.data_path).map_err(|e| format!("Failed to read event history: {}", e))
+    }
+
+    /// (timestamp, hash) of the stored lines within the dedupe window
+    fn hash_recent_lines(&self) -> Result<HashSet<(i64, u64)>, String> {
+        let mut recent = HashSet::new();
+        let Some(newest) = self.newest() else {
+            return Ok(recent);
+        };
+        let cutoff = newest - DEDUPE_WINDOW_MS;
+        let mut reader = BufReader::new(self.reader()?);
+        let mut line = String::new();
+        for entry in self.index.iter().rev().take_while(|e| e.ts >= cutoff) {
+            read_line_at(&mut reader, entry.offset, &mut line)?;
+            recent.insert((entry.ts, hash_line(line.trim())));
+        }
+        Ok(recent)
+    }
+}
+
+/// Read the line starting at `offset` into `line`
+fn read_line_at(
+    reader: &mut BufReader<File>,
+    offset: u64,
+    line: &mut String,
+) -> Result<(), String> {
+    reader
+        .seek(SeekFrom::Start(offset))
+        .map_err(|e| format!("Failed to read event history: {}", e))?;
+    line.clear();
+    reader
+        .read_line(line)
+        .map_err(|e| format!("Failed to read event history: {}", e))?;
+    Ok(())
+}
+
+/// Identity of a file on disk, used to notice the events log being replaced
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+fn hash_line(line: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    line.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn encode_entry(entry: &IndexEntry) -> [u8; INDEX_RECORD_LEN] {
+    let mut bytes = [0u8; INDEX_RECORD_LEN];
+    bytes[..8].copy_from_slice(&entry.ts.to_le_bytes());
+    bytes[8..].copy_from_slice(&entry.offset.to_le_bytes());
+    bytes
+}
+
+fn read_index(path: &Path) -> Result<Vec<IndexEntry>, String> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(format!("Failed to read event history index: {}", e)),
+    };
+    // A torn final record is dropped and re-indexed from the data file
+    Ok(bytes
+        .chunks_exact(INDEX_RECORD_LEN)
+        .map(|record| IndexEntry {
+            ts: i64::from_le_bytes(record[..8].try_into().unwrap()),
+            offset: u64::from_le_bytes(record[8..].try_into().unwrap()),
+        })
+        .collect())
+}
+
+/// Length of the data file up to the end of its last complete line
+fn complete_len(data_path: &Path) -> Result<u64, String> {
+    let mut file =
+        File::open(data_path).map_err(|e| format!("Failed to read event history: {}", e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read event history: {}", e))?
+        .len();
+    let mut pos = len;
+    let mut block = vec![0u8; 4096];
+    while pos > 0 {
+        let start = pos.saturating_sub(block.len() as u64);
+        let size = (pos - start) as usize;
+        file.seek(SeekFrom::Start(start))
+            .map_err(|e| format!("Failed to read event history: {}", e))?;
+        file.read_exact(&mut block[..size])
+            .map_err(|e| format!("Failed to read event history: {}", e))?;
+        if let Some(i) = block[..size].iter().rposition(|&b| b == b'\n') {
+            return Ok(start + i as u64 + 1);
+        }
+        pos = start;
+    }
+    Ok(0)
+}
+
+/// Offset just past the line starting at `offset`
+fn line_end(data_path: &Path, offset: u64) -> Result<u64, String> {
+    let mut reader = BufReader::new(
+        File::open(data_path).map_err(|e| format!("Failed to read event history: {}", e))?,
+    );
+    reader
+        .seek(SeekFrom::Start(offset))
+        .map_err(|e| format!("Failed to read event history: {}", e))?;
+    let mut line = Vec::new();
+    let read = reader
+        .read_until(b'\n', &mut line)
+        .map_err(|e| format!("Failed to read event history: {}", e))?;
+    Ok(offset + read as u64)
+}
+
+/// Index entries for the complete lines from `offset` onwards
+fn index_lines(data_path: &Path, offset: u64) -> Result<Vec<IndexEntry>, String> {
+    let mut file =
+        File::open(data_path).map_err(|e| format!("Failed to read event history: {}", e))?;
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| format!("Failed to read event history: {}", e))?;
+    let mut rest = Vec::new();
+    file.read_to_end(&mut rest)
+        .map_err(|e| format!("Failed to read event history: {}", e))?;
+
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    while let Some(len) = rest[pos..].iter().position(|&b| b == b'\n') {
+        let line = String::from_utf8_lossy(&rest[pos..pos + len]);
+        let ts = serde_json::from_str::<GasTownEvent>(&line)
+            .ok()
+            .and_then(|event| parse_timestamp(&event.ts));
+        if let Some(ts) = ts {
+            entries.push(IndexEntry {
+                ts,
+                offset: offset + pos as u64,
+            });
+        }
+        pos += len + 1;
+    }
+    Ok(entries)
+}
+
+/// Milliseconds since the Unix epoch for an RFC 3339 timestamp such as
+/// `2026-01-02T03:04:05.678Z` or `2026-01-02T03:04:05+02:00`
+pub fn parse_timestamp(ts: &str) -> Option<i64> {
+    let ts = ts.trim();
+    let (date, time) = ts.split_once(['T', 't', ' '])?;
+
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: u32 = date_parts.next()?.parse().ok()?;
+    let day: u32 = date_parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Split off the zone: Z, a +HH:MM / -HH:MM offset, or none for UTC
+    let (clock, offset_secs) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else if let Some(sign_at) = time.rfind(['+', '-']) {
+        let (clock, zone) = time.split_at(sign_at);
+        let sign = if zone.starts_with('-') { -1 } else { 1 };
+        let (hours, minutes) = zone[1..].split_once(':').unwrap_or((&zone[1..], "00"));
+        let hours = number_in(hours, 0..=23)?;
+        let minutes = number_in(minutes, 0..=59)?;
+        (clock, sign * (hours * 3600 + minutes * 60))
+    } else {
+        (time, 0)
+    };
+
+    let (hms, fraction) = clock.split_once('.').unwrap_or((clock, ""));
+    let mut hms_parts = hms.splitn(3, ':');
+    let hour = number_in(hms_parts.next()?, 0..=23)?;
+    let minute = number_in(hms_parts.next()?, 0..=59)?;
+    let second = number_in(hms_parts.next()?, 0..=59)?;
+    let millis: i64 = if fraction.is_empty() {
+        0
+    } else if fraction.bytes().all(|b| b.is_ascii_digit()) {
+        format!("{:0<3}", &fraction[..fraction.len().min(3)])
+            .parse()
+            .ok()?
+    } else {
+        return None;
+    };
+
+    let days = days_from_civil(year, month, day);
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second - offset_secs;
+    Some(secs * 1000 + millis)
+}
+
+/// A run of ASCII digits within `range`
+fn number_in(digits: &str, range: std::ops::RangeInclusive<i64>) -> Option<i64> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok().filter(|n| range.contains(n))
+}
+
+/// RFC 3339 UTC timestamp for milliseconds since the Unix epoch
+pub fn format_timestamp(ms: i64) -> String {
+    let secs = ms.div_euclid(1000);
+    let days = secs.div_euclid(86_400);
+    let of_day = secs.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        of_day / 3600,
+        of_day % 3600 / 60,
+        of_day % 60
+    )
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Proleptic Gregorian date for days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -1,63 +1,325 @@
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
-use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tauri::{AppHandle, Emitter, State};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::commentary::{self, CommentaryConfig};
+use crate::convoy;
+use crate::refinery;
+use crate::digest::{Announcement, Digest};
+use crate::event_filters::{self, EventFilters};
+use crate::event_store::{EventBucket, EventStore, HistoryQuery};
+use crate::tail::JsonlTail;
+use crate::towns;
+use crate::voice::{self, AgentPersona, VoiceServerState};
 
 /// Gas Town event from .events.jsonl
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GasTownEvent {
     pub ts: String,
     pub source: String,
+    pub actor: String,
+    /// The `type` and `payload` fields, typed by event type
+    #[serde(flatten, deserialize_with = "deserialize_kind")]
+    pub kind: EventKind,
+    pub visibility: Option<String>,
+}
+
+/// Event type and its payload. Serializes back to the same `type` and
+/// `payload` fields it was read from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload", rename_all = "snake_case")]
+pub enum EventKind {
+    Mail(MailPayload),
+    SessionStart(SessionPayload),
+    SessionEnd(SessionPayload),
+    Complete(CompletePayload),
+    Assignment(AssignmentPayload),
+    Stuck(StuckPayload),
+    Blocked(BlockedPayload),
+    Error(ErrorPayload),
+    Nudge(NudgePayload),
+    Merge(MergePayload),
+    ConvoyStart(ConvoyPayload),
+    ConvoyComplete(ConvoyPayload),
+    Escalation(EscalationPayload),
+    /// Any other event type, or a known type whose payload didn't parse
+    #[serde(untagged)]
+    Unknown(UnknownEvent),
+}
+
+impl GasTownEvent {
+    /// Rig the event belongs to: the payload's `rig` field, or the first
+    /// segment of a rig-scoped actor like `gastown/polecats/nux`
+    pub fn rig(&self) -> Option<&str> {
+        let payload_rig = match &self.kind {
+            EventKind::SessionStart(session) | EventKind::SessionEnd(session) => {
+                session.rig.as_deref()
+            }
+            kind => kind.payload_str("rig"),
+        };
+        payload_rig.or_else(|| {
+            self.actor
+                .split_once('/')
+                .map(|(rig, _)| rig)
+                .filter(|rig| !rig.is_empty())
+        })
+    }
+}
+
+impl EventKind {
+    /// The event's `type` string
+    pub fn type_name(&self) -> &str {
+        match self {
+            EventKind::Mail(_) => "mail",
+            EventKind::SessionStart(_) => "session_start",
+            EventKind::SessionEnd(_) => "session_end",
+            EventKind::Complete(_) => "complete",
+            EventKind::Assignment(_) => "assignment",
+            EventKind::Stuck(_) => "stuck",
+            EventKind::Blocked(_) => "blocked",
+            EventKind::Error(_) => "error",
+            EventKind::Nudge(_) => "nudge",
+            EventKind::Merge(_) => "merge",
+            EventKind::ConvoyStart(_) => "convoy_start",
+            EventKind::ConvoyComplete(_) => "convoy_complete",
+            EventKind::Escalation(_) => "escalation",
+            EventKind::Unknown(unknown) => &unknown.event_type,
+        }
+    }
+
+    /// A string payload field that isn't one of the typed fields
+    fn payload_str(&self, key: &str) -> Option<&str> {
+        let extra = match self {
+            EventKind::Mail(p) => &p.extra,
+            EventKind::SessionStart(p) | EventKind::SessionEnd(p) => &p.extra,
+            EventKind::Complete(p) => &p.extra,
+            EventKind::Assignment(p) => &p.extra,
+            EventKind::Stuck(p) => &p.extra,
+            EventKind::Blocked(p) => &p.extra,
+            EventKind::Error(p) => &p.extra,
+            EventKind::Nudge(p) => &p.extra,
+            EventKind::Merge(p) => &p.extra,
+            EventKind::ConvoyStart(p) | EventKind::ConvoyComplete(p) => &p.extra,
+            EventKind::Escalation(p) => &p.extra,
+            EventKind::Unknown(unknown) => return unknown.payload.get(key)?.as_str(),
+        };
+        extra.get(key)?.as_str()
+    }
+}
+
+/// Read the event kind, treating a missing or null payload as empty so
+/// payload-less events still get their typed variant
+fn deserialize_kind<'de, D>(deserializer: D) -> Result<EventKind, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct RawKind {
+        #[serde(rename = "type")]
+        event_type: String,
+        #[serde(default)]
+        payload: serde_json::Value,
+    }
+
+    let raw = RawKind::deserialize(deserializer)?;
+    let payload = match raw.payload {
+        serde_json::Value::Null => serde_json::Value::Object(Default::default()),
+        payload => payload,
+    };
+    EventKind::deserialize(serde_json::json!({ "type": raw.event_type, "payload": payload }))
+        .map_err(serde::de::Error::custom)
+}
+
+/// Payload fields not covered by a typed struct, kept so they reach the frontend
+type ExtraFields = serde_json::Map<String, serde_json::Value>;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MailPayload {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub subject: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionPayload {
+    pub session: Option<String>,
+    pub rig: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompletePayload {
+    pub task: Option<String>,
+    pub bead: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssignmentPayload {
+    pub bead: Option<String>,
+    pub assignee: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StuckPayload {
+    pub reason: Option<String>,
+    pub bead: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlockedPayload {
+    pub bead: Option<String>,
+    pub blocked_by: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ErrorPayload {
+    pub message: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NudgePayload {
+    pub target: Option<String>,
+    pub message: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MergePayload {
+    pub branch: Option<String>,
+    pub bead: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConvoyPayload {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EscalationPayload {
+    pub to: Option<String>,
+    pub reason: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// An event type this version doesn't model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnknownEvent {
     #[serde(rename = "type")]
     pub event_type: String,
-    pub actor: String,
+    #[serde(default)]
     pub payload: serde_json::Value,
-    pub visibility: Option<String>,
 }
 
 /// Verbosity level for event filtering
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Verbosity {
     Quiet,   // Only critical events (errors, stuck agents)
+    #[default]
     Normal,  // Standard events (completions, assignments)
     Chatty,  // All events including minor updates
 }
 
-impl Default for Verbosity {
-    fn default() -> Self {
-        Verbosity::Normal
+/// Channel the frontend listens on for live Gas Town events
+pub const EVENTS_CHANNEL: &str = "gastown-event";
+
+/// Channel spoken summaries of event batches are emitted on
+pub const EVENTS_SUMMARY_CHANNEL: &str = "gastown-event-summary";
+
+/// Channel grouped, rate-limited announcements meant to be spoken are emitted on
+pub const ANNOUNCEMENTS_CHANNEL: &str = "gastown-announcement";
+
+/// How often to look for a town's events file before one exists
+const AUTO_START_POLL: Duration = Duration::from_secs(5);
+
+/// Event history dir used while no town is registered
+const DEFAULT_HISTORY: &str = "default";
+
+/// Settings and tail shared between the commands and the running watcher,
+/// so changes apply to events as they arrive
+#[derive(Default)]
+struct WatchShared {
+    verbosity: Mutex<Verbosity>,
+    filters: Mutex<EventFilters>,
+    commentary: Mutex<CommentaryConfig>,
+    tail: Mutex<Option<JsonlTail>>,
+    summary: Mutex<SummaryBatch>,
+    digest: Mutex<Digest>,
+    history: Mutex<Option<EventStore>>,
+}
+
+/// Events collected for the next spoken summary
+#[derive(Default)]
+struct SummaryBatch {
+    lines: Vec<String>,
+    /// Bumped each time a batch is taken, so a stale flush timer is a no-op
+    generation: u64,
+}
+
+impl SummaryBatch {
+    fn take(&mut self) -> Vec<String> {
+        self.generation += 1;
+        std::mem::take(&mut self.lines)
     }
 }
 
+/// Spoken summary of a batch of events
+#[derive(Debug, Clone, Serialize)]
+pub struct EventSummary {
+    pub text: String,
+    pub audio_base64: Option<String>,
+    pub audio_sample_rate: u32,
+    pub event_count: usize,
+}
+
 /// Events watcher state
+#[derive(Default)]
 pub struct EventsWatcherState {
     is_watching: AtomicBool,
-    verbosity: Mutex<Verbosity>,
-    last_position: AtomicU64,
+    shared: Arc<WatchShared>,
     watcher: Mutex<Option<RecommendedWatcher>>,
+    /// Events file the watcher follows
+    events_path: Mutex<Option<PathBuf>>,
 }
 
-impl Default for EventsWatcherState {
-    fn default() -> Self {
-        Self {
-            is_watching: AtomicBool::new(false),
-            verbosity: Mutex::new(Verbosity::Normal),
-            last_position: AtomicU64::new(0),
-            watcher: Mutex::new(None),
-        }
-    }
-}
-
-/// Get the events file path
-fn get_events_path() -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
-    let events_path = home.join("gt/.events.jsonl");
+/// Get the events file path of `town` (default the active town)
+fn get_events_path(app: &AppHandle, town: Option<&str>) -> Result<PathBuf, String> {
+    let events_path = towns::root(app, town)?.join(".events.jsonl");
 
     if events_path.exists() {
         Ok(events_path)
@@ -68,21 +330,20 @@ fn get_events_path() -> Result<PathBuf, String> {
 
 /// Check if event should be emitted based on verbosity
 fn should_emit_event(event: &GasTownEvent, verbosity: Verbosity) -> bool {
+    use EventKind::*;
+
     match verbosity {
         Verbosity::Quiet => {
             // Only critical: errors, stuck, blocked
-            matches!(
-                event.event_type.as_str(),
-                "error" | "stuck" | "blocked" | "escalation"
-            )
+            matches!(event.kind, Error(_) | Stuck(_) | Blocked(_) | Escalation(_))
         }
         Verbosity::Normal => {
             // Standard: completions, assignments, mail, session changes
             matches!(
-                event.event_type.as_str(),
-                "error" | "stuck" | "blocked" | "escalation" |
-                "mail" | "complete" | "assignment" | "session_start" | "session_end" |
-                "merge" | "convoy_start" | "convoy_complete"
+                event.kind,
+                Error(_) | Stuck(_) | Blocked(_) | Escalation(_) |
+                Mail(_) | Complete(_) | Assignment(_) | SessionStart(_) | SessionEnd(_) |
+                Merge(_) | ConvoyStart(_) | ConvoyComplete(_)
             )
         }
         Verbosity::Chatty => {
@@ -92,64 +353,12 @@ fn should_emit_event(event: &GasTownEvent, verbosity: Verbosity) -> bool {
     }
 }
 
-/// Generate voice commentary for an event
-fn generate_commentary(event: &GasTownEvent) -> Option<String> {
-    let commentary = match event.event_type.as_str() {
-        "mail" => {
-            let subject = event.payload.get("subject")
-                .and_then(|v| v.as_str())
-                .unwrap_or("message");
-            let to = event.payload.get("to")
-                .and_then(|v| v.as_str())
-                .unwrap_or("someone");
-            Some(format!("Mail from {} to {}: {}", event.actor, to, subject))
-        }
-        "session_start" => {
-            Some(format!("{} has joined the party.", event.actor))
-        }
-        "session_end" => {
-            Some(format!("{} has left. Hopefully they finished something.", event.actor))
-        }
-        "complete" => {
-            let task = event.payload.get("task")
-                .and_then(|v| v.as_str())
-                .unwrap_or("something");
-            Some(format!("{} completed {}. Miracles do happen.", event.actor, task))
-        }
-        "stuck" => {
-            Some(format!("Heads up: {} appears to be stuck. Might need a nudge.", event.actor))
-        }
-        "error" => {
-            let msg = event.payload.get("message")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown error");
-            Some(format!("Error from {}: {}", event.actor, msg))
-        }
-        "nudge" => {
-            let target = event.payload.get("target")
-                .and_then(|v| v.as_str())
-                .unwrap_or("someone");
-            Some(format!("{} nudged {}.", event.actor, target))
-        }
-        "merge" => {
-            Some(format!("Work from {} has been merged. Progress!", event.actor))
-        }
-        "convoy_start" => {
-            let name = event.payload.get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("convoy");
-            Some(format!("Convoy {} is rolling out.", name))
-        }
-        "convoy_complete" => {
-            let name = event.payload.get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("convoy");
-            Some(format!("Convoy {} has reached its destination.", name))
-        }
-        _ => None,
-    };
-
-    commentary
+/// Check an event against the user's filter rules, falling back to the
+/// verbosity level when no rule matches
+fn is_wanted(event: &GasTownEvent, verbosity: Verbosity, filters: &EventFilters) -> bool {
+    filters
+        .decide(event)
+        .unwrap_or_else(|| should_emit_event(event, verbosity))
 }
 
 /// Enriched event with commentary
@@ -159,90 +368,367 @@ pub struct EnrichedEvent {
     pub commentary: Option<String>,
 }
 
-/// Start watching the events file
+/// Start watching the events file of `town` (default the active town)
 #[tauri::command]
 pub async fn start_events_watcher(
     app: AppHandle,
     state: State<'_, EventsWatcherState>,
+    town: Option<String>,
 ) -> Result<(), String> {
-    if state.is_watching.load(Ordering::SeqCst) {
+    let events_path = get_events_path(&app, town.as_deref())?;
+    start_watching(&app, &state, town.as_deref(), events_path)
+}
+
+/// Start the watcher on `events_path` if it isn't already following it,
+/// leaving any other town's file. The watcher lives in app state, so it
+/// keeps running across frontend reloads.
+fn start_watching(
+    app: &AppHandle,
+    state: &EventsWatcherState,
+    town: Option<&str>,
+    events_path: PathBuf,
+) -> Result<(), String> {
+    let mut watcher_slot = state.watcher.lock().map_err(|e| e.to_string())?;
+    let mut watched = state.events_path.lock().map_err(|e| e.to_string())?;
+    if watcher_slot.is_some() && watched.as_ref() == Some(&events_path) {
         return Ok(()); // Already watching
     }
+    *watcher_slot = None;
+    *watched = None;
+    state.is_watching.store(false, Ordering::SeqCst);
+    let events_dir = events_path
+        .parent()
+        .ok_or("Events file has no parent directory")?
+        .to_path_buf();
+    let file_name = events_path.file_name().map(|n| n.to_os_string());
 
-    let events_path = get_events_path()?;
-
-    // Get current file size as starting position
-    let file = File::open(&events_path)
-        .map_err(|e| format!("Failed to open events file: {}", e))?;
-    let metadata = file.metadata()
-        .map_err(|e| format!("Failed to get file metadata: {}", e))?;
-    state.last_position.store(metadata.len(), Ordering::SeqCst);
-
-    // Create shared state for the watcher callback
-    let last_position = Arc::new(AtomicU64::new(metadata.len()));
-    let verbosity_level = Arc::new(Mutex::new(*state.verbosity.lock().unwrap()));
+    // Only report events written from now on
+    *state.shared.tail.lock().map_err(|e| e.to_string())? =
+        Some(JsonlTail::from_end(&events_path));
+    read_new_events(app, &state.shared);
 
-    // Store starting position
-    state.last_position.store(metadata.len(), Ordering::SeqCst);
+    // Store whatever was logged while we weren't watching; anything the
+    // tail also picked up is skipped as a duplicate
+    switch_history(app, &state.shared, town);
+    catch_up_history(&state.shared, &events_path);
 
-    let last_position_clone = Arc::clone(&last_position);
-    let verbosity_clone = Arc::clone(&verbosity_level);
-    let events_path_clone = events_path.clone();
+    let shared = Arc::clone(&state.shared);
     let app_clone = app.clone();
 
-    // Create file watcher
+    // Watch the directory rather than the file, so rotation, deletion and
+    // recreation of the events file are all seen
     let mut watcher = RecommendedWatcher::new(
         move |res: Result<notify::Event, notify::Error>| {
             if let Ok(event) = res {
-                if event.kind.is_modify() {
-                    // Read new lines from the file
-                    if let Ok(mut file) = File::open(&events_path_clone) {
-                        let current_pos = last_position_clone.load(Ordering::SeqCst);
-                        if file.seek(SeekFrom::Start(current_pos)).is_ok() {
-                            let reader = BufReader::new(&file);
-                            let verbosity = *verbosity_clone.lock().unwrap();
-
-                            for line in reader.lines() {
-                                if let Ok(line) = line {
-                                    if let Ok(event) = serde_json::from_str::<GasTownEvent>(&line) {
-                                        if should_emit_event(&event, verbosity) {
-                                            let commentary = generate_commentary(&event);
-                                            let enriched = EnrichedEvent { event, commentary };
-                                            let _ = app_clone.emit("gastwon-event", &enriched);
-                                        }
-                                    }
-                                }
-                            }
-
-                            // Update position
-                            if let Ok(pos) = file.stream_position() {
-                                last_position_clone.store(pos, Ordering::SeqCst);
-                            }
-                        }
-                    }
+                if event.paths.iter().any(|p| p.file_name() == file_name.as_deref()) {
+                    read_new_events(&app_clone, &shared);
                 }
             }
         },
         Config::default().with_poll_interval(Duration::from_secs(1)),
     ).map_err(|e| format!("Failed to create watcher: {}", e))?;
 
-    watcher.watch(&events_path, RecursiveMode::NonRecursive)
-        .map_err(|e| format!("Failed to watch events file: {}", e))?;
+    watcher.watch(&events_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch events directory: {}", e))?;
 
-    *state.watcher.lock().unwrap() = Some(watcher);
+    *watcher_slot = Some(watcher);
+    *watched = Some(events_path.clone());
     state.is_watching.store(true, Ordering::SeqCst);
 
     log::info!("Started watching events at {:?}", events_path);
     Ok(())
 }
 
+/// Emit every complete event line written since the last read
+fn read_new_events(app: &AppHandle, shared: &Arc<WatchShared>) {
+    let chunk = {
+        let Ok(mut tail) = shared.tail.lock() else { return };
+        let Some(tail) = tail.as_mut() else { return };
+        match tail.poll() {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                log::warn!("Failed to read events file: {}", e);
+                return;
+            }
+        }
+    };
+
+    if chunk.reset {
+        log::info!("Events file was rotated or truncated; following the new file");
+    }
+
+    if let Ok(mut history) = shared.history.lock() {
+        if let Some(store) = history.as_mut() {
+            for line in chunk.previous.iter().chain(chunk.lines.iter()) {
+                if let Err(e) = store.ingest(line) {
+                    log::warn!("Failed to store event: {}", e);
+                }
+            }
+        }
+    }
+
+    let verbosity = shared.verbosity.lock().map(|v| *v).unwrap_or_default();
+    let filters = shared.filters.lock().map(|f| f.clone()).unwrap_or_default();
+    let commentary = shared.commentary.lock().map(|c| c.clone()).unwrap_or_default();
+    for line in chunk.previous.iter().chain(chunk.lines.iter()) {
+        if let Ok(event) = serde_json::from_str::<GasTownEvent>(line) {
+            match event.kind {
+                EventKind::ConvoyStart(_) | EventKind::ConvoyComplete(_) => {
+                    convoy::spawn_refresh(app)
+                }
+                EventKind::Merge(_) => refinery::spawn_refresh(app, event.rig().map(str::to_string)),
+                _ => {}
+            }
+            if is_wanted(&event, verbosity, &filters) {
+                let enriched = EnrichedEvent {
+                    commentary: commentary.render(&event),
+                    event,
+                };
+                let _ = app.emit(EVENTS_CHANNEL, &enriched);
+                announce(app, shared, &enriched, &commentary);
+                if commentary.summaries.enabled {
+                    queue_for_summary(app, shared, &enriched, &commentary);
+                }
+            }
+        }
+    }
+}
+
+/// Announce critical events right away; gather the rest into a digest
+/// that is announced when its window closes
+fn announce(
+    app: &AppHandle,
+    shared: &Arc<WatchShared>,
+    enriched: &EnrichedEvent,
+    config: &CommentaryConfig,
+) {
+    if enriched.commentary.is_none() {
+        return;
+    }
+    if config.digest.is_critical(enriched) {
+        if let Some(announcement) = Announcement::single(enriched, true) {
+            let _ = app.emit(ANNOUNCEMENTS_CHANNEL, &announcement);
+        }
+        return;
+    }
+
+    let Ok(mut digest) = shared.digest.lock() else { return };
+    if digest.queue(enriched.clone()) {
+        let window = Duration::from_secs(config.digest.window_secs);
+        schedule_digest_flush(app, shared, window);
+    }
+}
+
+/// Announce the pending digest after `delay`, waiting longer while the
+/// per-minute cap is reached
+fn schedule_digest_flush(app: &AppHandle, shared: &Arc<WatchShared>, delay: Duration) {
+    let app = app.clone();
+    let shared = Arc::clone(shared);
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        let max_per_minute = shared
+            .commentary
+            .lock()
+            .map(|c| c.digest.max_per_minute)
+            .unwrap_or_default();
+        let taken = {
+            let Ok(mut digest) = shared.digest.lock() else { return };
+            digest.take(Instant::now(), max_per_minute)
+        };
+        match taken {
+            Ok(events) => {
+                if let Some(announcement) = Announcement::digest(&events) {
+                    let _ = app.emit(ANNOUNCEMENTS_CHANNEL, &announcement);
+                }
+            }
+            // Keep collecting; everything held is folded into one announcement
+            Err(wait) => schedule_digest_flush(&app, &shared, wait),
+        }
+    });
+}
+
+/// Add an event to the summary batch, summarizing once the batch is full or
+/// its first event has waited long enough
+fn queue_for_summary(
+    app: &AppHandle,
+    shared: &Arc<WatchShared>,
+    enriched: &EnrichedEvent,
+    config: &CommentaryConfig,
+) {
+    let line = enriched.commentary.clone().unwrap_or_else(|| {
+        format!("{}: {}", enriched.event.actor, enriched.event.kind.type_name())
+    });
+    let persona = config.persona.clone().unwrap_or_default();
+
+    let Ok(mut batch) = shared.summary.lock() else { return };
+    batch.lines.push(line);
+    if batch.lines.len() >= config.summaries.batch_size.max(1) {
+        let lines = batch.take();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move { summarize(&app, &persona, lines).await });
+    } else if batch.lines.len() == 1 {
+        let generation = batch.generation;
+        let wait = Duration::from_secs(config.summaries.max_wait_secs);
+        let shared = Arc::clone(shared);
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(wait).await;
+            let lines = {
+                let Ok(mut batch) = shared.summary.lock() else { return };
+                if batch.generation != generation {
+                    return; // Already summarized when it filled up
+                }
+                batch.take()
+            };
+            summarize(&app, &persona, lines).await;
+        });
+    }
+}
+
+/// Have the voice server summarize a batch of events and emit the result
+async fn summarize(app: &AppHandle, persona: &AgentPersona, lines: Vec<String>) {
+    let voice_state = app.state::<VoiceServerState>();
+    match voice::summarize_in_persona(&voice_state, persona, &lines).await {
+        Ok(response) => {
+            let summary = EventSummary {
+                text: response.text,
+                audio_base64: response.audio_base64,
+                audio_sample_rate: response.audio_sample_rate,
+                event_count: lines.len(),
+            };
+            let _ = app.emit(EVENTS_SUMMARY_CHANNEL, &summary);
+        }
+        Err(e) => log::warn!("Failed to summarize events: {}", e),
+    }
+}
+
+/// Load the saved filter rules and commentary settings into the watcher state
+pub fn load_event_settings(app: &AppHandle) {
+    let state = app.state::<EventsWatcherState>();
+    let filters = event_filters::load(app);
+    log::info!("Loaded {} event filter rules", filters.rules.len());
+    *state.shared.filters.lock().unwrap() = filters;
+    *state.shared.commentary.lock().unwrap() = commentary::load(app);
+}
+
+/// Where `town`'s (default the active town's) event history is kept:
+/// `event_history/<town-id>` in the app data dir
+fn history_dir(app: &AppHandle, town: Option<&str>) -> Result<PathBuf, String> {
+    let id = towns::selected_id(app, town)?.unwrap_or_else(|| DEFAULT_HISTORY.to_string());
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(dir.join("event_history").join(id))
+}
+
+/// Point the watcher's event history at `town`'s store, unless it's
+/// already there
+fn switch_history(app: &AppHandle, shared: &WatchShared, town: Option<&str>) {
+    let dir = match history_dir(app, town) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("Event history unavailable: {}", e);
+            return;
+        }
+    };
+    let Ok(mut history) = shared.history.lock() else { return };
+    if history.as_ref().is_some_and(|store| store.dir() == dir) {
+        return;
+    }
+    match EventStore::open(&dir) {
+        Ok(store) => *history = Some(store),
+        Err(e) => {
+            *history = None;
+            log::warn!("Event history unavailable: {}", e);
+        }
+    }
+}
+
+/// Open the active town's on-disk event history
+pub fn open_event_history(app: &AppHandle) {
+    switch_history(app, &app.state::<EventsWatcherState>().shared, None);
+}
+
+/// Run `f` on `town`'s event history: the watcher's store when it's that
+/// town's, otherwise the town's own store, opened for this call. `f` is
+/// told whether the running watcher keeps the store current.
+fn with_history<T>(
+    app: &AppHandle,
+    state: &EventsWatcherState,
+    town: Option<&str>,
+    f: impl FnOnce(&mut EventStore, bool) -> Result<T, String>,
+) -> Result<T, String> {
+    let dir = history_dir(app, town)?;
+    let mut history = state.shared.history.lock().map_err(|e| e.to_string())?;
+    match history.as_mut() {
+        Some(store) if store.dir() == dir => f(store, state.is_watching.load(Ordering::SeqCst)),
+        _ => f(&mut EventStore::open(&dir)?, false),
+    }
+}
+
+/// Store any events in the log that the history doesn't have yet
+fn catch_up_history(shared: &WatchShared, events_path: &Path) {
+    let Ok(mut history) = shared.history.lock() else { return };
+    let Some(store) = history.as_mut() else { return };
+    match store.catch_up(events_path) {
+        Ok(0) => {}
+        Ok(added) => log::info!("Stored {} new events in event history", added),
+        Err(e) => log::warn!("Failed to catch up event history: {}", e),
+    }
+}
+
+/// Start the watcher as soon as a town's events file shows up.
+/// Gives up once the watcher has been started (by us or the frontend).
+pub fn spawn_auto_start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let state = app.state::<EventsWatcherState>();
+            if state.is_watching.load(Ordering::SeqCst) {
+                break;
+            }
+            if let Ok(events_path) = get_events_path(&app, None) {
+                match start_watching(&app, &state, None, events_path) {
+                    Ok(()) => break,
+                    Err(e) => log::warn!("Failed to auto-start events watcher: {}", e),
+                }
+            }
+            tokio::time::sleep(AUTO_START_POLL).await;
+        }
+    });
+}
+
+/// Switch the event history to the active town's, and move a running
+/// watcher to its events file. If that town has no events file yet, wait
+/// for one to show up.
+pub fn follow_active_town(app: &AppHandle) {
+    let state = app.state::<EventsWatcherState>();
+    switch_history(app, &state.shared, None);
+    if !state.is_watching.load(Ordering::SeqCst) {
+        return;
+    }
+    let started = get_events_path(app, None)
+        .and_then(|events_path| start_watching(app, &state, None, events_path));
+    if let Err(e) = started {
+        log::info!("Not following the active town's events yet: {}", e);
+        stop_watching(&state);
+        spawn_auto_start(app.clone());
+    }
+}
+
+fn stop_watching(state: &EventsWatcherState) {
+    *state.watcher.lock().unwrap() = None;
+    *state.events_path.lock().unwrap() = None;
+    *state.shared.tail.lock().unwrap() = None;
+    state.is_watching.store(false, Ordering::SeqCst);
+}
+
 /// Stop watching the events file
 #[tauri::command]
 pub async fn stop_events_watcher(
     state: State<'_, EventsWatcherState>,
 ) -> Result<(), String> {
-    state.is_watching.store(false, Ordering::SeqCst);
-    *state.watcher.lock().unwrap() = None;
+    stop_watching(&state);
     log::info!("Stopped watching events");
     Ok(())
 }
@@ -253,7 +739,7 @@ pub async fn set_events_verbosity(
     state: State<'_, EventsWatcherState>,
     verbosity: Verbosity,
 ) -> Result<(), String> {
-    *state.verbosity.lock().unwrap() = verbosity;
+    *state.shared.verbosity.lock().unwrap() = verbosity;
     log::info!("Set events verbosity to {:?}", verbosity);
     Ok(())
 }
@@ -263,36 +749,125 @@ pub async fn set_events_verbosity(
 pub async fn get_events_verbosity(
     state: State<'_, EventsWatcherState>,
 ) -> Result<Verbosity, String> {
-    Ok(*state.verbosity.lock().unwrap())
+    Ok(*state.shared.verbosity.lock().unwrap())
+}
+
+/// Get the event filter rules
+#[tauri::command]
+pub async fn get_event_filters(
+    state: State<'_, EventsWatcherState>,
+) -> Result<EventFilters, String> {
+    Ok(state.shared.filters.lock().unwrap().clone())
+}
+
+/// Replace the event filter rules and save them to the app config
+#[tauri::command]
+pub async fn set_event_filters(
+    app: AppHandle,
+    state: State<'_, EventsWatcherState>,
+    filters: EventFilters,
+) -> Result<(), String> {
+    event_filters::save(&app, &filters)?;
+    log::info!("Set {} event filter rules", filters.rules.len());
+    *state.shared.filters.lock().unwrap() = filters;
+    Ok(())
+}
+
+/// Get the commentary templates and summary settings
+#[tauri::command]
+pub async fn get_commentary_config(
+    state: State<'_, EventsWatcherState>,
+) -> Result<CommentaryConfig, String> {
+    Ok(state.shared.commentary.lock().unwrap().clone())
 }
 
-/// Get recent events (last N)
+/// Replace the commentary settings and save them to the app config
+#[tauri::command]
+pub async fn set_commentary_config(
+    app: AppHandle,
+    state: State<'_, EventsWatcherState>,
+    config: CommentaryConfig,
+) -> Result<(), String> {
+    commentary::save(&app, &config)?;
+    *state.shared.commentary.lock().unwrap() = config;
+    Ok(())
+}
+
+/// Get recent events (last N) of `town` (default the active town), oldest
+/// first. Unless the running watcher keeps that town's history current, it
+/// is first caught up from the town's events file.
 #[tauri::command]
 pub async fn get_recent_events(
+    app: AppHandle,
     state: State<'_, EventsWatcherState>,
     count: Option<usize>,
+    town: Option<String>,
 ) -> Result<Vec<EnrichedEvent>, String> {
-    let events_path = get_events_path()?;
-    let file = File::open(&events_path)
-        .map_err(|e| format!("Failed to open events file: {}", e))?;
-
-    let reader = BufReader::new(file);
-    let verbosity = *state.verbosity.lock().unwrap();
+    let verbosity = *state.shared.verbosity.lock().unwrap();
+    let filters = state.shared.filters.lock().unwrap().clone();
+    let commentary = state.shared.commentary.lock().unwrap().clone();
     let limit = count.unwrap_or(20);
 
-    let events: Vec<EnrichedEvent> = reader.lines()
-        .filter_map(|line| line.ok())
-        .filter_map(|line| serde_json::from_str::<GasTownEvent>(&line).ok())
-        .filter(|e| should_emit_event(e, verbosity))
-        .map(|event| {
-            let commentary = generate_commentary(&event);
-            EnrichedEvent { event, commentary }
+    let mut events = Vec::new();
+    with_history(&app, &state, town.as_deref(), |store, current| {
+        if !current {
+            match store.catch_up(&get_events_path(&app, town.as_deref())?) {
+                Ok(0) => {}
+                Ok(added) => log::info!("Stored {} new events in event history", added),
+                Err(e) => log::warn!("Failed to catch up event history: {}", e),
+            }
+        }
+        if limit == 0 {
+            return Ok(());
+        }
+        store.scan_back(&HistoryQuery::default(), |event| {
+            if is_wanted(&event, verbosity, &filters) {
+                events.push(EnrichedEvent {
+                    commentary: commentary.render(&event),
+                    event,
+                });
+            }
+            events.len() < limit
+        })
+    })?;
+    events.reverse();
+    Ok(events)
+}
+
+/// Query `town`'s stored events by time range, type, actor and rig, newest
+/// first
+#[tauri::command]
+pub async fn query_event_history(
+    app: AppHandle,
+    state: State<'_, EventsWatcherState>,
+    query: HistoryQuery,
+    town: Option<String>,
+) -> Result<Vec<EnrichedEvent>, String> {
+    let commentary = state.shared.commentary.lock().unwrap().clone();
+    let events = with_history(&app, &state, town.as_deref(), |store, _| {
+        store.query(&query)
+    })?;
+    Ok(events
+        .into_iter()
+        .map(|event| EnrichedEvent {
+            commentary: commentary.render(&event),
+            event,
         })
-        .collect();
+        .collect())
+}
 
-    // Return last N events
-    let start = events.len().saturating_sub(limit);
-    Ok(events[start..].to_vec())
+/// Count `town`'s stored events matching a query per time bucket
+#[tauri::command]
+pub async fn count_event_history(
+    app: AppHandle,
+    state: State<'_, EventsWatcherState>,
+    query: HistoryQuery,
+    bucket_secs: Option<u64>,
+    town: Option<String>,
+) -> Result<Vec<EventBucket>, String> {
+    with_history(&app, &state, town.as_deref(), |store, _| {
+        store.count(&query, bucket_secs.unwrap_or(3600))
+    })
 }
 
 /// Check if watcher is active
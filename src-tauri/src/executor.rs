@@ -0,0 +1,802 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+/// Programs the frontend may run
+pub const ALLOWED_PROGRAMS: &[&str] = &["gt", "bd", "tmux", "git"];
+
+/// How long a confirmation token stays valid
+const CONFIRMATION_TTL: Duration = Duration::from_secs(60);
+
+/// How much damage a command can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskClass {
+    /// Only reads state
+    ReadOnly,
+    /// Changes state in a way that's routine to undo
+    Mutating,
+    /// Stops agents, deletes data or rewrites history; needs confirmation
+    Destructive,
+}
+
+/// An allowlisted subcommand: how risky it is and which flags it takes.
+/// Flags not listed are refused.
+struct Subcommand {
+    /// One word, or two for a group like `convoy list`
+    name: &'static str,
+    risk: RiskClass,
+    /// `--flag=value` is checked as `--flag`, and combined short flags like
+    /// `-fd` one by one
+    flags: &'static [&'static str],
+    /// Flags that make a routine change destructive
+    escalating: &'static [&'static str],
+}
+
+const fn read_only(name: &'static str, flags: &'static [&'static str]) -> Subcommand {
+    Subcommand {
+        name,
+        risk: RiskClass::ReadOnly,
+        flags,
+        escalating: &[],
+    }
+}
+
+const fn mutating(name: &'static str, flags: &'static [&'static str]) -> Subcommand {
+    Subcommand {
+        name,
+        risk: RiskClass::Mutating,
+        flags,
+        escalating: &[],
+    }
+}
+
+/// A mutating subcommand some of whose flags make it destructive
+const fn forcible(
+    name: &'static str,
+    flags: &'static [&'static str],
+    escalating: &'static [&'static str],
+) -> Subcommand {
+    Subcommand {
+        name,
+        risk: RiskClass::Mutating,
+        flags,
+        escalating,
+    }
+}
+
+const fn destructive(name: &'static str, flags: &'static [&'static str]) -> Subcommand {
+    Subcommand {
+        name,
+        risk: RiskClass::Destructive,
+        flags,
+        escalating: &[],
+    }
+}
+
+const JSON: &[&str] = &["--json"];
+
+/// gt subcommands; anything unlisted is refused
+#[rustfmt::skip]
+const GT: &[Subcommand] = &[
+    read_only("version", &[]),
+    read_only("status", &["--json", "-v", "--verbose"]),
+    read_only("rigs", JSON),
+    read_only("peek", &["-n", "--lines"]),
+    read_only("cost", JSON),
+    read_only("doctor", JSON),
+    read_only("feed", &["--json", "-n", "--limit"]),
+    read_only("log", &["--json", "-n", "--limit"]),
+    read_only("trail", JSON),
+    read_only("convoy list", &["--json", "--all"]),
+    read_only("convoy show", JSON),
+    read_only("convoy status", JSON),
+    read_only("polecat list", JSON),
+    read_only("polecat status", JSON),
+    read_only("refinery queue", JSON),
+    read_only("refinery status", JSON),
+    read_only("mail inbox", &["--json", "--unread"]),
+    read_only("mail thread", JSON),
+    read_only("mail list", JSON),
+    read_only("mol progress", JSON),
+    read_only("mol status", JSON),
+    mutating("sling", &[]),
+    mutating("nudge", &[]),
+    mutating("handoff", &["-m", "--message"]),
+    mutating("hook", &[]),
+    mutating("done", &[]),
+    mutating("start", &[]),
+    mutating("up", &[]),
+    mutating("install", &[]),
+    mutating("convoy create", JSON),
+    mutating("convoy add", &[]),
+    mutating("polecat spawn", &[]),
+    mutating("polecat add", &[]),
+    mutating("refinery start", &[]),
+    mutating("refinery process", &[]),
+    mutating("mail send", &["-s", "--subject", "-m", "--message"]),
+    mutating("mail read", &[]),
+    mutating("mail reply", &["-m", "--message"]),
+    mutating("rig add", &[]),
+    destructive("stop", &["--all"]),
+    destructive("down", &["--all"]),
+    destructive("shutdown", &[]),
+    destructive("session restart", &[]),
+    destructive("convoy cancel", &["--reason"]),
+    destructive("polecat remove", &[]),
+    destructive("polecat kill", &[]),
+    destructive("polecat nuke", &[]),
+    destructive("refinery stop", &[]),
+    destructive("refinery reject", &["--reason"]),
+    destructive("rig remove", &[]),
+];
+
+/// bd subcommands
+#[rustfmt::skip]
+const BD: &[Subcommand] = &[
+    read_only("version", &[]),
+    read_only(
+        "list",
+        &[
+            "--json", "--status", "-s", "--priority", "-p", "--type", "-t", "--assignee", "--label",
+            "-l", "--limit", "-n",
+        ],
+    ),
+    read_only("show", JSON),
+    read_only("ready", &["--json", "--limit", "-n"]),
+    read_only("blocked", JSON),
+    read_only("stats", JSON),
+    read_only("search", &["--json", "--limit", "-n"]),
+    read_only("info", JSON),
+    read_only("doctor", JSON),
+    read_only("dep tree", JSON),
+    read_only("dep list", JSON),
+    mutating(
+        "create",
+        &[
+            "--json", "--title", "--description", "-d", "--priority", "-p", "--type", "-t",
+            "--labels", "-l", "--assignee", "-a",
+        ],
+    ),
+    mutating(
+        "update",
+        &[
+            "--json", "--status", "-s", "--priority", "-p", "--title", "--description", "-d",
+            "--assignee", "-a", "--notes",
+        ],
+    ),
+    mutating("close", &["--json", "--reason", "-r"]),
+    mutating("reopen", &["--json", "--reason", "-r"]),
+    mutating("sync", &[]),
+    mutating("init", &["--prefix"]),
+    mutating("dep add", &["--type", "-t"]),
+    mutating("dep remove", &[]),
+    mutating("label add", &[]),
+    mutating("label remove", &[]),
+    mutating("comment", &[]),
+    mutating("comments add", &[]),
+    destructive("delete", &["--json", "--force", "-f"]),
+    destructive("compact", &["--dry-run"]),
+];
+
+/// tmux subcommands. Starting programs in a session, typing into a pane and
+/// pasting go through their own commands, not through here.
+#[rustfmt::skip]
+const TMUX: &[Subcommand] = &[
+    read_only("list-sessions", &["-F", "-f"]),
+    read_only("ls", &["-F", "-f"]),
+    read_only("list-panes", &["-a", "-s", "-t", "-F", "-f"]),
+    read_only("list-windows", &["-a", "-t", "-F", "-f"]),
+    read_only("capture-pane", &["-p", "-e", "-J", "-N", "-t", "-S", "-E"]),
+    read_only("display-message", &["-p", "-t"]),
+    read_only("has-session", &["-t"]),
+    read_only("show-options", &["-g", "-s", "-w", "-p", "-t", "-v", "-q", "-A"]),
+    mutating("select-pane", &["-t", "-L", "-R", "-U", "-D", "-l", "-Z"]),
+    mutating("select-window", &["-t", "-l", "-n", "-p"]),
+    mutating("rename-session", &["-t"]),
+    mutating("rename-window", &["-t"]),
+    mutating("resize-pane", &["-t", "-x", "-y", "-Z", "-L", "-R", "-U", "-D"]),
+    destructive("kill-session", &["-t", "-a"]),
+    destructive("kill-server", &[]),
+    destructive("kill-pane", &["-t", "-a"]),
+    destructive("kill-window", &["-t", "-a"]),
+];
+
+/// git subcommands. Flags that run programs or write files, like
+/// `--upload-pack`, `--exec` or `--output`, are left out on purpose.
+#[rustfmt::skip]
+const GIT: &[Subcommand] = &[
+    read_only("version", &[]),
+    read_only(
+        "status",
+        &[
+            "-s", "--short", "-b", "--branch", "--porcelain", "-u", "--untracked-files",
+        ],
+    ),
+    read_only(
+        "log",
+        &[
+            "--oneline", "-n", "--max-count", "--graph", "--decorate", "--all", "--stat",
+            "--format", "--pretty", "--since", "--until", "--author", "-p", "--reverse",
+            "--no-merges", "--first-parent",
+        ],
+    ),
+    read_only(
+        "diff",
+        &[
+            "--stat", "--cached", "--staged", "--name-only", "--name-status", "--shortstat",
+            "--numstat", "--no-color",
+        ],
+    ),
+    read_only(
+        "show",
+        &[
+            "--stat", "--oneline", "--format", "--pretty", "--name-only", "--name-status",
+            "--no-patch", "-s",
+        ],
+    ),
+    read_only(
+        "rev-parse",
+        &[
+            "--abbrev-ref", "--short", "--show-toplevel", "--git-dir", "--verify", "-q", "--quiet",
+        ],
+    ),
+    read_only(
+        "ls-files",
+        &[
+            "-m", "--modified", "-o", "--others", "--exclude-standard", "-c", "--cached", "-d",
+            "--deleted",
+        ],
+    ),
+    read_only("describe", &["--tags", "--always", "--dirty", "--abbrev"]),
+    read_only("remote", &["-v", "--verbose"]),
+    read_only("remote show", &["-n"]),
+    read_only("remote get-url", &["--push", "--all"]),
+    read_only("worktree list", &["--porcelain", "-v"]),
+    read_only("stash list", &[]),
+    read_only("stash show", &["-p", "--stat"]),
+    forcible(
+        "branch",
+        &[
+            "-a", "--all", "-r", "--remotes", "-v", "--list", "--show-current", "-m", "--move",
+            "--merged", "--no-merged", "-d", "-D", "--delete", "-M", "-f", "--force",
+        ],
+        &["-d", "-D", "--delete", "-M", "-f", "--force"],
+    ),
+    forcible(
+        "fetch",
+        &[
+            "--all", "--prune", "-p", "--tags", "--no-tags", "-q", "--quiet", "-v", "--verbose",
+            "--depth", "--dry-run", "-f", "--force",
+        ],
+        &["-f", "--force"],
+    ),
+    forcible(
+        "pull",
+        &[
+            "--rebase", "--no-rebase", "--ff-only", "--no-edit", "--autostash", "--prune", "--tags",
+            "-q", "--quiet", "-v", "-f", "--force",
+        ],
+        &["-f", "--force"],
+    ),
+    mutating(
+        "add",
+        &[
+            "-A", "--all", "-u", "--update", "-N", "--intent-to-add", "-v", "-n", "--dry-run",
+        ],
+    ),
+    forcible(
+        "commit",
+        &[
+            "-m", "--message", "-a", "--all", "--amend", "--no-edit", "--allow-empty", "-s",
+            "--signoff", "-q", "--quiet",
+        ],
+        &["--amend"],
+    ),
+    forcible(
+        "checkout",
+        &["-b", "-B", "--track", "--no-track", "--detach", "-q", "--quiet", "-f", "--force"],
+        &["-B", "-f", "--force"],
+    ),
+    forcible(
+        "switch",
+        &[
+            "-c", "--create", "-C", "--force-create", "--detach", "--track", "--no-track", "-q",
+            "--discard-changes", "-f", "--force",
+        ],
+        &["-C", "--force-create", "--discard-changes", "-f", "--force"],
+    ),
+    mutating("stash", &["-u", "--include-untracked", "-k", "--keep-index", "-q"]),
+    mutating(
+        "stash push",
+        &[
+            "-m", "--message", "-u", "--include-untracked", "-k", "--keep-index", "-q",
+        ],
+    ),
+    mutating("stash pop", &["--index", "-q"]),
+    mutating("stash apply", &["--index", "-q"]),
+    mutating(
+        "merge",
+        &[
+            "--no-ff", "--ff-only", "--squash", "--no-edit", "-m", "--abort", "--continue", "-q",
+        ],
+    ),
+    mutating(
+        "rebase",
+        &[
+            "--continue", "--abort", "--skip", "--onto", "--autostash", "--autosquash", "-q",
+        ],
+    ),
+    forcible(
+        "push",
+        &[
+            "-u", "--set-upstream", "--tags", "-q", "--quiet", "-v", "-n", "--dry-run", "-f",
+            "--force", "--force-with-lease", "--force-if-includes", "-d", "--delete", "--mirror",
+            "--prune", "--all",
+        ],
+        &[
+            "-f", "--force", "--force-with-lease", "--force-if-includes", "-d", "--delete",
+            "--mirror", "--prune", "--all",
+        ],
+    ),
+    forcible(
+        "worktree add",
+        &[
+            "-b", "-B", "--detach", "--track", "--no-track", "--checkout", "--no-checkout",
+            "--lock", "-q", "-f", "--force",
+        ],
+        &["-B", "-f", "--force"],
+    ),
+    mutating(
+        "remote add",
+        &[
+            "-f", "--fetch", "-t", "--track", "-m", "--master", "--tags", "--no-tags",
+        ],
+    ),
+    forcible("remote set-url", &["--push", "--add", "--delete"], &["--delete"]),
+    mutating("remote rename", &[]),
+    destructive("remote remove", &[]),
+    destructive("remote rm", &[]),
+    destructive("remote prune", &["-n", "--dry-run"]),
+    destructive("stash drop", &["-q"]),
+    destructive("stash clear", &[]),
+    destructive("reset", &["--hard", "--soft", "--mixed", "--keep", "--merge", "-q", "--quiet"]),
+    destructive("clean", &["-f", "--force", "-d", "-x", "-X", "-n", "--dry-run", "-q", "--quiet"]),
+    destructive("worktree remove", &["-f", "--force"]),
+];
+
+/// Find the subcommand `words` names. A word that starts a group only
+/// matches on its own when nothing follows it, so `remote add` can't pass
+/// as `remote`.
+fn lookup<'a>(table: &'a [Subcommand], words: &[&str]) -> Option<&'a Subcommand> {
+    let first = *words.first()?;
+    if let Some(second) = words.get(1) {
+        let pair = format!("{} {}", first, second);
+        if let Some(sub) = table.iter().find(|sub| sub.name == pair) {
+            return Some(sub);
+        }
+        let group = format!("{} ", first);
+        if table.iter().any(|sub| sub.name.starts_with(&group)) {
+            return None;
+        }
+    }
+    table.iter().find(|sub| sub.name == first)
+}
+
+/// The flags an argument sets: `--name=value` sets `--name`, `-abc` sets
+/// `-a`, `-b` and `-c`. Arguments that aren't flags, negative numbers
+/// included, set none.
+fn flags_of(arg: &str) -> Vec<String> {
+    if let Some(long) = arg.strip_prefix("--") {
+        let name = long.split('=').next().unwrap_or_default();
+        return vec![format!("--{}", name)];
+    }
+    match arg.strip_prefix('-') {
+        Some(short) if !short.is_empty() && short.parse::<i64>().is_err() => {
+            short.chars().map(|c| format!("-{}", c)).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Whether git arguments other than flags make a routine change
+/// destructive: forced (`+ref`) or deleting (`:ref`) refspecs, and
+/// checking out paths over local changes. git takes `checkout <path>`
+/// without `--` too, so unless a branch is being created or detached, more
+/// than one argument, or one that looks like a path, counts as a path
+/// checkout.
+fn git_escalates(subcommand: &str, options: &[&str], positional: &[&str], paths: &[&str]) -> bool {
+    match subcommand {
+        "push" => positional
+            .iter()
+            .any(|a| a.starts_with('+') || a.starts_with(':')),
+        "fetch" | "pull" => positional.iter().any(|a| a.starts_with('+')),
+        "checkout" => {
+            let switching = options
+                .iter()
+                .flat_map(|a| flags_of(a))
+                .any(|flag| matches!(flag.as_str(), "-b" | "-B" | "--detach"));
+            !paths.is_empty()
+                || (!switching
+                    && (positional.len() > 1 || positional.iter().any(|a| looks_like_path(a))))
+        }
+        _ => false,
+    }
+}
+
+/// A file name, glob or pathspec rather than a branch name
+fn looks_like_path(arg: &str) -> bool {
+    arg.starts_with(['/', ':']) || arg.contains(['.', '*', '?', '['])
+}
+
+/// Work out how risky `program args...` is, refusing programs, subcommands
+/// and flags that aren't allowlisted. Arguments after `--` are never
+/// taken as flags.
+pub fn classify(program: &str, args: &[String]) -> Result<RiskClass, String> {
+    let table = match program {
+        "gt" => GT,
+        "bd" => BD,
+        "tmux" => TMUX,
+        "git" => GIT,
+        _ => {
+            return Err(format!(
+                "Command not allowed: {} (only {} can be run)",
+                program,
+                ALLOWED_PROGRAMS.join(", ")
+            ))
+        }
+    };
+
+    // A bare version flag is always fine
+    if matches!(args, [flag] if ["--version", "-V", "-v"].contains(&flag.as_str())) {
+        return Ok(RiskClass::ReadOnly);
+    }
+
+    // tmux runs `#(...)` in formats as a shell command, and takes `;` as
+    // the start of another command
+    if program == "tmux" {
+        if let Some(arg) = args
+            .iter()
+            .find(|a| a.contains("#(") || a.as_str() == ";" || a.ends_with(';'))
+        {
+            return Err(format!("Argument not allowed: tmux {}", arg));
+        }
+    }
+
+    let words: Vec<&str> = args
+        .iter()
+        .map(|a| a.as_str())
+        .take_while(|a| !a.starts_with('-'))
+        .take(2)
+        .collect();
+    if words.is_empty() {
+        return Err(format!("{} needs a subcommand", program));
+    }
+    let sub = lookup(table, &words)
+        .ok_or_else(|| format!("Subcommand not allowed: {} {}", program, words.join(" ")))?;
+
+    let rest: Vec<&str> = args[sub.name.split(' ').count()..]
+        .iter()
+        .map(|a| a.as_str())
+        .collect();
+    let (options, paths) = match rest.iter().position(|&a| a == "--") {
+        Some(i) => (&rest[..i], &rest[i + 1..]),
+        None => (&rest[..], &[][..]),
+    };
+
+    let mut escalated = false;
+    let mut positional = Vec::new();
+    for &arg in options {
+        let flags = flags_of(arg);
+        if flags.is_empty() {
+            positional.push(arg);
+        }
+        for flag in flags {
+            if !sub.flags.contains(&flag.as_str()) {
+                return Err(format!(
+                    "Flag not allowed: {} {} {}",
+                    program, sub.name, flag
+                ));
+            }
+            escalated |= sub.escalating.contains(&flag.as_str());
+        }
+    }
+    escalated |= program == "git" && git_escalates(sub.name, options, &positional, paths);
+
+    if sub.risk == RiskClass::Mutating && escalated {
+        return Ok(RiskClass::Destructive);
+    }
+    Ok(sub.risk)
+}
+
+/// A confirmation issued for one exact destructive command
+struct PendingConfirmation {
+    argv: Vec<String>,
+    expires_at: Instant,
+}
+
+/// Outstanding confirmation tokens for destructive commands
+#[derive(Default)]
+pub struct ExecutorState {
+    confirmations: Mutex<HashMap<String, PendingConfirmation>>,
+}
+
+impl ExecutorState {
+    /// Check a command may run now. Destructive commands must present a
+    /// token issued for exactly this argv; the token is used up either way.
+    pub fn authorize(
+        &self,
+        argv: &[String],
+        risk: RiskClass,
+        token: Option<&str>,
+    ) -> Result<(), String> {
+        if risk != RiskClass::Destructive {
+            return Ok(());
+        }
+
+        let mut confirmations = self.confirmations.lock().map_err(|e| e.to_string())?;
+        let now = Instant::now();
+        confirmations.retain(|_, pending| pending.expires_at > now);
+
+        let pending = token.and_then(|token| confirmations.remove(token));
+        match pending {
+            Some(pending) if pending.argv == argv => Ok(()),
+            Some(_) => Err("Confirmation token was issued for a different command".to_string()),
+            None => Err(format!(
+                "`{}` is destructive and needs confirmation; request a confirmation token first",
+                argv.join(" ")
+            )),
+        }
+    }
+}
+
+/// Token confirming one destructive command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandConfirmation {
+    pub token: String,
+    pub argv: Vec<String>,
+    pub risk: RiskClass,
+    pub expires_in_secs: u64,
+}
+
+/// Classify a command without running it
+#[tauri::command]
+pub async fn classify_command(cmd: Option<String>, args: Vec<String>) -> Result<RiskClass, String> {
+    classify(cmd.as_deref().unwrap_or("gt"), &args)
+}
+
+/// An argv as the user should read it, with arguments that have spaces in
+/// them or are empty quoted
+fn display_argv(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("{:?}", arg)
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Ask the user in a native dialog whether `argv` may run. The webview
+/// can't answer this for them.
+async fn ask_user(app: &AppHandle, argv: &[String]) -> bool {
+    let (answer, answered) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(format!(
+            "This command is destructive:\n\n{}\n\nRun it?",
+            display_argv(argv)
+        ))
+        .title("Confirm command")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Run".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            let _ = answer.send(confirmed);
+        });
+    answered.await.unwrap_or(false)
+}
+
+/// Ask the user to confirm `argv`, and if they do, issue a single-use token
+/// that lets exactly that command run
+pub async fn confirm(
+    app: &AppHandle,
+    state: &ExecutorState,
+    argv: Vec<String>,
+    risk: RiskClass,
+) -> Result<CommandConfirmation, String> {
+    if !ask_user(app, &argv).await {
+        return Err(format!("`{}` was not confirmed", display_argv(&argv)));
+    }
+
+    let token = uuid::Uuid::new_v4().to_string();
+    state
+        .confirmations
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(
+            token.clone(),
+            PendingConfirmation {
+                argv: argv.clone(),
+                expires_at: Instant::now() + CONFIRMATION_TTL,
+            },
+        );
+
+    Ok(CommandConfirmation {
+        token,
+        argv,
+        risk,
+        expires_in_secs: CONFIRMATION_TTL.as_secs(),
+    })
+}
+
+/// Ask the user, in a native dialog, to confirm one destructive command,
+/// and issue a single-use token that lets it run if they do
+#[tauri::command]
+pub async fn request_command_confirmation(
+    app: AppHandle,
+    state: State<'_, ExecutorState>,
+    cmd: Option<String>,
+    args: Vec<String>,
+) -> Result<CommandConfirmation, String> {
+    let program = cmd.unwrap_or_else(|| "gt".to_string());
+    let risk = classify(&program, &args)?;
+
+    let mut argv = vec![program];
+    argv.extend(args);
+    confirm(&app, &state, argv, risk).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn risk(program: &str, args: &[&str]) -> Result<RiskClass, String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        classify(program, &args)
+    }
+
+    #[test]
+    fn classifies_allowlisted_commands() {
+        assert_eq!(risk("gt", &["status"]), Ok(RiskClass::ReadOnly));
+        assert_eq!(
+            risk("gt", &["convoy", "list", "--json"]),
+            Ok(RiskClass::ReadOnly)
+        );
+        assert_eq!(
+            risk("gt", &["sling", "gt-1", "rig"]),
+            Ok(RiskClass::Mutating)
+        );
+        assert_eq!(risk("gt", &["stop", "--all"]), Ok(RiskClass::Destructive));
+        assert_eq!(
+            risk("bd", &["list", "--status=open"]),
+            Ok(RiskClass::ReadOnly)
+        );
+        assert_eq!(risk("tmux", &["-V"]), Ok(RiskClass::ReadOnly));
+        assert_eq!(
+            risk("tmux", &["capture-pane", "-p", "-t", "%1", "-S", "-30"]),
+            Ok(RiskClass::ReadOnly)
+        );
+        assert_eq!(
+            risk("git", &["branch", "-D", "x"]),
+            Ok(RiskClass::Destructive)
+        );
+        assert_eq!(risk("git", &["diff", "--stat"]), Ok(RiskClass::ReadOnly));
+        assert!(risk("sh", &["-c", "rm -rf /"]).is_err());
+        assert!(risk("git", &["-c", "core.pager=sh", "log"]).is_err());
+    }
+
+    #[test]
+    fn refuses_unlisted_flags() {
+        assert!(risk("gt", &["mail", "send", "x", "--all"]).is_err());
+        assert!(risk("git", &["fetch", "--upload-pack=touch /tmp/x", "origin"]).is_err());
+        assert!(risk("git", &["fetch", "--upload-pack", "touch /tmp/x"]).is_err());
+        assert!(risk("git", &["pull", "--upload-pack=touch /tmp/x"]).is_err());
+        assert!(risk("git", &["rebase", "--exec", "touch /tmp/x", "main"]).is_err());
+        assert!(risk("git", &["rebase", "-x", "touch /tmp/x", "main"]).is_err());
+        assert!(risk("git", &["log", "--output=/tmp/x"]).is_err());
+        assert!(risk("git", &["diff", "--output", "/tmp/x"]).is_err());
+        // Combined short flags are checked one by one
+        assert!(risk("git", &["clean", "-fdi"]).is_err());
+        assert_eq!(risk("git", &["clean", "-fd"]), Ok(RiskClass::Destructive));
+    }
+
+    #[test]
+    fn takes_arguments_after_double_dash_as_text() {
+        assert_eq!(
+            risk("gt", &["nudge", "gastown/polecats/toast", "--", "--all -f"]),
+            Ok(RiskClass::Mutating)
+        );
+        assert!(risk("gt", &["nudge", "gastown/polecats/toast", "--all"]).is_err());
+    }
+
+    #[test]
+    fn refuses_tmux_commands_that_start_programs() {
+        assert!(risk("tmux", &["new-session", "-d", "touch /tmp/x"]).is_err());
+        assert!(risk("tmux", &["new-window", "touch /tmp/x"]).is_err());
+        assert!(risk("tmux", &["send-keys", "-t", "%1", "rm -rf ~", "Enter"]).is_err());
+        assert!(risk("tmux", &["set-buffer", "x"]).is_err());
+        assert!(risk("tmux", &["paste-buffer", "-t", "%1"]).is_err());
+    }
+
+    #[test]
+    fn refuses_tmux_shell_formats_and_command_chains() {
+        assert!(risk("tmux", &["list-sessions", "-F", "#(touch /tmp/x)"]).is_err());
+        assert!(risk("tmux", &["display-message", "-p", "#(touch /tmp/x)"]).is_err());
+        assert!(risk("tmux", &["list-sessions", ";", "kill-server"]).is_err());
+        assert!(risk("tmux", &["list-sessions", "-F", "x;", "kill-server"]).is_err());
+        assert_eq!(
+            risk("tmux", &["list-sessions", "-F", "#{session_name}"]),
+            Ok(RiskClass::ReadOnly)
+        );
+    }
+
+    #[test]
+    fn splits_git_remote_by_subcommand() {
+        assert_eq!(risk("git", &["remote", "-v"]), Ok(RiskClass::ReadOnly));
+        assert_eq!(
+            risk("git", &["remote", "get-url", "origin"]),
+            Ok(RiskClass::ReadOnly)
+        );
+        assert_eq!(
+            risk("git", &["remote", "add", "evil", "https://example.com"]),
+            Ok(RiskClass::Mutating)
+        );
+        assert_eq!(
+            risk(
+                "git",
+                &["remote", "set-url", "origin", "https://example.com"]
+            ),
+            Ok(RiskClass::Mutating)
+        );
+        assert_eq!(
+            risk("git", &["remote", "remove", "origin"]),
+            Ok(RiskClass::Destructive)
+        );
+        // A group word doesn't pass on its own with an unlisted subcommand
+        assert!(risk("git", &["remote", "update"]).is_err());
+        assert!(risk("git", &["stash", "unknown"]).is_err());
+    }
+
+    #[test]
+    fn escalates_history_and_work_losing_git_commands() {
+        let destructive = [
+            &["stash", "drop"][..],
+            &["stash", "clear"],
+            &["checkout", "--", "."],
+            &["checkout", "."],
+            &["checkout", "main", "--", "src/lib.rs"],
+            &["checkout", "src/lib.rs"],
+            &["checkout", "main", "README"],
+            &["checkout", "*.rs"],
+            &["push", "origin", "+main"],
+            &["push", "origin", ":main"],
+            &["push", "--force-with-lease", "origin", "main"],
+            &["push", "--force-with-lease=main", "origin", "main"],
+            &["push", "-fu", "origin", "main"],
+            &["fetch", "origin", "+main:main"],
+            &["commit", "--amend", "--no-edit"],
+        ];
+        for args in destructive {
+            assert_eq!(risk("git", args), Ok(RiskClass::Destructive), "{:?}", args);
+        }
+        assert_eq!(risk("git", &["checkout", "main"]), Ok(RiskClass::Mutating));
+        assert_eq!(
+            risk("git", &["checkout", "-b", "feature/x", "v1.2"]),
+            Ok(RiskClass::Mutating)
+        );
+        assert_eq!(
+            risk("git", &["push", "-u", "origin", "main"]),
+            Ok(RiskClass::Mutating)
+        );
+        assert_eq!(risk("git", &["stash"]), Ok(RiskClass::Mutating));
+        assert_eq!(risk("git", &["stash", "list"]), Ok(RiskClass::ReadOnly));
+    }
+}
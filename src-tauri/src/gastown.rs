@@ -1,6 +1,18 @@
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::process::Command;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::Duration;
+
+use tauri::{AppHandle, State};
+
+use crate::audit::{self, AuditEntry, CommandOrigin};
+use crate::beads::{ActivityEvent, BeadsIndex, BeadsStoreState};
+use crate::client::{BdClient, CliError, GtClient};
+use crate::executor::{self, ExecutorState, RiskClass};
+use crate::health::{self, HealthSignal, HealthState};
+use crate::runner::{self, ProcessOutput, RunnerState};
+use crate::terminal::{self, TerminalState};
+use crate::towns;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CommandResult {
@@ -71,30 +83,130 @@ pub struct TmuxPane {
 pub struct TmuxSessionDetail {
     pub session: TmuxSession,
     pub panes: Vec<TmuxPane>,
-    pub health: String,
+    pub health: HealthState,
+    /// Why the session got its health
+    pub health_signals: Vec<HealthSignal>,
     pub connection_string: String,
 }
 
-/// Run a gt or bd command and return the output
+/// Run an allowlisted gt, bd, tmux or git command and return the output.
+/// `cmd` defaults to gt. Destructive commands need a `confirmation_token`
+/// from `request_command_confirmation`.
+///
+/// Pass a `job_id` to stream output lines on `command-output` and to be able
+/// to cancel the command with `cancel_command`. The command is killed after
+/// `timeout_secs` (default 60s).
+///
+/// Every request, refused or run, is written to the audit log under
+/// `origin` (default UI).
+///
+/// The command runs in `town` (default the active town).
 #[tauri::command]
-pub async fn run_gt_command(cmd: String, args: Vec<String>) -> Result<CommandResult, String> {
-    let output = Command::new(&cmd)
-        .args(&args)
-        .output()
-        .map_err(|e| format!("Failed to execute command: {}", e))?;
+#[allow(clippy::too_many_arguments)]
+pub async fn run_gt_command(
+    app: AppHandle,
+    executor_state: State<'_, ExecutorState>,
+    runner_state: State<'_, RunnerState>,
+    cmd: Option<String>,
+    args: Vec<String>,
+    confirmation_token: Option<String>,
+    job_id: Option<String>,
+    timeout_secs: Option<u64>,
+    origin: Option<CommandOrigin>,
+    town: Option<String>,
+) -> Result<CommandResult, String> {
+    let program = cmd.unwrap_or_else(|| "gt".to_string());
+    let timeout = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(runner::DEFAULT_TIMEOUT);
+    let town = towns::selected_root(&app, town.as_deref())?;
+    let output = execute(
+        &app,
+        &executor_state,
+        &runner_state,
+        origin.unwrap_or_default(),
+        &program,
+        &args,
+        town.as_deref(),
+        confirmation_token.as_deref(),
+        job_id.as_deref(),
+        timeout,
+    )
+    .await?;
 
     Ok(CommandResult {
-        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-        exit_code: output.status.code().unwrap_or(-1),
+        stdout: output.stdout,
+        stderr: output.stderr,
+        exit_code: output.exit_code,
     })
 }
 
-/// Read a beads file (issues.jsonl or interactions.jsonl)
-#[tauri::command]
-pub async fn read_beads_file(path: String) -> Result<String, String> {
-    std::fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read beads file {}: {}", path, e))
+/// Classify, authorize and run an allowlisted command in `town`, auditing
+/// refusals and every run
+#[allow(clippy::too_many_arguments)]
+pub async fn execute(
+    app: &AppHandle,
+    executor_state: &ExecutorState,
+    runner_state: &RunnerState,
+    origin: CommandOrigin,
+    program: &str,
+    args: &[String],
+    town: Option<&Path>,
+    confirmation_token: Option<&str>,
+    job_id: Option<&str>,
+    timeout: Duration,
+) -> Result<ProcessOutput, String> {
+    let risk = match executor::classify(program, args) {
+        Ok(risk) => risk,
+        Err(e) => {
+            let mut argv = vec![program.to_string()];
+            argv.extend(args.iter().cloned());
+            audit::record(app, &AuditEntry::denied(origin, argv, None, &e));
+            return Err(e);
+        }
+    };
+    execute_as(
+        app,
+        executor_state,
+        runner_state,
+        origin,
+        program,
+        args,
+        risk,
+        town,
+        confirmation_token,
+        job_id,
+        timeout,
+    )
+    .await
+}
+
+/// `execute` for a command already classified, e.g. one the caller knows
+/// is riskier than the allowlist says
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_as(
+    app: &AppHandle,
+    executor_state: &ExecutorState,
+    runner_state: &RunnerState,
+    origin: CommandOrigin,
+    program: &str,
+    args: &[String],
+    risk: RiskClass,
+    town: Option<&Path>,
+    confirmation_token: Option<&str>,
+    job_id: Option<&str>,
+    timeout: Duration,
+) -> Result<ProcessOutput, String> {
+    let mut argv = vec![program.to_string()];
+    argv.extend(args.iter().cloned());
+
+    if let Err(e) = executor_state.authorize(&argv, risk, confirmation_token) {
+        audit::record(app, &AuditEntry::denied(origin, argv, Some(risk), &e));
+        return Err(e);
+    }
+
+    let run = runner_state.run_job(app, job_id, program, args, town, timeout);
+    audit::run_recorded(app, origin, argv, Some(risk), run).await
 }
 
 /// List all tmux sessions
@@ -178,18 +290,27 @@ pub async fn get_tmux_panes(session_name: String) -> Result<Vec<TmuxPane>, Strin
     Ok(panes)
 }
 
+/// Capture a pane's text. With `escapes`, colors and styling are kept as
+/// escape sequences; see `tmux::capture_tmux_pane_styled` for more.
 #[tauri::command]
-pub async fn capture_tmux_pane(target: String, lines: Option<i32>) -> Result<String, String> {
+pub async fn capture_tmux_pane(
+    target: String,
+    lines: Option<i32>,
+    escapes: Option<bool>,
+) -> Result<String, String> {
     let line_count = lines.unwrap_or(30).max(1);
+    let mut args = vec!["capture-pane".to_string(), "-p".to_string()];
+    if escapes.unwrap_or(false) {
+        args.push("-e".to_string());
+    }
+    args.extend([
+        "-t".to_string(),
+        target,
+        "-S".to_string(),
+        format!("-{}", line_count),
+    ]);
     let output = Command::new("tmux")
-        .args([
-            "capture-pane",
-            "-p",
-            "-t",
-            &target,
-            "-S",
-            &format!("-{}", line_count),
-        ])
+        .args(&args)
         .output()
         .map_err(|e| format!("Failed to capture tmux pane: {}", e))?;
 
@@ -201,30 +322,11 @@ pub async fn capture_tmux_pane(target: String, lines: Option<i32>) -> Result<Str
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-fn session_health(session: &TmuxSession, _panes: &[TmuxPane]) -> String {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs() as i64;
-
-    let delta = session
-        .activity
-        .map(|last| now.saturating_sub(last))
-        .unwrap_or(i64::MAX);
-
-    if delta < 10 {
-        return "active".to_string();
-    }
-
-    if delta < 60 {
-        return "processing".to_string();
-    }
-
-    "idle".to_string()
-}
-
 #[tauri::command]
-pub async fn get_session_details(session_name: String) -> Result<TmuxSessionDetail, String> {
+pub async fn get_session_details(
+    app: AppHandle,
+    session_name: String,
+) -> Result<TmuxSessionDetail, String> {
     let sessions = list_tmux_sessions().await?;
     let session = sessions
         .into_iter()
@@ -232,29 +334,52 @@ pub async fn get_session_details(session_name: String) -> Result<TmuxSessionDeta
         .ok_or_else(|| "Tmux session not found".to_string())?;
 
     let panes = get_tmux_panes(session.name.clone()).await.unwrap_or_default();
-    let health = session_health(&session, &panes);
+    let samples = health::sample(Some(&session.name)).await?;
+    let report = health::record(&app, &samples)
+        .into_iter()
+        .find(|r| r.session == session.name)
+        .ok_or_else(|| "Tmux session not found".to_string())?;
 
     Ok(TmuxSessionDetail {
         session: session.clone(),
         panes,
-        health,
+        health: report.state,
+        health_signals: report.signals,
         connection_string: format!("tmux attach -t {}", session.name),
     })
 }
 
+/// Open a terminal attached to the session. A terminal chosen with
+/// `set_terminal_config` is used on any platform; otherwise Linux picks an
+/// installed one, macOS uses Terminal and Windows uses cmd. Those two run
+/// the attach through a command line, so they only take plain session names.
 #[tauri::command]
-pub async fn attach_tmux_session(session_name: String) -> Result<(), String> {
-    let attach_cmd = format!("tmux attach -t {}", session_name);
+pub async fn attach_tmux_session(
+    terminal_state: State<'_, TerminalState>,
+    session_name: String,
+) -> Result<(), String> {
+    let config = terminal_state.config()?;
+    if config.launcher.is_some() {
+        return terminal::launch(&config, &session_name);
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    if !terminal::is_plain_session_name(&session_name) {
+        return Err(format!(
+            "Can't attach to {:?} from here; choose a terminal in the settings to attach to it",
+            session_name
+        ));
+    }
 
     #[cfg(target_os = "macos")]
     {
-        let escaped = attach_cmd.replace('"', "\\\"");
+        let attach_cmd = format!("tmux attach -t {}", session_name);
         Command::new("osascript")
             .args([
                 "-e",
                 &format!(
                     "tell application \"Terminal\" to do script \"{}\"",
-                    escaped
+                    attach_cmd
                 ),
                 "-e",
                 "tell application \"Terminal\" to activate",
@@ -266,16 +391,12 @@ pub async fn attach_tmux_session(session_name: String) -> Result<(), String> {
 
     #[cfg(target_os = "linux")]
     {
-        Command::new("sh")
-            .arg("-c")
-            .arg(format!("x-terminal-emulator -e {}", attach_cmd))
-            .output()
-            .map_err(|e| format!("Failed to launch terminal: {}", e))?;
-        return Ok(());
+        return terminal::launch(&config, &session_name);
     }
 
     #[cfg(target_os = "windows")]
     {
+        let attach_cmd = format!("tmux attach -t {}", session_name);
         Command::new("cmd")
             .args(["/C", "start", "cmd", "/K", &attach_cmd])
             .output()
@@ -284,266 +405,128 @@ pub async fn attach_tmux_session(session_name: String) -> Result<(), String> {
     }
 }
 
-/// Get molecule progress for a given root issue
+/// Assign a bead to a rig or agent. Audited under `origin` (default UI).
 #[tauri::command]
-pub async fn get_molecule_progress(issue_id: String) -> Result<Molecule, String> {
-    let output = Command::new("gt")
-        .args(["mol", "progress", &issue_id, "--json"])
-        .output()
-        .map_err(|e| format!("Failed to get molecule progress: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Failed to get molecule: {}", stderr));
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse molecule JSON: {}", e))
+pub async fn sling_bead(
+    app: AppHandle,
+    bead_id: String,
+    target: String,
+    origin: Option<CommandOrigin>,
+    town: Option<String>,
+) -> Result<(), String> {
+    let argv = vec![
+        "gt".to_string(),
+        "sling".to_string(),
+        "--".to_string(),
+        bead_id.clone(),
+        target.clone(),
+    ];
+    let gt = towns::gt(&app, town.as_deref())?;
+    audit::run_cli_recorded(&app, origin.unwrap_or_default(), argv, gt.sling(&bead_id, &target))
+        .await
+        .map_err(String::from)
 }
 
-// ===== Activity Feed =====
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ActivityEvent {
-    pub timestamp: String,
-    pub event_type: String,      // "created", "closed", "updated", "merged", "claimed"
-    pub actor: Option<String>,   // Who did it
-    pub target_id: String,       // Issue ID
-    pub target_title: String,    // Issue title
-    pub details: Option<String>, // Extra info like close_reason
+/// Send a message to an agent's session. Audited under `origin` (default UI).
+#[tauri::command]
+pub async fn nudge_agent(
+    app: AppHandle,
+    target: String,
+    message: String,
+    origin: Option<CommandOrigin>,
+    town: Option<String>,
+) -> Result<(), String> {
+    let argv = vec![
+        "gt".to_string(),
+        "nudge".to_string(),
+        target.clone(),
+        "--".to_string(),
+        message.clone(),
+    ];
+    let gt = towns::gt(&app, town.as_deref())?;
+    audit::run_cli_recorded(&app, origin.unwrap_or_default(), argv, gt.nudge(&target, &message))
+        .await
+        .map_err(String::from)
 }
 
-#[derive(Debug, Deserialize)]
-struct BeadsIssue {
-    id: String,
-    title: String,
-    status: String,
-    #[serde(default)]
-    issue_type: Option<String>,
-    #[serde(default)]
-    assignee: Option<String>,
-    created_at: String,
-    created_by: Option<String>,
-    updated_at: String,
-    #[serde(default)]
-    closed_at: Option<String>,
-    #[serde(default)]
-    close_reason: Option<String>,
+/// Get molecule progress for a given root issue
+#[tauri::command]
+pub async fn get_molecule_progress(
+    app: AppHandle,
+    issue_id: String,
+    town: Option<String>,
+) -> Result<Molecule, String> {
+    towns::gt(&app, town.as_deref())?
+        .mol_progress(&issue_id)
+        .await
+        .map_err(|e| format!("Failed to get molecule progress: {}", e))
 }
 
-/// Find the beads directory, following redirect if present
-fn find_beads_dir() -> Option<std::path::PathBuf> {
-    // Try current directory first
-    let cwd = std::env::current_dir().ok()?;
-
-    // Look for .beads in current dir or parent dirs
-    let mut dir = cwd.as_path();
-    loop {
-        let beads_dir = dir.join(".beads");
-        if beads_dir.exists() {
-            // Check for redirect
-            let redirect_file = beads_dir.join("redirect");
-            if redirect_file.exists() {
-                if let Ok(redirect_path) = std::fs::read_to_string(&redirect_file) {
-                    let redirect_path = redirect_path.trim();
-                    // Handle relative redirects
-                    let target = if redirect_path.starts_with('/') {
-                        std::path::PathBuf::from(redirect_path)
-                    } else {
-                        beads_dir.join(redirect_path)
-                    };
-                    if target.exists() {
-                        return Some(target);
-                    }
-                }
-            }
-            return Some(beads_dir);
-        }
-
-        // Move up to parent
-        match dir.parent() {
-            Some(parent) => dir = parent,
-            None => break,
-        }
-    }
-
-    // Try ~/gt/.beads as fallback
-    if let Some(home) = dirs::home_dir() {
-        let gt_beads = home.join("gt").join(".beads");
-        if gt_beads.exists() {
-            return Some(gt_beads);
-        }
-    }
+// ===== Activity Feed =====
 
-    None
+/// One page of the activity feed, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityPage {
+    pub events: Vec<ActivityEvent>,
+    /// Pass back as `cursor` to fetch the next (older) page
+    pub next_cursor: Option<String>,
 }
 
-/// Get activity feed from beads issues.jsonl (direct file read)
+/// Get activity feed from the recorded beads history, newest first
 #[tauri::command]
-pub async fn get_activity_feed(limit: Option<usize>) -> Result<Vec<ActivityEvent>, String> {
+pub async fn get_activity_feed(
+    app: AppHandle,
+    state: State<'_, BeadsStoreState>,
+    limit: Option<usize>,
+    cursor: Option<String>,
+    town: Option<String>,
+) -> Result<ActivityPage, String> {
     let max_events = limit.unwrap_or(50);
-    let mut events: Vec<ActivityEvent> = Vec::new();
-
-    // Find beads directory
-    let beads_dir = find_beads_dir()
-        .ok_or_else(|| "Could not find .beads directory".to_string())?;
+    let beads_dir = towns::beads_dir(&app, town.as_deref(), None)?;
 
-    // First try interactions.jsonl (preferred for real-time events)
-    let interactions_path = beads_dir.join("interactions.jsonl");
-    let issues_path = beads_dir.join("issues.jsonl");
-
-    // Read issues.jsonl to generate events
-    if issues_path.exists() {
-        let content = std::fs::read_to_string(&issues_path)
-            .map_err(|e| format!("Failed to read issues.jsonl: {}", e))?;
-
-        for line in content.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
-
-            let issue: BeadsIssue = match serde_json::from_str(line) {
-                Ok(i) => i,
-                Err(_) => continue, // Skip malformed lines
-            };
-
-            // Skip certain event types that clutter the feed
-            let issue_type = issue.issue_type.as_deref().unwrap_or("");
-            if issue_type == "event" {
-                continue; // Skip session-ended events etc
-            }
-
-            // Generate events from issue lifecycle
-
-            // 1. Closed event (most important - work completed)
-            if issue.status == "closed" {
-                if let Some(ref closed_at) = issue.closed_at {
-                    events.push(ActivityEvent {
-                        timestamp: closed_at.clone(),
-                        event_type: "closed".to_string(),
-                        actor: issue.assignee.clone().or_else(|| issue.created_by.clone()),
-                        target_id: issue.id.clone(),
-                        target_title: issue.title.clone(),
-                        details: issue.close_reason.clone(),
-                    });
-                }
-            }
-
-            // 2. Merged event (for merge-requests)
-            if issue_type == "merge-request" && issue.status == "closed" {
-                if let Some(ref closed_at) = issue.closed_at {
-                    events.push(ActivityEvent {
-                        timestamp: closed_at.clone(),
-                        event_type: "merged".to_string(),
-                        actor: Some("refinery".to_string()),
-                        target_id: issue.id.clone(),
-                        target_title: issue.title.clone(),
-                        details: None,
-                    });
-                }
-            }
-
-            // 3. Claimed event (when assigned)
-            if issue.assignee.is_some() && issue.status == "in_progress" {
-                events.push(ActivityEvent {
-                    timestamp: issue.updated_at.clone(),
-                    event_type: "claimed".to_string(),
-                    actor: issue.assignee.clone(),
-                    target_id: issue.id.clone(),
-                    target_title: issue.title.clone(),
-                    details: None,
-                });
-            }
+    state.with_index(&app, &beads_dir, |index| {
+        activity_page(index, cursor.as_deref(), max_events)
+    })
+}
 
-            // 4. Created event
-            events.push(ActivityEvent {
-                timestamp: issue.created_at.clone(),
-                event_type: "created".to_string(),
-                actor: issue.created_by.clone(),
-                target_id: issue.id.clone(),
-                target_title: issue.title.clone(),
-                details: None,
-            });
-        }
+/// A page of an index's history, newest first
+pub fn activity_page(index: &BeadsIndex, cursor: Option<&str>, limit: usize) -> ActivityPage {
+    let (events, next_cursor) = index.history_page(cursor, limit);
+    ActivityPage {
+        events,
+        next_cursor,
     }
-
-    // Also check interactions.jsonl for additional events (if it has content)
-    if interactions_path.exists() {
-        if let Ok(content) = std::fs::read_to_string(&interactions_path) {
-            if !content.trim().is_empty() {
-                for line in content.lines() {
-                    if line.trim().is_empty() {
-                        continue;
-                    }
-                    if let Ok(event) = serde_json::from_str::<ActivityEvent>(line) {
-                        events.push(event);
-                    }
-                }
-            }
-        }
-    }
-
-    // Sort by timestamp descending (most recent first)
-    events.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-
-    // Deduplicate by (timestamp, event_type, target_id)
-    events.dedup_by(|a, b| {
-        a.timestamp == b.timestamp && a.event_type == b.event_type && a.target_id == b.target_id
-    });
-
-    // Limit results
-    events.truncate(max_events);
-
-    Ok(events)
 }
 
 /// List all active molecules (workflows in progress)
 #[tauri::command]
-pub async fn list_active_molecules() -> Result<Vec<Molecule>, String> {
-    // Get molecules by looking for in_progress beads with molecule attachments
-    let output = Command::new("bd")
-        .args(["list", "--json", "--status=in_progress", "--type=molecule"])
-        .output()
-        .map_err(|e| format!("Failed to list molecules: {}", e))?;
-
-    if !output.status.success() {
-        // If command fails, return empty list (no molecules)
-        return Ok(vec![]);
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    if stdout.trim().is_empty() {
-        return Ok(vec![]);
-    }
+pub async fn list_active_molecules(
+    app: AppHandle,
+    town: Option<String>,
+) -> Result<Vec<Molecule>, String> {
+    let town = town.as_deref();
+    active_molecules(&towns::bd(&app, town)?, &towns::gt(&app, town)?).await
+}
 
-    // Parse the JSON output - it may be a list of issues
-    let issues: Vec<serde_json::Value> = serde_json::from_str(&stdout)
-        .unwrap_or_else(|_| vec![]);
+/// Molecules attached to in-progress beads
+pub async fn active_molecules(
+    bd: &impl BdClient,
+    gt: &impl GtClient,
+) -> Result<Vec<Molecule>, String> {
+    let issues = match bd.list(Some("in_progress"), Some("molecule")).await {
+        Ok(issues) => issues,
+        Err(e @ CliError::Run { .. }) => return Err(format!("Failed to list molecules: {}", e)),
+        // If the listing fails, there are no molecules to show
+        Err(_) => return Ok(vec![]),
+    };
 
     // For each issue, try to get its molecule progress
     let mut molecules = Vec::new();
     for issue in issues {
-        if let Some(id) = issue.get("id").and_then(|v| v.as_str()) {
-            if let Ok(mol) = get_molecule_progress_internal(id) {
-                molecules.push(mol);
-            }
+        if let Ok(mol) = gt.mol_progress(&issue.id).await {
+            molecules.push(mol);
         }
     }
 
     Ok(molecules)
 }
-
-fn get_molecule_progress_internal(issue_id: &str) -> Result<Molecule, String> {
-    let output = Command::new("gt")
-        .args(["mol", "progress", issue_id, "--json"])
-        .output()
-        .map_err(|e| format!("Failed to get molecule: {}", e))?;
-
-    if !output.status.success() {
-        return Err("No molecule found".to_string());
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&stdout).map_err(|e| e.to_string())
-}
@@ -0,0 +1,508 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::process::Command;
+
+use crate::tmux::AGENT_SESSION_PREFIX;
+
+/// Event pushed with a `SessionHealthReport` when a session's health changes
+pub const SESSION_HEALTH_CHANNEL: &str = "session-health-changed";
+
+/// How often the monitor samples every session
+const MONITOR_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Output or activity this recent means the session is responding
+const RESPONDING_WITHIN_SECS: i64 = 60;
+
+/// An agent whose screen hasn't changed for this long is stuck
+const STALE_AFTER_SECS: i64 = 10 * 60;
+
+/// This many restarts within the window means the session is cycling
+const RESTART_LIMIT: usize = 3;
+const RESTART_WINDOW_SECS: i64 = 10 * 60;
+
+/// Sessions not seen for this long are forgotten
+const FORGET_AFTER_SECS: i64 = 60 * 60;
+
+/// Lines from the bottom of a pane searched for prompts
+const PROMPT_LINES: usize = 5;
+
+const SHELLS: &[&str] = &[
+    "bash", "zsh", "sh", "fish", "dash", "ksh", "tcsh", "csh", "nu",
+];
+
+const AGENTS: &[&str] = &[
+    "claude",
+    "codex",
+    "gemini",
+    "aider",
+    "amp",
+    "opencode",
+    "cursor-agent",
+    "goose",
+];
+
+/// Text agents and tools show when they're waiting on a person
+const AWAITING_INPUT: &[&str] = &[
+    "(y/n)",
+    "[y/n]",
+    "do you want to",
+    "press enter",
+    "waiting for input",
+    "awaiting input",
+    "continue?",
+    "approve?",
+];
+
+/// Text that means the agent hit an error it won't get past by itself
+const ERROR_PROMPTS: &[&str] = &[
+    "error:",
+    "panic:",
+    "fatal:",
+    "traceback (most recent call last)",
+    "api error",
+    "rate limit",
+    "segmentation fault",
+    "command not found",
+];
+
+/// Health of a tmux session, as the PRD names it, plus idle for sessions
+/// quietly sitting at a shell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    Responding,
+    Idle,
+    Stuck,
+    Cycling,
+}
+
+/// Why a session got the health it has
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HealthSignal {
+    /// Restarted this many times within the restart window
+    Restarts {
+        count: usize,
+    },
+    /// The pane's process is gone
+    DeadProcess {
+        pane_id: String,
+    },
+    ErrorPrompt {
+        pane_id: String,
+        line: String,
+    },
+    AwaitingInput {
+        pane_id: String,
+        line: String,
+    },
+    /// An agent session is back at a shell: the agent exited
+    AgentExited {
+        pane_id: String,
+        command: String,
+    },
+    /// An agent's screen hasn't changed for this long
+    Unchanged {
+        secs: i64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionHealthReport {
+    pub session: String,
+    pub state: HealthState,
+    pub previous: Option<HealthState>,
+    pub signals: Vec<HealthSignal>,
+    /// Seconds since the screen changed or tmux saw activity
+    pub quiet_secs: i64,
+    pub checked_at: i64,
+}
+
+/// One pane, as sampled from tmux
+#[derive(Debug, Clone, Default)]
+pub struct PaneSample {
+    pub pane_id: String,
+    pub command: String,
+    pub pid: u32,
+    /// tmux kept the pane after its process exited, or the pid is gone
+    pub dead: bool,
+    /// The visible screen
+    pub content: String,
+}
+
+/// A session, as sampled from tmux
+#[derive(Debug, Clone, Default)]
+pub struct SessionSample {
+    pub name: String,
+    /// When tmux created the session; a new value means it was restarted
+    pub created: i64,
+    pub activity: Option<i64>,
+    pub panes: Vec<PaneSample>,
+}
+
+/// What the tracker remembers about a pane between samples
+struct PaneHistory {
+    pid: u32,
+    content_hash: u64,
+    changed_at: i64,
+}
+
+#[derive(Default)]
+struct SessionHistory {
+    created: Option<i64>,
+    restarts: VecDeque<i64>,
+    panes: HashMap<String, PaneHistory>,
+    state: Option<HealthState>,
+    seen_at: i64,
+}
+
+/// Follows sessions across samples, to spot screens that stopped changing
+/// and sessions that keep restarting
+#[derive(Default)]
+pub struct HealthTracker {
+    sessions: HashMap<String, SessionHistory>,
+}
+
+impl HealthTracker {
+    /// Judge a session from a new sample taken at `now` (unix seconds)
+    pub fn observe(&mut self, sample: &SessionSample, now: i64) -> SessionHealthReport {
+        // Forget sessions long gone, so a name reused much later starts fresh
+        self.sessions
+            .retain(|_, history| now - history.seen_at <= FORGET_AFTER_SECS);
+        let history = self.sessions.entry(sample.name.clone()).or_default();
+        history.seen_at = now;
+
+        if history
+            .created
+            .is_some_and(|created| created != sample.created)
+        {
+            history.restarts.push_back(now);
+        }
+        history.created = Some(sample.created);
+
+        // A pane whose process changed was respawned
+        let mut last_change = sample.activity;
+        for pane in &sample.panes {
+            let content_hash = hash(&pane.content);
+            match history.panes.get_mut(&pane.pane_id) {
+                Some(known) => {
+                    if known.pid != pane.pid {
+                        history.restarts.push_back(now);
+                        known.pid = pane.pid;
+                    }
+                    if known.content_hash != content_hash {
+                        known.content_hash = content_hash;
+                        known.changed_at = now;
+                    }
+                    last_change = last_change.max(Some(known.changed_at));
+                }
+                None => {
+                    history.panes.insert(
+                        pane.pane_id.clone(),
+                        PaneHistory {
+                            pid: pane.pid,
+                            content_hash,
+                            // Not seen before: go by tmux's activity
+                            changed_at: sample.activity.unwrap_or(now),
+                        },
+                    );
+                }
+            }
+        }
+        let live: HashSet<&str> = sample.panes.iter().map(|p| p.pane_id.as_str()).collect();
+        history.panes.retain(|id, _| live.contains(id.as_str()));
+        history
+            .restarts
+            .retain(|&at| now - at <= RESTART_WINDOW_SECS);
+
+        let quiet_secs = last_change.map(|at| (now - at).max(0)).unwrap_or(0);
+        let (state, signals) = assess(sample, history.restarts.len(), quiet_secs);
+        let previous = history.state.replace(state);
+
+        SessionHealthReport {
+            session: sample.name.clone(),
+            state,
+            previous,
+            signals,
+            quiet_secs,
+            checked_at: now,
+        }
+    }
+}
+
+fn hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether a pane is running a shell rather than a program
+pub fn is_shell(command: &str) -> bool {
+    SHELLS.contains(&command.trim_start_matches('-'))
+}
+
+/// Whether a pane is running a coding agent. Claude Code renames its
+/// process to its version number, so that counts too.
+pub fn is_agent(command: &str) -> bool {
+    AGENTS.contains(&command)
+        || (command.contains('.') && command.chars().all(|c| c.is_ascii_digit() || c == '.'))
+}
+
+/// The first of the last few non-blank lines containing one of `patterns`
+fn find_prompt(content: &str, patterns: &[&str]) -> Option<String> {
+    content
+        .lines()
+        .rev()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .take(PROMPT_LINES)
+        .find(|line| {
+            let lower = line.to_lowercase();
+            patterns.iter().any(|pattern| lower.contains(pattern))
+        })
+        .map(str::to_string)
+}
+
+/// Work out a session's health from what's on its panes, how often it has
+/// restarted and how long it has been quiet
+pub fn assess(
+    sample: &SessionSample,
+    restarts: usize,
+    quiet_secs: i64,
+) -> (HealthState, Vec<HealthSignal>) {
+    let mut signals = Vec::new();
+    if restarts >= RESTART_LIMIT {
+        signals.push(HealthSignal::Restarts { count: restarts });
+    }
+
+    let agent_session = sample.name.starts_with(AGENT_SESSION_PREFIX);
+    let mut runs_agent = false;
+    for pane in &sample.panes {
+        let pane_id = pane.pane_id.clone();
+        if pane.dead {
+            signals.push(HealthSignal::DeadProcess { pane_id });
+            continue;
+        }
+        let agent_pane = is_agent(&pane.command);
+        runs_agent |= agent_pane;
+        // An agent that is still working may well get past an error, and
+        // errors on other panes are just output
+        if agent_pane && quiet_secs >= STALE_AFTER_SECS {
+            if let Some(line) = find_prompt(&pane.content, ERROR_PROMPTS) {
+                signals.push(HealthSignal::ErrorPrompt {
+                    pane_id: pane_id.clone(),
+                    line,
+                });
+            }
+        }
+        if let Some(line) = find_prompt(&pane.content, AWAITING_INPUT) {
+            signals.push(HealthSignal::AwaitingInput {
+                pane_id: pane_id.clone(),
+                line,
+            });
+        }
+        if agent_session && is_shell(&pane.command) {
+            signals.push(HealthSignal::AgentExited {
+                pane_id,
+                command: pane.command.clone(),
+            });
+        }
+    }
+    if (agent_session || runs_agent) && quiet_secs >= STALE_AFTER_SECS {
+        signals.push(HealthSignal::Unchanged { secs: quiet_secs });
+    }
+
+    let state = if restarts >= RESTART_LIMIT {
+        HealthState::Cycling
+    } else if !signals.is_empty() {
+        HealthState::Stuck
+    } else if quiet_secs < RESPONDING_WITHIN_SECS {
+        HealthState::Responding
+    } else {
+        HealthState::Idle
+    };
+    (state, signals)
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Sample every session, or just `session`, from tmux. No tmux server means
+/// no sessions.
+pub async fn sample(session: Option<&str>) -> Result<Vec<SessionSample>, String> {
+    let format = "#{session_name}\t#{session_created}\t#{session_activity}\t#{pane_id}\t#{pane_current_command}\t#{pane_pid}\t#{pane_dead}";
+    let mut args = vec!["list-panes", "-F", format];
+    match session {
+        Some(session) => args.extend(["-s", "-t", session]),
+        None => args.push("-a"),
+    }
+    let output = Command::new("tmux")
+        .args(&args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to list tmux panes: {}", e))?;
+    if !output.status.success() {
+        return Ok(vec![]);
+    }
+
+    let mut sessions: Vec<SessionSample> = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() < 7 {
+            continue;
+        }
+        let pane = PaneSample {
+            pane_id: parts[3].to_string(),
+            command: parts[4].to_string(),
+            pid: parts[5].parse().unwrap_or(0),
+            dead: parts[6] == "1",
+            content: String::new(),
+        };
+        match sessions.iter_mut().find(|s| s.name == parts[0]) {
+            Some(session) => session.panes.push(pane),
+            None => sessions.push(SessionSample {
+                name: parts[0].to_string(),
+                created: parts[1].parse().unwrap_or(0),
+                activity: parts[2].parse().ok(),
+                panes: vec![pane],
+            }),
+        }
+    }
+
+    let pids: Vec<u32> = sessions
+        .iter()
+        .flat_map(|s| s.panes.iter().map(|p| p.pid))
+        .filter(|&pid| pid > 0)
+        .collect();
+    let running = running_pids(&pids).await;
+    for pane in sessions.iter_mut().flat_map(|s| s.panes.iter_mut()) {
+        if running
+            .as_ref()
+            .is_some_and(|running| !running.contains(&pane.pid))
+        {
+            pane.dead = true;
+        }
+        if let Ok(output) = Command::new("tmux")
+            .args(["capture-pane", "-p", "-t", &pane.pane_id])
+            .output()
+            .await
+        {
+            pane.content = String::from_utf8_lossy(&output.stdout).into_owned();
+        }
+    }
+    Ok(sessions)
+}
+
+/// Which of `pids` are still running, per `ps`; None if `ps` can't say
+async fn running_pids(pids: &[u32]) -> Option<HashSet<u32>> {
+    if pids.is_empty() {
+        return Some(HashSet::new());
+    }
+    let list = pids
+        .iter()
+        .map(|pid| pid.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    // ps exits non-zero when some pids are gone, so only a failure to run
+    // it means we can't tell
+    let output = Command::new("ps")
+        .args(["-o", "pid=", "-p", &list])
+        .output()
+        .await
+        .ok()?;
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .filter_map(|pid| pid.parse().ok())
+            .collect(),
+    )
+}
+
+/// The tracker, and the latest report per session
+#[derive(Default)]
+pub struct SessionHealthState {
+    tracker: Mutex<HealthTracker>,
+    reports: Mutex<HashMap<String, SessionHealthReport>>,
+}
+
+/// Judge sampled sessions, remember the reports and push the ones whose
+/// health changed
+pub fn record(app: &AppHandle, samples: &[SessionSample]) -> Vec<SessionHealthReport> {
+    let state = app.state::<SessionHealthState>();
+    let now = now_secs();
+    let reports: Vec<SessionHealthReport> = {
+        let Ok(mut tracker) = state.tracker.lock() else {
+            return vec![];
+        };
+        samples
+            .iter()
+            .map(|sample| tracker.observe(sample, now))
+            .collect()
+    };
+
+    if let Ok(mut latest) = state.reports.lock() {
+        for report in &reports {
+            latest.insert(report.session.clone(), report.clone());
+        }
+    }
+    for report in &reports {
+        if report.previous != Some(report.state) {
+            let _ = app.emit(SESSION_HEALTH_CHANNEL, report);
+        }
+    }
+    reports
+}
+
+/// Sample every session in the background and push health changes
+pub fn spawn_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match sample(None).await {
+                Ok(samples) => {
+                    let live: HashSet<&str> = samples.iter().map(|s| s.name.as_str()).collect();
+                    if let Ok(mut latest) = app.state::<SessionHealthState>().reports.lock() {
+                        latest.retain(|name, _| live.contains(name.as_str()));
+                    }
+                    record(&app, &samples);
+                }
+                Err(e) => log::warn!("Failed to check tmux session health: {}", e),
+            }
+            tokio::time::sleep(MONITOR_INTERVAL).await;
+        }
+    });
+}
+
+/// Latest health of every tmux session, worst first
+#[tauri::command]
+pub async fn list_session_health(
+    state: State<'_, SessionHealthState>,
+) -> Result<Vec<SessionHealthReport>, String> {
+    let mut reports: Vec<SessionHealthReport> = state
+        .reports
+        .lock()
+        .map_err(|e| e.to_string())?
+        .values()
+        .cloned()
+        .collect();
+    let rank = |state: HealthState| match state {
+        HealthState::Cycling => 0,
+        HealthState::Stuck => 1,
+        HealthState::Responding => 2,
+        HealthState::Idle => 3,
+    };
+    reports.sort_by(|a, b| {
+        rank(a.state)
+            .cmp(&rank(b.state))
+            .then_with(|| a.session.cmp(&b.session))
+    });
+    Ok(reports)
+}
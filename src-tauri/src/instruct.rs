@@ -2,7 +2,10 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Mutex;
-use tauri::State;
+use tauri::{AppHandle, State};
+
+use crate::client::{BdClient, CliError, GtClient};
+use crate::towns;
 
 /// Instruct model state for Deep Query
 pub struct InstructState {
@@ -83,20 +86,15 @@ pub async fn set_instruct_model(
     get_instruct_status(state).await
 }
 
-/// Gather Gas Town context for Deep Query
-async fn gather_gt_context() -> Result<GasTownContext, String> {
-    // Run gt and bd commands to gather current state
-    let convoy_status = run_command("gt", &["convoy", "list"])
-        .unwrap_or_else(|e| format!("Failed to get convoy status: {}", e));
-
-    let ready_work = run_command("bd", &["ready"])
-        .unwrap_or_else(|e| format!("Failed to get ready work: {}", e));
+/// Gather Gas Town context for Deep Query, as JSON the model can read
+async fn gather_gt_context(app: &AppHandle, town: Option<&str>) -> Result<GasTownContext, String> {
+    let gt = towns::gt(app, town)?;
+    let bd = towns::bd(app, town)?;
 
-    let polecat_status = run_command("gt", &["polecat", "list"])
-        .unwrap_or_else(|e| format!("Failed to get polecat status: {}", e));
-
-    let blocked_issues = run_command("bd", &["blocked"])
-        .unwrap_or_else(|e| format!("Failed to get blocked issues: {}", e));
+    let convoy_status = describe(gt.convoy_list().await, "convoy status");
+    let ready_work = describe(bd.ready().await, "ready work");
+    let polecat_status = describe(gt.polecat_list(None).await, "polecat status");
+    let blocked_issues = describe(bd.blocked().await, "blocked issues");
 
     Ok(GasTownContext {
         convoy_status,
@@ -106,31 +104,23 @@ async fn gather_gt_context() -> Result<GasTownContext, String> {
     })
 }
 
-fn run_command(cmd: &str, args: &[&str]) -> Result<String, String> {
-    let output = Command::new(cmd)
-        .args(args)
-        .output()
-        .map_err(|e| format!("Failed to run {} {:?}: {}", cmd, args, e))?;
-
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        // Return stdout anyway if there's output, some commands write to stdout even on "failure"
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        if !stdout.is_empty() {
-            Ok(stdout.to_string())
-        } else {
-            Err(format!("Command failed: {}", stderr))
-        }
+/// Render a CLI result for the prompt
+fn describe<T: Serialize>(result: Result<T, CliError>, what: &str) -> String {
+    match result {
+        Ok(value) => serde_json::to_string_pretty(&value)
+            .unwrap_or_else(|e| format!("Failed to format {}: {}", what, e)),
+        Err(e) => format!("Failed to get {}: {}", what, e),
     }
 }
 
-/// Run Deep Query - analyze Gas Town state with local LLM
+/// Run Deep Query - analyze the state of `town` (default the active town)
+/// with local LLM
 #[tauri::command]
 pub async fn query_deep(
+    app: AppHandle,
     state: State<'_, InstructState>,
     query: String,
+    town: Option<String>,
 ) -> Result<DeepQueryResponse, String> {
     let start = std::time::Instant::now();
 
@@ -143,7 +133,7 @@ pub async fn query_deep(
 
     // If no instruct model, use the voice server API for text-only queries
     if model_path.is_none() {
-        return query_via_voice_server(query, start).await;
+        return query_via_voice_server(&app, town.as_deref(), query, start).await;
     }
 
     let model_path = model_path.unwrap();
@@ -152,7 +142,7 @@ pub async fn query_deep(
     }
 
     // Gather Gas Town context
-    let context = gather_gt_context().await?;
+    let context = gather_gt_context(&app, town.as_deref()).await?;
     let context_used = vec![
         "convoy_status".to_string(),
         "ready_work".to_string(),
@@ -226,11 +216,13 @@ Provide a concise, helpful answer based only on the data above. If you cannot an
 
 /// Fallback: Use the voice server for text queries when no instruct model
 async fn query_via_voice_server(
+    app: &AppHandle,
+    town: Option<&str>,
     query: String,
     start: std::time::Instant,
 ) -> Result<DeepQueryResponse, String> {
     // Gather Gas Town context
-    let context = gather_gt_context().await?;
+    let context = gather_gt_context(app, town).await?;
     let context_used = vec![
         "convoy_status".to_string(),
         "ready_work".to_string(),
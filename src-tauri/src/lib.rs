@@ -1,14 +1,44 @@
-mod gastown;
+pub mod gastown;
+pub mod beads;
+pub mod tail;
 mod voice;
 mod self_test;
 mod instruct;
-mod setup;
+pub mod setup;
 mod chunked_download;
+pub mod events;
+pub mod event_filters;
+mod commentary;
+mod digest;
+pub mod event_store;
+pub mod executor;
+pub mod audit;
+pub mod runner;
+pub mod client;
+pub mod convoy;
+pub mod polecats;
+pub mod refinery;
+pub mod rigs;
+pub mod towns;
+pub mod tmux;
+pub mod ansi;
+pub mod health;
+pub mod terminal;
 
+use beads::BeadsStoreState;
 use voice::VoiceServerState;
 use self_test::SelfTestState;
 use instruct::InstructState;
 use chunked_download::DownloadManagerState;
+use events::EventsWatcherState;
+use executor::ExecutorState;
+use runner::RunnerState;
+use convoy::ConvoyState;
+use refinery::RefineryState;
+use towns::TownRegistryState;
+use tmux::TmuxState;
+use health::SessionHealthState;
+use terminal::TerminalState;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -16,10 +46,21 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_dialog::init())
         .manage(VoiceServerState::default())
         .manage(SelfTestState::default())
         .manage(InstructState::default())
         .manage(DownloadManagerState::default())
+        .manage(BeadsStoreState::default())
+        .manage(EventsWatcherState::default())
+        .manage(ExecutorState::default())
+        .manage(RunnerState::default())
+        .manage(ConvoyState::default())
+        .manage(RefineryState::default())
+        .manage(TownRegistryState::default())
+        .manage(TmuxState::default())
+        .manage(SessionHealthState::default())
+        .manage(TerminalState::default())
         .setup(|app| {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
@@ -28,19 +69,62 @@ pub fn run() {
                         .build(),
                 )?;
             }
+            towns::load_registry(app.handle());
+            terminal::load_config(app.handle());
+            events::load_event_settings(app.handle());
+            events::open_event_history(app.handle());
+            events::spawn_auto_start(app.handle().clone());
+            health::spawn_monitor(app.handle().clone());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             gastown::run_gt_command,
-            gastown::read_beads_file,
+            executor::classify_command,
+            executor::request_command_confirmation,
+            runner::cancel_command,
+            runner::list_running_commands,
+            audit::query_audit_log,
+            audit::rerun_audit_entry,
             gastown::list_tmux_sessions,
             gastown::get_session_details,
+            health::list_session_health,
             gastown::get_tmux_panes,
             gastown::capture_tmux_pane,
             gastown::attach_tmux_session,
+            terminal::list_terminal_launchers,
+            terminal::set_terminal_config,
+            tmux::subscribe_tmux_session,
+            tmux::unsubscribe_tmux_session,
+            tmux::send_keys_to_pane,
+            tmux::request_pane_input_confirmation,
+            tmux::set_pane_read_only,
+            tmux::list_read_only_panes,
+            tmux::capture_tmux_pane_styled,
+            gastown::sling_bead,
+            gastown::nudge_agent,
             gastown::get_molecule_progress,
             gastown::list_active_molecules,
             gastown::get_activity_feed,
+            convoy::list_convoys,
+            convoy::get_convoy,
+            polecats::list_polecats,
+            polecats::get_polecat,
+            polecats::nudge_polecat,
+            polecats::restart_polecat,
+            polecats::retire_polecat,
+            polecats::reassign_polecat_bead,
+            refinery::list_merge_queue,
+            refinery::reprioritize_merge_request,
+            refinery::remove_merge_request,
+            rigs::list_rigs,
+            rigs::get_rig,
+            towns::list_towns,
+            towns::add_town,
+            towns::remove_town,
+            towns::set_active_town,
+            beads::query_beads,
+            beads::get_bead,
+            beads::get_bead_dependencies,
             voice::start_voice_server,
             voice::stop_voice_server,
             voice::get_voice_server_status,
@@ -62,12 +146,26 @@ pub fn run() {
             setup::create_workspace,
             setup::get_setup_status,
             setup::check_disk_space,
+            setup::check_homebrew,
+            setup::check_gobin_in_path,
             chunked_download::start_chunked_download,
             chunked_download::pause_download,
             chunked_download::resume_download,
             chunked_download::cancel_download,
             chunked_download::get_download_status,
             chunked_download::list_downloads,
+            events::start_events_watcher,
+            events::stop_events_watcher,
+            events::set_events_verbosity,
+            events::get_events_verbosity,
+            events::get_event_filters,
+            events::set_event_filters,
+            events::get_commentary_config,
+            events::set_commentary_config,
+            events::get_recent_events,
+            events::query_event_history,
+            events::count_event_history,
+            events::is_events_watcher_active,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,437 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+use crate::audit::CommandOrigin;
+use crate::beads::{BeadsStoreState, Issue};
+use crate::client::{Cli, GtClient, Polecat};
+use crate::executor::ExecutorState;
+use crate::gastown::{self, TmuxSession};
+use crate::rigs::rig_beads_dir;
+use crate::runner::{self, RunnerState};
+use crate::towns;
+
+/// Git calls against a worktree should be near-instant
+const GIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A polecat's git worktree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolecatWorktree {
+    pub path: String,
+    pub branch: Option<String>,
+    /// Files with uncommitted changes
+    pub changed_files: usize,
+}
+
+/// The bead on a polecat's hook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookedBead {
+    pub id: String,
+    pub title: String,
+    /// Bead status, or `unknown` if it isn't in the beads index
+    pub status: String,
+}
+
+/// A polecat joined with its tmux session, worktree and hooked bead
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolecatInfo {
+    pub name: String,
+    pub rig: String,
+    /// Status as reported by gt
+    pub status: String,
+    pub last_activity: Option<String>,
+    pub session: Option<TmuxSession>,
+    pub worktree: Option<PolecatWorktree>,
+    pub hooked_bead: Option<HookedBead>,
+}
+
+/// Action taken on a polecat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolecatAction {
+    Nudge,
+    Restart,
+    Retire,
+    Reassign,
+}
+
+/// Outcome of a polecat action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolecatActionResult {
+    /// `<rig>/<name>`
+    pub polecat: String,
+    pub action: PolecatAction,
+    pub success: bool,
+    pub exit_code: i32,
+    /// gt's output, or its error output when the action failed
+    pub message: String,
+}
+
+/// gt's address for a polecat
+fn address(rig: &str, name: &str) -> String {
+    format!("{}/{}", rig, name)
+}
+
+/// gt names polecat sessions `gt-<rig>-<name>`
+fn session_name(rig: &str, name: &str) -> String {
+    format!("gt-{}-{}", rig, name)
+}
+
+/// Worktree gt creates for a polecat
+fn worktree_path(town: &Path, rig: &str, name: &str) -> PathBuf {
+    town.join(rig).join("polecats").join(name)
+}
+
+/// Whether a bead assignee refers to this polecat. gt writes
+/// `<rig>/polecats/<name>`; older towns use `<rig>/<name>` or the bare name.
+fn is_assigned_to(assignee: &str, rig: &str, name: &str) -> bool {
+    assignee == name
+        || assignee == address(rig, name)
+        || assignee == format!("{}/polecats/{}", rig, name)
+}
+
+/// The bead on a polecat's hook: what gt reports it working on, else a
+/// hooked (or failing that, in-progress) bead assigned to it
+fn hooked_bead(polecat: &Polecat, claimed: &[Issue]) -> Option<HookedBead> {
+    let to_hooked = |issue: &Issue| HookedBead {
+        id: issue.id.clone(),
+        title: issue.title.clone(),
+        status: issue.status.clone(),
+    };
+
+    if let Some(id) = &polecat.current_work {
+        return Some(
+            claimed
+                .iter()
+                .find(|issue| &issue.id == id)
+                .map(to_hooked)
+                .unwrap_or_else(|| HookedBead {
+                    id: id.clone(),
+                    title: String::new(),
+                    status: "unknown".to_string(),
+                }),
+        );
+    }
+
+    let mine: Vec<&Issue> = claimed
+        .iter()
+        .filter(|issue| {
+            issue
+                .assignee
+                .as_deref()
+                .is_some_and(|a| is_assigned_to(a, &polecat.rig, &polecat.name))
+        })
+        .collect();
+    mine.iter()
+        .find(|issue| issue.status == "hooked")
+        .or_else(|| mine.first())
+        .map(|issue| to_hooked(issue))
+}
+
+async fn worktree(path: &Path) -> Option<PolecatWorktree> {
+    if !path.is_dir() {
+        return None;
+    }
+    let dir = path.to_string_lossy();
+    let git = Cli::new("git").with_timeout(GIT_TIMEOUT);
+    let branch = git
+        .run(&["-C", &dir, "rev-parse", "--abbrev-ref", "HEAD"])
+        .await
+        .ok()
+        .map(|output| output.stdout.trim().to_string())
+        .filter(|branch| !branch.is_empty());
+    let changed_files = git
+        .run(&["-C", &dir, "status", "--porcelain"])
+        .await
+        .map(|output| {
+            output
+                .stdout
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .count()
+        })
+        .unwrap_or(0);
+
+    Some(PolecatWorktree {
+        path: dir.to_string(),
+        branch,
+        changed_files,
+    })
+}
+
+/// Join polecats from gt with their tmux sessions, worktrees under `town`
+/// and the beads in `claimed`
+pub async fn join(polecats: Vec<Polecat>, town: &Path, claimed: &[Issue]) -> Vec<PolecatInfo> {
+    let sessions = gastown::list_tmux_sessions().await.unwrap_or_default();
+
+    let mut joined = Vec::with_capacity(polecats.len());
+    for polecat in polecats {
+        let session_name = session_name(&polecat.rig, &polecat.name);
+        joined.push(PolecatInfo {
+            session: sessions.iter().find(|s| s.name == session_name).cloned(),
+            worktree: worktree(&worktree_path(town, &polecat.rig, &polecat.name)).await,
+            hooked_bead: hooked_bead(&polecat, claimed),
+            name: polecat.name,
+            rig: polecat.rig,
+            status: polecat.status,
+            last_activity: polecat.last_activity,
+        });
+    }
+    joined
+}
+
+/// Hooked and in-progress beads from the beads of every rig in `polecats`
+fn claimed_beads(
+    app: &AppHandle,
+    beads_state: &BeadsStoreState,
+    town: &Path,
+    polecats: &[Polecat],
+) -> Vec<Issue> {
+    let mut dirs: Vec<PathBuf> = polecats
+        .iter()
+        .filter_map(|p| rig_beads_dir(town, &p.rig))
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+
+    let mut claimed = Vec::new();
+    for dir in dirs {
+        let issues = beads_state.with_index(app, &dir, |index| {
+            index
+                .issues()
+                .iter()
+                .filter(|issue| matches!(issue.status.as_str(), "hooked" | "in_progress"))
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+        match issues {
+            Ok(issues) => claimed.extend(issues),
+            Err(e) => log::warn!("Failed to read beads in {:?}: {}", dir, e),
+        }
+    }
+    claimed
+}
+
+async fn gather(
+    app: &AppHandle,
+    beads_state: &BeadsStoreState,
+    town: Option<&str>,
+    rig: Option<&str>,
+) -> Result<Vec<PolecatInfo>, String> {
+    let root = towns::root(app, town)?;
+    let polecats = towns::gt(app, town)?
+        .polecat_list(rig)
+        .await
+        .map_err(|e| format!("Failed to list polecats: {}", e))?;
+    let claimed = claimed_beads(app, beads_state, &root, &polecats);
+    Ok(join(polecats, &root, &claimed).await)
+}
+
+/// List the polecats of `town` (default the active town), in one rig or
+/// all, with their sessions, worktrees and hooked beads
+#[tauri::command]
+pub async fn list_polecats(
+    app: AppHandle,
+    beads_state: State<'_, BeadsStoreState>,
+    rig: Option<String>,
+    town: Option<String>,
+) -> Result<Vec<PolecatInfo>, String> {
+    gather(&app, &beads_state, town.as_deref(), rig.as_deref()).await
+}
+
+/// Get one polecat of `town` (default the active town)
+#[tauri::command]
+pub async fn get_polecat(
+    app: AppHandle,
+    beads_state: State<'_, BeadsStoreState>,
+    rig: String,
+    name: String,
+    town: Option<String>,
+) -> Result<PolecatInfo, String> {
+    gather(&app, &beads_state, town.as_deref(), Some(&rig))
+        .await?
+        .into_iter()
+        .find(|polecat| polecat.name == name)
+        .ok_or_else(|| format!("Polecat not found: {}", address(&rig, &name)))
+}
+
+/// Run a polecat action in `town` through the allowlist, so it is
+/// confirmed and audited like any other command
+#[allow(clippy::too_many_arguments)]
+async fn act(
+    app: &AppHandle,
+    executor_state: &ExecutorState,
+    runner_state: &RunnerState,
+    origin: Option<CommandOrigin>,
+    town: Option<&str>,
+    polecat: String,
+    action: PolecatAction,
+    args: Vec<String>,
+    confirmation_token: Option<String>,
+) -> Result<PolecatActionResult, String> {
+    let output = gastown::execute(
+        app,
+        executor_state,
+        runner_state,
+        origin.unwrap_or_default(),
+        "gt",
+        &args,
+        towns::selected_root(app, town)?.as_deref(),
+        confirmation_token.as_deref(),
+        None,
+        runner::DEFAULT_TIMEOUT,
+    )
+    .await?;
+
+    let message = if output.success {
+        output.stdout
+    } else {
+        output.stderr
+    };
+    Ok(PolecatActionResult {
+        polecat,
+        action,
+        success: output.success,
+        exit_code: output.exit_code,
+        message: message.trim().to_string(),
+    })
+}
+
+/// Send a message to a polecat's session
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn nudge_polecat(
+    app: AppHandle,
+    executor_state: State<'_, ExecutorState>,
+    runner_state: State<'_, RunnerState>,
+    rig: String,
+    name: String,
+    message: String,
+    origin: Option<CommandOrigin>,
+    town: Option<String>,
+) -> Result<PolecatActionResult, String> {
+    let polecat = address(&rig, &name);
+    // `--` so a message starting with `-` isn't taken for a flag
+    let args = vec![
+        "nudge".to_string(),
+        polecat.clone(),
+        "--".to_string(),
+        message,
+    ];
+    act(
+        &app,
+        &executor_state,
+        &runner_state,
+        origin,
+        town.as_deref(),
+        polecat,
+        PolecatAction::Nudge,
+        args,
+        None,
+    )
+    .await
+}
+
+/// Restart a polecat's session. Needs a confirmation token.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn restart_polecat(
+    app: AppHandle,
+    executor_state: State<'_, ExecutorState>,
+    runner_state: State<'_, RunnerState>,
+    rig: String,
+    name: String,
+    confirmation_token: Option<String>,
+    origin: Option<CommandOrigin>,
+    town: Option<String>,
+) -> Result<PolecatActionResult, String> {
+    let polecat = address(&rig, &name);
+    let args = vec![
+        "session".to_string(),
+        "restart".to_string(),
+        polecat.clone(),
+    ];
+    act(
+        &app,
+        &executor_state,
+        &runner_state,
+        origin,
+        town.as_deref(),
+        polecat,
+        PolecatAction::Restart,
+        args,
+        confirmation_token,
+    )
+    .await
+}
+
+/// Retire a polecat, removing its session and worktree. Needs a confirmation token.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn retire_polecat(
+    app: AppHandle,
+    executor_state: State<'_, ExecutorState>,
+    runner_state: State<'_, RunnerState>,
+    rig: String,
+    name: String,
+    confirmation_token: Option<String>,
+    origin: Option<CommandOrigin>,
+    town: Option<String>,
+) -> Result<PolecatActionResult, String> {
+    let polecat = address(&rig, &name);
+    let args = vec!["polecat".to_string(), "remove".to_string(), polecat.clone()];
+    act(
+        &app,
+        &executor_state,
+        &runner_state,
+        origin,
+        town.as_deref(),
+        polecat,
+        PolecatAction::Retire,
+        args,
+        confirmation_token,
+    )
+    .await
+}
+
+/// Sling a polecat's bead to another rig or agent. `bead_id` defaults to the
+/// bead on the polecat's hook.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn reassign_polecat_bead(
+    app: AppHandle,
+    beads_state: State<'_, BeadsStoreState>,
+    executor_state: State<'_, ExecutorState>,
+    runner_state: State<'_, RunnerState>,
+    rig: String,
+    name: String,
+    target: String,
+    bead_id: Option<String>,
+    origin: Option<CommandOrigin>,
+    town: Option<String>,
+) -> Result<PolecatActionResult, String> {
+    let polecat = address(&rig, &name);
+    let bead_id = match bead_id {
+        Some(id) => id,
+        None => get_polecat(app.clone(), beads_state, rig, name, town.clone())
+            .await?
+            .hooked_bead
+            .map(|bead| bead.id)
+            .ok_or_else(|| format!("{} has nothing on its hook", polecat))?,
+    };
+    // `--` so a bead ID starting with `-` isn't taken for a flag
+    let args = vec!["sling".to_string(), "--".to_string(), bead_id, target];
+    act(
+        &app,
+        &executor_state,
+        &runner_state,
+        origin,
+        town.as_deref(),
+        polecat,
+        PolecatAction::Reassign,
+        args,
+        None,
+    )
+    .await
+}
@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::audit::CommandOrigin;
+use crate::beads::{BeadsDelta, BeadsIndex, BeadsStoreState};
+use crate::client::{GtClient, MergeRequest};
+use crate::executor::ExecutorState;
+use crate::gastown;
+use crate::rigs::{beads_cwd, rig_beads_dir};
+use crate::runner::{self, RunnerState};
+use crate::towns;
+
+/// Event pushed with a `MergeQueueChange` whenever a rig's queue changes
+pub const MERGE_QUEUE_CHANNEL: &str = "merge-queue-changed";
+
+/// Lowest bead priority
+const MAX_PRIORITY: u8 = 4;
+
+/// A rig's merge queue (or every rig's, when `rig` is none) after a change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeQueueChange {
+    pub rig: Option<String>,
+    pub entries: Vec<MergeRequest>,
+}
+
+/// Last queue pushed per rig, so unchanged refreshes aren't re-sent
+#[derive(Default)]
+pub struct RefineryState {
+    queues: Mutex<HashMap<Option<String>, Vec<MergeRequest>>>,
+}
+
+impl RefineryState {
+    /// Store a queue, returning whether it differs from the last one seen
+    fn remember(&self, rig: Option<&str>, entries: &[MergeRequest]) -> bool {
+        let Ok(mut queues) = self.queues.lock() else {
+            return true;
+        };
+        let key = rig.map(str::to_string);
+        if queues.get(&key).is_some_and(|known| known == entries) {
+            return false;
+        }
+        queues.insert(key, entries.to_vec());
+        true
+    }
+}
+
+/// Fill in what gt left out from each merge request's bead, whose
+/// description carries `branch:`, `worker:`, `conflicts:` and `ci:` lines
+pub fn enrich(entries: &mut [MergeRequest], index: &BeadsIndex) {
+    for entry in entries.iter_mut() {
+        let Some(issue) = index.get(&entry.id) else {
+            continue;
+        };
+        if entry.branch.is_empty() {
+            entry.branch = issue.description_field("branch").unwrap_or_default();
+        }
+        if entry.rig.is_empty() {
+            entry.rig = issue.rig().unwrap_or_default();
+        }
+        entry.priority = entry.priority.or(issue.priority);
+        entry.target = entry
+            .target
+            .take()
+            .or_else(|| issue.description_field("target"));
+        entry.author = entry.author.take().or_else(|| {
+            issue
+                .description_field("worker")
+                .or_else(|| issue.description_field("polecat"))
+        });
+        entry.source_issue = entry
+            .source_issue
+            .take()
+            .or_else(|| issue.description_field("source_issue"));
+        entry.has_conflicts = entry.has_conflicts.or_else(|| {
+            issue
+                .description_field("conflicts")
+                .and_then(|v| v.parse().ok())
+        });
+        entry.ci_status = entry
+            .ci_status
+            .take()
+            .or_else(|| issue.description_field("ci"));
+    }
+}
+
+/// `town`'s queue from gt, enriched from the beads of the rigs it covers
+async fn load_queue(
+    app: &AppHandle,
+    beads_state: &BeadsStoreState,
+    town: Option<&str>,
+    rig: Option<&str>,
+) -> Result<Vec<MergeRequest>, String> {
+    let mut entries = towns::gt(app, town)?
+        .refinery_queue(rig)
+        .await
+        .map_err(|e| format!("Failed to list merge queue: {}", e))?;
+
+    if let Ok(root) = towns::root(app, town) {
+        let rigs: BTreeSet<&str> = match rig {
+            Some(rig) => [rig].into(),
+            None => entries.iter().map(|e| e.rig.as_str()).collect(),
+        };
+        let dirs: BTreeSet<_> = rigs
+            .into_iter()
+            .filter_map(|rig| rig_beads_dir(&root, rig))
+            .collect();
+        for dir in dirs {
+            if let Err(e) = beads_state.with_index(app, &dir, |index| enrich(&mut entries, index)) {
+                log::warn!("Failed to read merge request beads in {:?}: {}", dir, e);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Re-read a queue of `town`, and push it if it's the active town's and it
+/// changed
+async fn refresh(
+    app: &AppHandle,
+    town: Option<&str>,
+    rig: Option<&str>,
+) -> Result<Vec<MergeRequest>, String> {
+    let entries = load_queue(app, &app.state::<BeadsStoreState>(), town, rig).await?;
+    if towns::is_active(app, town) && app.state::<RefineryState>().remember(rig, &entries) {
+        let change = MergeQueueChange {
+            rig: rig.map(str::to_string),
+            entries: entries.clone(),
+        };
+        let _ = app.emit(MERGE_QUEUE_CHANNEL, change);
+    }
+    Ok(entries)
+}
+
+/// Refresh a rig's queue in the background, e.g. after a merge event
+pub fn spawn_refresh(app: &AppHandle, rig: Option<String>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = refresh(&app, None, rig.as_deref()).await {
+            log::warn!("Failed to refresh merge queue: {}", e);
+        }
+    });
+}
+
+/// Refresh the queues of rigs whose merge request beads just changed.
+/// Called by the beads watcher.
+pub fn beads_changed(app: &AppHandle, delta: &BeadsDelta) {
+    let rigs: BTreeSet<Option<String>> = delta
+        .added
+        .iter()
+        .chain(&delta.updated)
+        .filter(|issue| issue.issue_type.as_deref() == Some("merge-request"))
+        .map(|issue| issue.rig())
+        .collect();
+    for rig in rigs {
+        spawn_refresh(app, rig);
+    }
+}
+
+/// List the queued merge requests of `town` (default the active town) in
+/// merge order, with branch, author polecat, conflict and CI state
+#[tauri::command]
+pub async fn list_merge_queue(
+    app: AppHandle,
+    beads_state: State<'_, BeadsStoreState>,
+    rig: Option<String>,
+    town: Option<String>,
+) -> Result<Vec<MergeRequest>, String> {
+    load_queue(&app, &beads_state, town.as_deref(), rig.as_deref()).await
+}
+
+/// Run a change to a queue of `town` in `dir` through the allowlist and
+/// return the refreshed queue
+#[allow(clippy::too_many_arguments)]
+async fn change_queue(
+    app: &AppHandle,
+    executor_state: &ExecutorState,
+    runner_state: &RunnerState,
+    origin: Option<CommandOrigin>,
+    town: Option<&str>,
+    rig: &str,
+    dir: Option<&Path>,
+    program: &str,
+    args: Vec<String>,
+    confirmation_token: Option<&str>,
+) -> Result<Vec<MergeRequest>, String> {
+    let output = gastown::execute(
+        app,
+        executor_state,
+        runner_state,
+        origin.unwrap_or_default(),
+        program,
+        &args,
+        dir,
+        confirmation_token,
+        None,
+        runner::DEFAULT_TIMEOUT,
+    )
+    .await?;
+    if !output.success {
+        return Err(format!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            output.stderr.trim()
+        ));
+    }
+    refresh(app, town, Some(rig)).await
+}
+
+/// Move a merge request up or down its rig's queue by setting its bead's
+/// priority (0-4). The refinery merges higher priorities first. bd runs in
+/// the rig's directory when the rig keeps its own beads.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn reprioritize_merge_request(
+    app: AppHandle,
+    executor_state: State<'_, ExecutorState>,
+    runner_state: State<'_, RunnerState>,
+    rig: String,
+    id: String,
+    priority: u8,
+    origin: Option<CommandOrigin>,
+    town: Option<String>,
+) -> Result<Vec<MergeRequest>, String> {
+    if priority > MAX_PRIORITY {
+        return Err(format!("Priority must be 0-{}", MAX_PRIORITY));
+    }
+    let args = vec![
+        "update".to_string(),
+        id,
+        "--priority".to_string(),
+        priority.to_string(),
+    ];
+    let dir = towns::selected_root(&app, town.as_deref())?.map(|root| beads_cwd(&root, &rig));
+    change_queue(
+        &app,
+        &executor_state,
+        &runner_state,
+        origin,
+        town.as_deref(),
+        &rig,
+        dir.as_deref(),
+        "bd",
+        args,
+        None,
+    )
+    .await
+}
+
+/// Take a merge request out of the queue by rejecting it. Needs a
+/// confirmation token.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn remove_merge_request(
+    app: AppHandle,
+    executor_state: State<'_, ExecutorState>,
+    runner_state: State<'_, RunnerState>,
+    rig: String,
+    id: String,
+    reason: Option<String>,
+    confirmation_token: Option<String>,
+    origin: Option<CommandOrigin>,
+    town: Option<String>,
+) -> Result<Vec<MergeRequest>, String> {
+    let mut args = vec!["refinery".to_string(), "reject".to_string(), id];
+    if let Some(reason) = reason {
+        args.push("--reason".to_string());
+        args.push(reason);
+    }
+    let root = towns::selected_root(&app, town.as_deref())?;
+    change_queue(
+        &app,
+        &executor_state,
+        &runner_state,
+        origin,
+        town.as_deref(),
+        &rig,
+        root.as_deref(),
+        "gt",
+        args,
+        confirmation_token.as_deref(),
+    )
+    .await
+}
@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::beads::{self, BeadsStoreState, Issue};
+use crate::event_store::parse_timestamp;
+use crate::towns;
+
+/// Subdirectories that only a rig has
+const RIG_MARKERS: &[&str] = &["polecats", "refinery", "witness", "crew"];
+
+/// Town-level agent directories, which have beads but aren't rigs
+const TOWN_AGENTS: &[&str] = &["mayor", "deacon"];
+
+/// A rig directory in the town
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rig {
+    pub name: String,
+    pub path: PathBuf,
+    /// Where the rig's beads live, after following any redirect
+    pub beads_dir: Option<PathBuf>,
+    /// Whether other rigs or the town use the same beads, in which case
+    /// beads are attributed by their `rig` field rather than by location
+    pub shared_beads: bool,
+}
+
+/// Per-rig status for the rig view and rig comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RigSummary {
+    pub name: String,
+    pub path: String,
+    pub beads_dir: Option<String>,
+    pub polecat_count: usize,
+    /// Open beads with no open blockers
+    pub ready: usize,
+    /// Open beads waiting on an open blocker, or marked blocked
+    pub blocked: usize,
+    /// Beads being worked on, including hooked ones
+    pub in_progress: usize,
+    pub closed: usize,
+    /// Merge requests waiting in the refinery
+    pub queue_depth: usize,
+    /// Most recent create, update or close of the rig's beads
+    pub last_activity: Option<String>,
+}
+
+/// A rig's beads: its own `.beads` (following any redirect), or the town's
+pub fn rig_beads_dir(town: &Path, rig: &str) -> Option<PathBuf> {
+    [town.join(rig).join(".beads"), town.join(".beads")]
+        .into_iter()
+        .find(|dir| dir.exists())
+        .map(|dir| beads::resolve_redirect(&dir))
+}
+
+/// Where bd has to run to find a rig's beads: the rig's directory if it
+/// has a `.beads` of its own, otherwise the town
+pub fn beads_cwd(town: &Path, rig: &str) -> PathBuf {
+    let dir = town.join(rig);
+    let plain_name = !rig.is_empty() && !rig.starts_with('.') && !rig.contains(['/', '\\']);
+    if plain_name && dir.join(".beads").exists() {
+        dir
+    } else {
+        town.to_path_buf()
+    }
+}
+
+/// Every rig under `town`, by name
+pub fn discover(town: &Path) -> Result<Vec<Rig>, String> {
+    let entries =
+        fs::read_dir(town).map_err(|e| format!("Failed to read town directory: {}", e))?;
+
+    let mut rigs: Vec<Rig> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            let is_rig = !name.starts_with('.')
+                && !TOWN_AGENTS.contains(&name.as_str())
+                && (path.join(".beads").exists()
+                    || RIG_MARKERS.iter().any(|marker| path.join(marker).is_dir()));
+            is_rig.then(|| Rig {
+                beads_dir: rig_beads_dir(town, &name),
+                name,
+                path,
+                shared_beads: false,
+            })
+        })
+        .collect();
+    rigs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let shared = shared_beads_dirs(town, &rigs);
+    for rig in &mut rigs {
+        rig.shared_beads = rig
+            .beads_dir
+            .as_deref()
+            .is_some_and(|dir| shared.contains(&canonical(dir)));
+    }
+    Ok(rigs)
+}
+
+fn canonical(dir: &Path) -> PathBuf {
+    fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf())
+}
+
+/// Beads dirs used by more than one rig, or by the town itself
+fn shared_beads_dirs(town: &Path, rigs: &[Rig]) -> Vec<PathBuf> {
+    let mut uses: HashMap<PathBuf, usize> = HashMap::new();
+    for dir in rigs.iter().filter_map(|rig| rig.beads_dir.as_deref()) {
+        *uses.entry(canonical(dir)).or_default() += 1;
+    }
+    let town_beads = canonical(&town.join(".beads"));
+    uses.into_iter()
+        .filter(|(dir, count)| *count > 1 || *dir == town_beads)
+        .map(|(dir, _)| dir)
+        .collect()
+}
+
+/// Summarize a rig from every bead in its beads dir. When the dir is
+/// shared, only beads whose rig is this one are counted.
+pub fn summarize(rig: &Rig, issues: &[Issue]) -> RigSummary {
+    let by_id: HashMap<&str, &Issue> = issues.iter().map(|i| (i.id.as_str(), i)).collect();
+    let has_open_blocker = |issue: &Issue| {
+        issue.dependencies.iter().any(|dep| {
+            dep.dep_type == "blocks"
+                && by_id
+                    .get(dep.depends_on_id.as_str())
+                    .is_some_and(|blocker| blocker.status != "closed")
+        })
+    };
+
+    let mut summary = RigSummary {
+        name: rig.name.clone(),
+        path: rig.path.to_string_lossy().to_string(),
+        beads_dir: rig
+            .beads_dir
+            .as_ref()
+            .map(|dir| dir.to_string_lossy().to_string()),
+        polecat_count: count_polecats(&rig.path),
+        ready: 0,
+        blocked: 0,
+        in_progress: 0,
+        closed: 0,
+        queue_depth: 0,
+        last_activity: None,
+    };
+    let mut last_activity: Option<(i64, &str)> = None;
+
+    let mine = issues
+        .iter()
+        .filter(|issue| !rig.shared_beads || issue.rig().as_deref() == Some(rig.name.as_str()));
+    for issue in mine {
+        let touched = [
+            Some(issue.created_at.as_str()),
+            Some(issue.updated_at.as_str()),
+            issue.closed_at.as_deref(),
+        ];
+        for ts in touched.into_iter().flatten() {
+            if let Some(ms) = parse_timestamp(ts) {
+                if !matches!(last_activity, Some((latest, _)) if latest >= ms) {
+                    last_activity = Some((ms, ts));
+                }
+            }
+        }
+
+        if issue.issue_type.as_deref() == Some("merge-request") {
+            if issue.status != "closed" {
+                summary.queue_depth += 1;
+            }
+            continue;
+        }
+        match issue.status.as_str() {
+            "closed" => summary.closed += 1,
+            "in_progress" | "hooked" => summary.in_progress += 1,
+            "blocked" => summary.blocked += 1,
+            "open" if has_open_blocker(issue) => summary.blocked += 1,
+            "open" => summary.ready += 1,
+            _ => {}
+        }
+    }
+
+    summary.last_activity = last_activity.map(|(_, ts)| ts.to_string());
+    summary
+}
+
+fn count_polecats(rig_path: &Path) -> usize {
+    fs::read_dir(rig_path.join("polecats"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+                .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+fn summarize_rigs(app: &AppHandle, beads_state: &BeadsStoreState, rigs: &[Rig]) -> Vec<RigSummary> {
+    rigs.iter()
+        .map(|rig| {
+            let summary = rig.beads_dir.as_deref().map(|dir| {
+                beads_state.with_index(app, dir, |index| summarize(rig, index.issues()))
+            });
+            match summary {
+                Some(Ok(summary)) => summary,
+                Some(Err(e)) => {
+                    log::warn!("Failed to read beads for rig {}: {}", rig.name, e);
+                    summarize(rig, &[])
+                }
+                None => summarize(rig, &[]),
+            }
+        })
+        .collect()
+}
+
+/// List the rigs of `town` (default the active town) with polecat, bead and
+/// merge queue counts
+#[tauri::command]
+pub async fn list_rigs(
+    app: AppHandle,
+    beads_state: State<'_, BeadsStoreState>,
+    town: Option<String>,
+) -> Result<Vec<RigSummary>, String> {
+    let rigs = discover(&towns::root(&app, town.as_deref())?)?;
+    Ok(summarize_rigs(&app, &beads_state, &rigs))
+}
+
+/// Get the summary of one rig of `town` (default the active town)
+#[tauri::command]
+pub async fn get_rig(
+    app: AppHandle,
+    beads_state: State<'_, BeadsStoreState>,
+    name: String,
+    town: Option<String>,
+) -> Result<RigSummary, String> {
+    let rig = discover(&towns::root(&app, town.as_deref())?)?
+        .into_iter()
+        .find(|rig| rig.name == name)
+        .ok_or_else(|| format!("Rig not found: {}", name))?;
+    Ok(summarize_rigs(&app, &beads_state, &[rig]).remove(0))
+}
@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::oneshot;
+
+/// Channel streamed output lines are emitted on
+pub const OUTPUT_CHANNEL: &str = "command-output";
+
+/// Timeout for quick status queries
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Timeout for installs and builds
+pub const INSTALL_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Which pipe a line of output came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// A line of output from a running job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputLine {
+    pub job_id: String,
+    pub stream: OutputStream,
+    pub line: String,
+}
+
+/// What a finished process wrote and how it exited
+#[derive(Debug, Clone)]
+pub struct ProcessOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// -1 if killed by a signal
+    pub exit_code: i32,
+    pub success: bool,
+}
+
+impl From<std::process::Output> for ProcessOutput {
+    fn from(output: std::process::Output) -> Self {
+        Self {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            exit_code: output.status.code().unwrap_or(-1),
+            success: output.status.success(),
+        }
+    }
+}
+
+/// Run `program args...` without blocking the runtime. The process is killed
+/// if it outlives `timeout` or `cancel` fires. Each line is passed to
+/// `on_line` as it arrives. Given a `town` root, the process runs there with
+/// `GT_ROOT` pointing at it.
+pub async fn run(
+    program: &str,
+    args: &[String],
+    town: Option<&Path>,
+    timeout: Duration,
+    cancel: Option<oneshot::Receiver<()>>,
+    on_line: impl Fn(OutputStream, &str) + Sync,
+) -> Result<ProcessOutput, String> {
+    let mut command = Command::new(program);
+    if let Some(town) = town {
+        command.current_dir(town).env("GT_ROOT", town);
+    }
+    let mut child = command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to execute {}: {}", program, e))?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    // Owns the child, so dropping it on timeout or cancel kills the process
+    let work = async move {
+        let (stdout, stderr, status) = tokio::join!(
+            read_lines(stdout, OutputStream::Stdout, &on_line),
+            read_lines(stderr, OutputStream::Stderr, &on_line),
+            child.wait(),
+        );
+        status.map(|status| ProcessOutput {
+            stdout,
+            stderr,
+            exit_code: status.code().unwrap_or(-1),
+            success: status.success(),
+        })
+    };
+    let cancelled = async {
+        match cancel {
+            Some(cancel) => {
+                // A dropped sender just means nobody can cancel any more
+                if cancel.await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            }
+            None => std::future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        result = work => result.map_err(|e| format!("Failed to wait for {}: {}", program, e)),
+        _ = tokio::time::sleep(timeout) => Err(format!(
+            "{} timed out after {}s",
+            program,
+            timeout.as_secs()
+        )),
+        _ = cancelled => Err(format!("{} was cancelled", program)),
+    }
+}
+
+/// Read a pipe to the end, reporting each line and returning everything read
+async fn read_lines<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    stream: OutputStream,
+    on_line: &(impl Fn(OutputStream, &str) + Sync),
+) -> String {
+    let Some(reader) = reader else {
+        return String::new();
+    };
+    let mut reader = BufReader::new(reader);
+    let mut collected = String::new();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buf);
+                collected.push_str(&line);
+                on_line(stream, line.trim_end_matches(['\r', '\n']));
+            }
+        }
+    }
+    collected
+}
+
+/// Jobs that can be cancelled by ID while they run
+#[derive(Default)]
+pub struct RunnerState {
+    jobs: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+impl RunnerState {
+    /// Run a command for the frontend. With a `job_id` its output is streamed
+    /// on `command-output` and it can be stopped with `cancel_command`.
+    pub async fn run_job(
+        &self,
+        app: &AppHandle,
+        job_id: Option<&str>,
+        program: &str,
+        args: &[String],
+        town: Option<&Path>,
+        timeout: Duration,
+    ) -> Result<ProcessOutput, String> {
+        let Some(job_id) = job_id else {
+            return run(program, args, town, timeout, None, |_, _| {}).await;
+        };
+
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            if jobs.contains_key(job_id) {
+                return Err(format!("Job {} is already running", job_id));
+            }
+            jobs.insert(job_id.to_string(), cancel_tx);
+        }
+
+        let result = run(
+            program,
+            args,
+            town,
+            timeout,
+            Some(cancel_rx),
+            |stream, line| {
+                let _ = app.emit(
+                    OUTPUT_CHANNEL,
+                    OutputLine {
+                        job_id: job_id.to_string(),
+                        stream,
+                        line: line.to_string(),
+                    },
+                );
+            },
+        )
+        .await;
+
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.remove(job_id);
+        }
+        result
+    }
+
+    /// Kill a running job. False if no job has that ID.
+    pub fn cancel(&self, job_id: &str) -> Result<bool, String> {
+        let sender = self.jobs.lock().map_err(|e| e.to_string())?.remove(job_id);
+        Ok(sender.is_some_and(|sender| sender.send(()).is_ok()))
+    }
+
+    /// IDs of jobs still running
+    pub fn running(&self) -> Result<Vec<String>, String> {
+        Ok(self
+            .jobs
+            .lock()
+            .map_err(|e| e.to_string())?
+            .keys()
+            .cloned()
+            .collect())
+    }
+}
+
+/// Cancel a running command by the job ID it was started with
+#[tauri::command]
+pub async fn cancel_command(state: State<'_, RunnerState>, job_id: String) -> Result<bool, String> {
+    state.cancel(&job_id)
+}
+
+/// List the job IDs of commands still running
+#[tauri::command]
+pub async fn list_running_commands(state: State<'_, RunnerState>) -> Result<Vec<String>, String> {
+    state.running()
+}
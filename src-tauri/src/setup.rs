@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use tauri::{AppHandle, State};
+
+use crate::audit::{self, CommandOrigin};
+use crate::executor::RiskClass;
+use crate::runner::{self, ProcessOutput, RunnerState};
+use crate::towns;
 
 /// Status of a single dependency
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -219,8 +226,13 @@ fn check_tmux() -> DependencyInfo {
     }
 }
 
-/// Check if Gas Town workspace exists
-fn check_workspace() -> (bool, Option<String>) {
+/// Check if Gas Town workspace exists: the selected `town` when there is
+/// one, otherwise the usual places
+fn check_workspace(town: Option<&Path>) -> (bool, Option<String>) {
+    if let Some(town) = town {
+        return (town.is_dir(), Some(town.to_string_lossy().to_string()));
+    }
+
     // Check common locations
     let home = dirs::home_dir().unwrap_or_default();
     let common_paths = vec![
@@ -281,9 +293,26 @@ fn generate_voice_guidance(deps: &[DependencyInfo], workspace_exists: bool) -> S
     }
 }
 
+/// Check whether Homebrew is installed
+#[tauri::command]
+pub async fn check_homebrew() -> Result<bool, String> {
+    Ok(check_command("brew", &["--version"]).0)
+}
+
+/// Check whether Go's bin directory is on PATH
+#[tauri::command]
+pub async fn check_gobin_in_path() -> Result<bool, String> {
+    Ok(check_gopath_in_path().is_none())
+}
+
 /// Check all dependencies and return setup status
 #[tauri::command]
 pub async fn check_dependencies() -> Result<SetupStatus, String> {
+    Ok(setup_status(None))
+}
+
+/// Tools plus the workspace at `town`, or wherever one is found
+fn setup_status(town: Option<&Path>) -> SetupStatus {
     let dependencies = vec![
         check_git(),
         check_go(),
@@ -293,17 +322,17 @@ pub async fn check_dependencies() -> Result<SetupStatus, String> {
     ];
 
     let missing_count = dependencies.iter().filter(|d| !d.installed).count();
-    let (workspace_exists, workspace_path) = check_workspace();
+    let (workspace_exists, workspace_path) = check_workspace(town);
     let voice_guidance = generate_voice_guidance(&dependencies, workspace_exists);
 
-    Ok(SetupStatus {
+    SetupStatus {
         ready: missing_count == 0 && workspace_exists,
         dependencies,
         workspace_exists,
         workspace_path,
         missing_count,
         voice_guidance,
-    })
+    }
 }
 
 /// Result of an installation step
@@ -315,9 +344,38 @@ pub struct InstallResult {
     pub voice_response: String,
 }
 
-/// Install a specific dependency (guides user through process)
+/// Run an install step, streaming its output when given a job ID, and
+/// record it in the audit log
+async fn run_install(
+    app: &AppHandle,
+    runner_state: &RunnerState,
+    origin: CommandOrigin,
+    job_id: Option<&str>,
+    program: &str,
+    args: &[&str],
+    dir: Option<&Path>,
+) -> Result<ProcessOutput, String> {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    let mut argv = vec![program.to_string()];
+    argv.extend(args.iter().cloned());
+    let run = runner_state.run_job(app, job_id, program, &args, dir, runner::INSTALL_TIMEOUT);
+    audit::run_recorded(app, origin, argv, Some(RiskClass::Mutating), run)
+        .await
+        .map_err(|e| format!("Failed to run {} {}: {}", program, args.join(" "), e))
+}
+
+/// Install a specific dependency (guides user through process). Pass a
+/// `job_id` to stream install output on `command-output` and to be able to
+/// cancel it with `cancel_command`. Installs are audited under `origin`.
 #[tauri::command]
-pub async fn install_dependency(name: String) -> Result<InstallResult, String> {
+pub async fn install_dependency(
+    app: AppHandle,
+    runner_state: State<'_, RunnerState>,
+    name: String,
+    job_id: Option<String>,
+    origin: Option<CommandOrigin>,
+) -> Result<InstallResult, String> {
+    let origin = origin.unwrap_or_default();
     match name.to_lowercase().as_str() {
         "go" => {
             // Can't auto-install Go, guide user
@@ -330,12 +388,18 @@ pub async fn install_dependency(name: String) -> Result<InstallResult, String> {
         }
         "gt" | "gastown" => {
             // Try to install gt via go install
-            let output = Command::new("go")
-                .args(["install", "github.com/txgsync/gastown/cmd/gt@latest"])
-                .output()
-                .map_err(|e| format!("Failed to run go install: {}", e))?;
-
-            if output.status.success() {
+            let output = run_install(
+                &app,
+                &runner_state,
+                origin,
+                job_id.as_deref(),
+                "go",
+                &["install", "github.com/txgsync/gastown/cmd/gt@latest"],
+                None,
+            )
+            .await?;
+
+            if output.success {
                 Ok(InstallResult {
                     success: true,
                     message: "gt installed successfully".to_string(),
@@ -343,7 +407,7 @@ pub async fn install_dependency(name: String) -> Result<InstallResult, String> {
                     voice_response: "Got it! gt is now installed. You'll also need bd for issue tracking. Say 'install bd' to continue.".to_string(),
                 })
             } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
+                let stderr = output.stderr;
                 Ok(InstallResult {
                     success: false,
                     message: format!("gt installation failed: {}", stderr),
@@ -354,12 +418,18 @@ pub async fn install_dependency(name: String) -> Result<InstallResult, String> {
         }
         "bd" | "beads" => {
             // Try to install bd via go install
-            let output = Command::new("go")
-                .args(["install", "github.com/mbarnson/beads/cmd/bd@latest"])
-                .output()
-                .map_err(|e| format!("Failed to run go install: {}", e))?;
-
-            if output.status.success() {
+            let output = run_install(
+                &app,
+                &runner_state,
+                origin,
+                job_id.as_deref(),
+                "go",
+                &["install", "github.com/mbarnson/beads/cmd/bd@latest"],
+                None,
+            )
+            .await?;
+
+            if output.success {
                 // Check if GOPATH/bin is in PATH
                 let path_warning = check_gopath_in_path();
                 let voice_response = if path_warning.is_some() {
@@ -375,7 +445,7 @@ pub async fn install_dependency(name: String) -> Result<InstallResult, String> {
                     voice_response,
                 })
             } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
+                let stderr = output.stderr;
                 Ok(InstallResult {
                     success: false,
                     message: format!("bd installation failed: {}", stderr),
@@ -396,12 +466,19 @@ pub async fn install_dependency(name: String) -> Result<InstallResult, String> {
             // Try brew install on macOS
             #[cfg(target_os = "macos")]
             {
-                let output = Command::new("brew")
-                    .args(["install", "tmux"])
-                    .output();
+                let output = run_install(
+                    &app,
+                    &runner_state,
+                    origin,
+                    job_id.as_deref(),
+                    "brew",
+                    &["install", "tmux"],
+                    None,
+                )
+                .await;
 
                 match output {
-                    Ok(o) if o.status.success() => {
+                    Ok(o) if o.success => {
                         return Ok(InstallResult {
                             success: true,
                             message: "tmux installed via Homebrew".to_string(),
@@ -445,9 +522,35 @@ fn expand_tilde(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
-/// Create a new Gas Town workspace
+/// Create a new Gas Town workspace. Pass a `job_id` to stream `gt install`
+/// or `gt init` output on `command-output` and to be able to cancel it. The commands run are audited under `origin`.
 #[tauri::command]
-pub async fn create_workspace(path: Option<String>) -> Result<InstallResult, String> {
+pub async fn create_workspace(
+    app: AppHandle,
+    runner_state: State<'_, RunnerState>,
+    path: Option<String>,
+    job_id: Option<String>,
+    origin: Option<CommandOrigin>,
+) -> Result<InstallResult, String> {
+    let origin = origin.unwrap_or_default();
+    let app = &app;
+    let runner_state = &*runner_state;
+    let job_id = job_id.as_deref();
+
+    init_workspace(path, |args, dir| async move {
+        let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+        run_install(app, runner_state, origin, job_id, "gt", &args, dir.as_deref()).await
+    })
+    .await
+}
+
+/// Create a workspace at `path` (default `~/gt`). `run_gt` runs one gt step
+/// given its args and, for `gt init`, the directory to run it in.
+pub async fn init_workspace<F, Fut>(path: Option<String>, run_gt: F) -> Result<InstallResult, String>
+where
+    F: Fn(Vec<String>, Option<PathBuf>) -> Fut,
+    Fut: Future<Output = Result<ProcessOutput, String>>,
+{
     let home = dirs::home_dir().ok_or("Could not find home directory")?;
 
     // Expand ~ in path and use default if not provided
@@ -491,12 +594,13 @@ pub async fn create_workspace(path: Option<String>) -> Result<InstallResult, Str
         .map_err(|e| format!("Failed to create workspace directory: {}", e))?;
 
     // Initialize with gt install (creates HQ structure)
-    let output = Command::new("gt")
-        .args(["install", workspace_path.to_string_lossy().as_ref()])
-        .output()
-        .map_err(|e| format!("Failed to run gt install: {}", e))?;
+    let output = run_gt(
+        vec!["install".to_string(), workspace_path.to_string_lossy().to_string()],
+        None,
+    )
+    .await?;
 
-    if output.status.success() {
+    if output.success {
         Ok(InstallResult {
             success: true,
             message: format!("Workspace created at {}", workspace_path.display()),
@@ -507,17 +611,14 @@ pub async fn create_workspace(path: Option<String>) -> Result<InstallResult, Str
             ),
         })
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = output.stderr;
+        let stdout = output.stdout;
 
         // Try gt init as fallback (older version)
-        let init_output = Command::new("gt")
-            .args(["init"])
-            .current_dir(&workspace_path)
-            .output();
+        let init_output = run_gt(vec!["init".to_string()], Some(workspace_path.clone())).await;
 
         if let Ok(out) = init_output {
-            if out.status.success() {
+            if out.success {
                 return Ok(InstallResult {
                     success: true,
                     message: format!("Workspace created at {} (using gt init)", workspace_path.display()),
@@ -542,10 +643,12 @@ pub async fn create_workspace(path: Option<String>) -> Result<InstallResult, Str
     }
 }
 
-/// Get current setup status for the FTUE wizard
+/// Get current setup status for the FTUE wizard, checking `town` (default
+/// the active town) as the workspace
 #[tauri::command]
-pub async fn get_setup_status() -> Result<SetupStatus, String> {
-    check_dependencies().await
+pub async fn get_setup_status(app: AppHandle, town: Option<String>) -> Result<SetupStatus, String> {
+    let town = towns::selected_root(&app, town.as_deref())?;
+    Ok(setup_status(town.as_deref()))
 }
 
 /// Disk space information
@@ -0,0 +1,211 @@
+use std::fs::{File, Metadata};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Bytes before the read position remembered to detect in-place rewrites
+const FINGERPRINT_LEN: u64 = 64;
+
+/// New complete lines read from a followed file
+#[derive(Debug, Default)]
+pub struct TailChunk {
+    /// The file was truncated, rewritten or replaced; `lines` start from the
+    /// beginning of the current file
+    pub reset: bool,
+    /// Lines drained from a file that was rotated away, read before `lines`
+    pub previous: Vec<String>,
+    pub lines: Vec<String>,
+}
+
+/// Identity of a file on disk, used to notice rotation and recreation
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Follows a JSONL file as it grows, one complete line at a time.
+///
+/// Handles the ways log files change under a reader: appends that end
+/// mid-line, truncation, in-place rewrites, rename-based rotation, and the
+/// file being deleted and created again.
+pub struct JsonlTail {
+    path: PathBuf,
+    file: Option<File>,
+    file_id: Option<(u64, u64)>,
+    offset: u64,
+    fingerprint: Vec<u8>,
+    /// Skip whatever is already in the file the first time it is opened
+    skip_existing: bool,
+}
+
+impl JsonlTail {
+    /// Follow `path` from the beginning
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            file: None,
+            file_id: None,
+            offset: 0,
+            fingerprint: Vec::new(),
+            skip_existing: false,
+        }
+    }
+
+    /// Follow `path` starting after the lines it already contains. If the
+    /// file doesn't exist yet, it is read from the start once created.
+    pub fn from_end(path: impl Into<PathBuf>) -> Self {
+        Self {
+            skip_existing: true,
+            ..Self::new(path)
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read any complete lines written since the last poll.
+    ///
+    /// A trailing line without a newline is left for the next poll.
+    pub fn poll(&mut self) -> std::io::Result<TailChunk> {
+        let mut chunk = TailChunk::default();
+
+        let current = match std::fs::metadata(&self.path) {
+            Ok(metadata) => Some(metadata),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+
+        // The path now points at a different file (rotated, or deleted and
+        // recreated): finish the old one, then start over on the new one
+        let replaced = match (&self.file, &current) {
+            (Some(_), None) => true,
+            (Some(_), Some(metadata)) => {
+                self.file_id.is_some() && file_id(metadata) != self.file_id
+            }
+            _ => false,
+        };
+        if replaced {
+            if let Some(mut old) = self.file.take() {
+                if let Ok(len) = old.metadata().map(|m| m.len()) {
+                    chunk.previous = self.read_lines(&mut old, len)?;
+                }
+            }
+            chunk.reset = true;
+            self.offset = 0;
+            self.fingerprint.clear();
+            self.file_id = None;
+        }
+
+        let Some(metadata) = current else {
+            // Anything written once the file appears is new
+            self.skip_existing = false;
+            return Ok(chunk);
+        };
+
+        let mut file = match self.file.take() {
+            Some(file) => file,
+            None => {
+                let mut file = File::open(&self.path)?;
+                self.file_id = file_id(&metadata);
+                if self.skip_existing {
+                    self.skip_existing = false;
+                    self.skip_to_last_line(&mut file, metadata.len())?;
+                }
+                file
+            }
+        };
+        let len = file.metadata()?.len();
+
+        // Truncated (copytruncate rotation) or rewritten in place
+        if len < self.offset || !self.fingerprint_matches(&mut file)? {
+            chunk.reset = true;
+            self.offset = 0;
+            self.fingerprint.clear();
+        }
+
+        let lines = self.read_lines(&mut file, len);
+        self.file = Some(file);
+        chunk.lines = lines?;
+        Ok(chunk)
+    }
+
+    /// Read complete lines between the current offset and `len`
+    fn read_lines(&mut self, file: &mut File, len: u64) -> std::io::Result<Vec<String>> {
+        if len <= self.offset {
+            return Ok(Vec::new());
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::with_capacity((len - self.offset) as usize);
+        file.take(len - self.offset).read_to_end(&mut buf)?;
+
+        // Only consume up to the last newline; a partial line stays unread
+        let consumed = match buf.iter().rposition(|&b| b == b'\n') {
+            Some(pos) => pos + 1,
+            None => return Ok(Vec::new()),
+        };
+
+        self.advance(&buf[..consumed]);
+        Ok(String::from_utf8_lossy(&buf[..consumed])
+            .lines()
+            .map(|l| l.to_string())
+            .collect())
+    }
+
+    /// Position after the last complete line, without returning any lines
+    fn skip_to_last_line(&mut self, file: &mut File, len: u64) -> std::io::Result<()> {
+        let mut pos = len;
+        let mut block = vec![0u8; 4096];
+        while pos > 0 {
+            let start = pos.saturating_sub(block.len() as u64);
+            let size = (pos - start) as usize;
+            file.seek(SeekFrom::Start(start))?;
+            file.read_exact(&mut block[..size])?;
+            if let Some(i) = block[..size].iter().rposition(|&b| b == b'\n') {
+                let end = start + i as u64 + 1;
+                let fp_start = end.saturating_sub(FINGERPRINT_LEN);
+                let mut fingerprint = vec![0u8; (end - fp_start) as usize];
+                file.seek(SeekFrom::Start(fp_start))?;
+                file.read_exact(&mut fingerprint)?;
+                self.offset = end;
+                self.fingerprint = fingerprint;
+                return Ok(());
+            }
+            pos = start;
+        }
+        Ok(())
+    }
+
+    /// Move past `consumed` bytes, keeping the fingerprint in step
+    fn advance(&mut self, consumed: &[u8]) {
+        self.offset += consumed.len() as u64;
+        let keep = (FINGERPRINT_LEN as usize).min(consumed.len());
+        self.fingerprint
+            .extend_from_slice(&consumed[consumed.len() - keep..]);
+        let excess = self
+            .fingerprint
+            .len()
+            .saturating_sub(FINGERPRINT_LEN as usize);
+        self.fingerprint.drain(..excess);
+    }
+
+    /// Check the bytes just before our position are still the ones we read
+    fn fingerprint_matches(&self, file: &mut File) -> std::io::Result<bool> {
+        if self.fingerprint.is_empty() {
+            return Ok(true);
+        }
+        let start = self.offset - self.fingerprint.len() as u64;
+        file.seek(SeekFrom::Start(start))?;
+        let mut current = vec![0u8; self.fingerprint.len()];
+        if file.read_exact(&mut current).is_err() {
+            return Ok(false);
+        }
+        Ok(current == self.fingerprint)
+    }
+}
@@ -0,0 +1,342 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+use tokio::process::Command;
+
+/// File in the app config dir the terminal choice is saved to
+const TERMINAL_FILE: &str = "terminal.json";
+
+/// Launcher ID for the user's own command template
+pub const CUSTOM_LAUNCHER: &str = "custom";
+
+/// A terminal emulator we know how to start with a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Launcher {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub program: &'static str,
+    /// Arguments before the command to run
+    pub args: &'static [&'static str],
+}
+
+impl Launcher {
+    /// Full argv running `command` in this terminal
+    pub fn argv(&self, command: &[String]) -> Vec<String> {
+        std::iter::once(self.program)
+            .chain(self.args.iter().copied())
+            .map(str::to_string)
+            .chain(command.iter().cloned())
+            .collect()
+    }
+}
+
+/// Known terminals, in the order auto-detection tries them
+pub const LAUNCHERS: &[Launcher] = &[
+    Launcher {
+        id: "gnome-terminal",
+        name: "GNOME Terminal",
+        program: "gnome-terminal",
+        args: &["--"],
+    },
+    Launcher {
+        id: "konsole",
+        name: "Konsole",
+        program: "konsole",
+        args: &["-e"],
+    },
+    Launcher {
+        id: "kitty",
+        name: "kitty",
+        program: "kitty",
+        args: &[],
+    },
+    Launcher {
+        id: "alacritty",
+        name: "Alacritty",
+        program: "alacritty",
+        args: &["-e"],
+    },
+    Launcher {
+        id: "wezterm",
+        name: "WezTerm",
+        program: "wezterm",
+        args: &["start", "--"],
+    },
+    Launcher {
+        id: "foot",
+        name: "foot",
+        program: "foot",
+        args: &[],
+    },
+    // Debian's alternatives link, then the terminal that's always around
+    Launcher {
+        id: "x-terminal-emulator",
+        name: "Default terminal",
+        program: "x-terminal-emulator",
+        args: &["-e"],
+    },
+    Launcher {
+        id: "xterm",
+        name: "xterm",
+        program: "xterm",
+        args: &["-e"],
+    },
+];
+
+/// Which terminal `attach_tmux_session` opens
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TerminalConfig {
+    /// A launcher ID, or `custom`; unset means auto-detect
+    #[serde(default)]
+    pub launcher: Option<String>,
+    /// Command for the custom launcher, e.g. `st -t {session} -e {command}`.
+    /// `{command}` stands for the whole tmux command and must be a word of
+    /// its own; it's appended when left out.
+    #[serde(default)]
+    pub custom_command: Option<String>,
+}
+
+/// A launcher, and whether its program is on PATH
+#[derive(Debug, Clone, Serialize)]
+pub struct LauncherInfo {
+    #[serde(flatten)]
+    pub launcher: Launcher,
+    pub installed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminalLaunchers {
+    pub launchers: Vec<LauncherInfo>,
+    /// What auto-detection picks
+    pub detected: Option<String>,
+    pub config: TerminalConfig,
+}
+
+/// The command a terminal runs to attach to `session`
+pub fn attach_command(session: &str) -> Vec<String> {
+    ["tmux", "attach-session", "-t", session]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Whether `session` is safe to put in a shell or AppleScript command line
+/// unquoted: letters, digits, `-` and `_`, as gt names its sessions
+pub fn is_plain_session_name(session: &str) -> bool {
+    !session.is_empty()
+        && session
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_'))
+}
+
+/// Whether `program` is an executable file in one of the `path` dirs
+fn on_path(program: &str, path: Option<&OsStr>) -> bool {
+    let Some(path) = path else {
+        return false;
+    };
+    std::env::split_paths(path).any(|dir| {
+        let file = dir.join(program);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::metadata(&file)
+                .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+                .unwrap_or(false)
+        }
+        #[cfg(not(unix))]
+        {
+            file.is_file()
+        }
+    })
+}
+
+/// The first known terminal installed on `path`
+pub fn detect(path: Option<&OsStr>) -> Option<&'static Launcher> {
+    LAUNCHERS.iter().find(|l| on_path(l.program, path))
+}
+
+/// Split a command template into words. Quotes group words as in a shell,
+/// but nothing else is interpreted.
+fn split_words(template: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    for c in template.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err("Unclosed quote in terminal command".to_string());
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Argv for a custom command template. `{session}` is replaced inside a
+/// word, so the session name never becomes more than one argument. That
+/// word may still reach a shell (`sh -c "... {session}"`), so only plain
+/// session names are filled in.
+pub fn custom_argv(template: &str, session: &str) -> Result<Vec<String>, String> {
+    if template.contains("{session}") && !is_plain_session_name(session) {
+        return Err(format!(
+            "Can't fill session name {:?} into a terminal command; use {{command}} instead",
+            session
+        ));
+    }
+    let command = attach_command(session);
+    let mut argv = Vec::new();
+    let mut placed = false;
+    for word in split_words(template)? {
+        if word == "{command}" {
+            argv.extend(command.iter().cloned());
+            placed = true;
+        } else if word.contains("{command}") {
+            return Err("{command} must be a word of its own".to_string());
+        } else {
+            argv.push(word.replace("{session}", session));
+        }
+    }
+    if argv.is_empty() {
+        return Err("Terminal command is empty".to_string());
+    }
+    if !placed {
+        argv.extend(command);
+    }
+    Ok(argv)
+}
+
+/// Argv opening a terminal attached to `session`, per `config`, looking for
+/// terminals on `path` when none is chosen
+pub fn launch_argv(
+    config: &TerminalConfig,
+    session: &str,
+    path: Option<&OsStr>,
+) -> Result<Vec<String>, String> {
+    match config.launcher.as_deref() {
+        Some(CUSTOM_LAUNCHER) => {
+            let template = config
+                .custom_command
+                .as_deref()
+                .ok_or("No custom terminal command set")?;
+            custom_argv(template, session)
+        }
+        Some(id) => LAUNCHERS
+            .iter()
+            .find(|l| l.id == id)
+            .map(|l| l.argv(&attach_command(session)))
+            .ok_or_else(|| format!("Unknown terminal: {}", id)),
+        None => detect(path)
+            .map(|l| l.argv(&attach_command(session)))
+            .ok_or_else(|| {
+                "No terminal emulator found. Choose one or set a custom command.".to_string()
+            }),
+    }
+}
+
+/// Open a terminal attached to `session`. The terminal runs on its own; it
+/// isn't waited for.
+pub fn launch(config: &TerminalConfig, session: &str) -> Result<(), String> {
+    let argv = launch_argv(config, session, std::env::var_os("PATH").as_deref())?;
+    Command::new(&argv[0])
+        .args(&argv[1..])
+        .spawn()
+        .map_err(|e| format!("Failed to launch {}: {}", argv[0], e))?;
+    Ok(())
+}
+
+/// The terminal choice, loaded from the app config dir at startup
+#[derive(Default)]
+pub struct TerminalState {
+    config: Mutex<TerminalConfig>,
+}
+
+impl TerminalState {
+    pub fn config(&self) -> Result<TerminalConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+}
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config directory: {}", e))?;
+    Ok(dir.join(TERMINAL_FILE))
+}
+
+/// Load the saved terminal choice into app state; missing or unreadable
+/// config means auto-detect
+pub fn load_config(app: &AppHandle) {
+    let Ok(path) = config_path(app) else {
+        return;
+    };
+    let config = match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid terminal config in {:?}: {}", path, e);
+            TerminalConfig::default()
+        }),
+        Err(_) => TerminalConfig::default(),
+    };
+    *app.state::<TerminalState>().config.lock().unwrap() = config;
+}
+
+fn save(app: &AppHandle, config: &TerminalConfig) -> Result<(), String> {
+    let path = config_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize terminal config: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save terminal config: {}", e))
+}
+
+/// Known terminals, which are installed, and the current choice
+#[tauri::command]
+pub async fn list_terminal_launchers(
+    state: State<'_, TerminalState>,
+) -> Result<TerminalLaunchers, String> {
+    let path = std::env::var_os("PATH");
+    Ok(TerminalLaunchers {
+        launchers: LAUNCHERS
+            .iter()
+            .map(|&launcher| LauncherInfo {
+                launcher,
+                installed: on_path(launcher.program, path.as_deref()),
+            })
+            .collect(),
+        detected: detect(path.as_deref()).map(|l| l.id.to_string()),
+        config: state.config()?,
+    })
+}
+
+/// Choose the terminal `attach_tmux_session` opens and save the choice
+#[tauri::command]
+pub async fn set_terminal_config(
+    app: AppHandle,
+    state: State<'_, TerminalState>,
+    config: TerminalConfig,
+) -> Result<TerminalConfig, String> {
+    match config.launcher.as_deref() {
+        Some(CUSTOM_LAUNCHER) => {
+            custom_argv(config.custom_command.as_deref().unwrap_or_default(), "")?;
+        }
+        Some(id) if !LAUNCHERS.iter().any(|l| l.id == id) => {
+            return Err(format!("Unknown terminal: {}", id));
+        }
+        _ => {}
+    }
+    save(&app, &config)?;
+    *state.config.lock().map_err(|e| e.to_string())? = config.clone();
+    Ok(config)
+}
@@ -0,0 +1,997 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+use crate::ansi::{self, Cell, StyledSpan};
+use crate::audit::{self, AuditEntry, CommandOrigin};
+use crate::executor::{self, CommandConfirmation, ExecutorState, RiskClass};
+use crate::gastown;
+use crate::runner::{self, RunnerState};
+
+/// Event pushed with a `PaneOutput` for each chunk a watched pane writes
+pub const TMUX_OUTPUT_CHANNEL: &str = "tmux-output";
+
+/// Event pushed with a `PaneSnapshot` to the window that subscribed
+pub const TMUX_SNAPSHOT_CHANNEL: &str = "tmux-snapshot";
+
+/// Event pushed with a `SessionNotification` for window, layout and session
+/// changes, and when the connection closes
+pub const TMUX_NOTIFICATION_CHANNEL: &str = "tmux-notification";
+
+/// Something tmux reported on a control-mode connection
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Notification {
+    /// Bytes a pane wrote, from `%output` or `%extended-output`
+    Output {
+        pane_id: String,
+        #[serde(skip)]
+        data: Vec<u8>,
+    },
+    WindowAdd {
+        window_id: String,
+    },
+    WindowClose {
+        window_id: String,
+    },
+    WindowRenamed {
+        window_id: String,
+        name: String,
+    },
+    WindowPaneChanged {
+        window_id: String,
+        pane_id: String,
+    },
+    LayoutChange {
+        window_id: String,
+        layout: String,
+    },
+    PaneModeChanged {
+        pane_id: String,
+    },
+    SessionChanged {
+        session_id: String,
+        name: String,
+    },
+    SessionRenamed {
+        name: String,
+    },
+    SessionWindowChanged {
+        session_id: String,
+        window_id: String,
+    },
+    SessionsChanged,
+    /// The connection closed, with tmux's reason if it gave one
+    Exit {
+        reason: Option<String>,
+    },
+    /// Anything else, e.g. `%unlinked-window-add` or `%client-detached`
+    Other {
+        name: String,
+        args: String,
+    },
+}
+
+/// One message parsed from control-mode output
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlMessage {
+    Notification(Notification),
+    /// A command's output, between `%begin` and `%end` (or `%error`).
+    /// `from_client` is set for commands this connection sent.
+    Reply {
+        number: u64,
+        from_client: bool,
+        ok: bool,
+        lines: Vec<Vec<u8>>,
+    },
+}
+
+/// Turns control-mode output lines into messages, collecting reply blocks
+#[derive(Default)]
+pub struct ControlParser {
+    block: Option<(u64, bool, Vec<Vec<u8>>)>,
+}
+
+impl ControlParser {
+    /// Feed one line; returns a message once one is complete
+    pub fn feed(&mut self, line: &[u8]) -> Option<ControlMessage> {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+        if let Some((number, _, lines)) = self.block.as_mut() {
+            // Reply text isn't escaped, so only the matching guard ends it
+            let (name, rest) = split_word(line);
+            let ends =
+                (name == b"%end" || name == b"%error") && guard_number(rest) == Some(*number);
+            if !ends {
+                lines.push(line.to_vec());
+                return None;
+            }
+            let (number, from_client, lines) = self.block.take()?;
+            return Some(ControlMessage::Reply {
+                number,
+                from_client,
+                ok: name == b"%end",
+                lines,
+            });
+        }
+
+        let (name, rest) = split_word(line);
+        if name == b"%begin" {
+            let number = guard_number(rest)?;
+            let from_client = String::from_utf8_lossy(rest).split(' ').nth(2) == Some("1");
+            self.block = Some((number, from_client, Vec::new()));
+            return None;
+        }
+        parse_notification(line).map(ControlMessage::Notification)
+    }
+}
+
+/// The command number from a `%begin`/`%end`/`%error` line's
+/// `time number flags`
+fn guard_number(rest: &[u8]) -> Option<u64> {
+    String::from_utf8_lossy(rest)
+        .split(' ')
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+fn split_word(line: &[u8]) -> (&[u8], &[u8]) {
+    match line.iter().position(|&b| b == b' ') {
+        Some(i) => (&line[..i], &line[i + 1..]),
+        None => (line, &[]),
+    }
+}
+
+/// Parse a notification line; None for lines that aren't one
+pub fn parse_notification(line: &[u8]) -> Option<Notification> {
+    if !line.starts_with(b"%") {
+        return None;
+    }
+    let (name, rest) = split_word(line);
+    match name {
+        b"%output" => {
+            let (pane_id, data) = split_word(rest);
+            return Some(Notification::Output {
+                pane_id: String::from_utf8_lossy(pane_id).into_owned(),
+                data: unescape(data),
+            });
+        }
+        b"%extended-output" => {
+            // %extended-output %pane age ... : data
+            let (pane_id, rest) = split_word(rest);
+            let data = rest
+                .windows(3)
+                .position(|w| w == b" : ")
+                .map(|i| &rest[i + 3..])
+                .unwrap_or_default();
+            return Some(Notification::Output {
+                pane_id: String::from_utf8_lossy(pane_id).into_owned(),
+                data: unescape(data),
+            });
+        }
+        _ => {}
+    }
+
+    let name = String::from_utf8_lossy(&name[1..]).into_owned();
+    let args = String::from_utf8_lossy(rest).into_owned();
+    let (first, second) = match args.split_once(' ') {
+        Some((first, second)) => (first.to_string(), second.to_string()),
+        None => (args.clone(), String::new()),
+    };
+    let notification = match name.as_str() {
+        "window-add" => Notification::WindowAdd { window_id: first },
+        "window-close" => Notification::WindowClose { window_id: first },
+        "window-renamed" => Notification::WindowRenamed {
+            window_id: first,
+            name: second,
+        },
+        "window-pane-changed" => Notification::WindowPaneChanged {
+            window_id: first,
+            pane_id: second,
+        },
+        "layout-change" => Notification::LayoutChange {
+            window_id: first,
+            layout: second.split(' ').next().unwrap_or_default().to_string(),
+        },
+        "pane-mode-changed" => Notification::PaneModeChanged { pane_id: first },
+        "session-changed" => Notification::SessionChanged {
+            session_id: first,
+            name: second,
+        },
+        // Newer tmux puts the session ID first
+        "session-renamed" if first.starts_with('$') => {
+            Notification::SessionRenamed { name: second }
+        }
+        "session-renamed" => Notification::SessionRenamed { name: args },
+        "session-window-changed" => Notification::SessionWindowChanged {
+            session_id: first,
+            window_id: second,
+        },
+        "sessions-changed" => Notification::SessionsChanged,
+        "exit" => Notification::Exit {
+            reason: Some(args).filter(|reason| !reason.is_empty()),
+        },
+        _ => Notification::Other { name, args },
+    };
+    Some(notification)
+}
+
+/// Undo tmux's output escaping: bytes below space and backslash arrive as
+/// `\ooo` octal
+pub fn unescape(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        let octal = data
+            .get(i + 1..i + 4)
+            .filter(|digits| data[i] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d)));
+        match octal {
+            Some(digits) => {
+                let value = digits
+                    .iter()
+                    .fold(0u16, |acc, d| acc * 8 + u16::from(d - b'0'));
+                out.push(value as u8);
+                i += 4;
+            }
+            None => {
+                out.push(data[i]);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Output from one pane, numbered so a snapshot can say which chunks it
+/// already includes
+#[derive(Debug, Default)]
+pub struct PaneStream {
+    seq: u64,
+    /// Trailing bytes of a UTF-8 character split across chunks
+    partial: Vec<u8>,
+}
+
+impl PaneStream {
+    /// Add a chunk, returning its sequence number and text. Text is empty
+    /// (and no number is used) while a split character is incomplete.
+    pub fn push(&mut self, data: &[u8]) -> (u64, String) {
+        self.partial.extend_from_slice(data);
+        let complete = match std::str::from_utf8(&self.partial) {
+            Ok(_) => self.partial.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.partial.len(),
+        };
+        let rest = self.partial.split_off(complete);
+        let text = String::from_utf8_lossy(&self.partial).into_owned();
+        self.partial = rest;
+        if !text.is_empty() {
+            self.seq += 1;
+        }
+        (self.seq, text)
+    }
+
+    /// Number of the last chunk pushed
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+}
+
+/// A chunk of pane output
+#[derive(Debug, Clone, Serialize)]
+pub struct PaneOutput {
+    pub session: String,
+    pub pane_id: String,
+    pub seq: u64,
+    pub data: String,
+}
+
+/// A pane's visible screen, with escape sequences. It includes every
+/// `PaneOutput` up to `seq`; later chunks continue from the cursor.
+#[derive(Debug, Clone, Serialize)]
+pub struct PaneSnapshot {
+    pub session: String,
+    pub pane_id: String,
+    pub seq: u64,
+    pub content: String,
+    pub cursor_x: u32,
+    pub cursor_y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionNotification {
+    pub session: String,
+    #[serde(flatten)]
+    pub notification: Notification,
+}
+
+/// A command sent over the connection, waiting for its reply
+enum Request {
+    Detach,
+    /// Cursor and size of a pane, sent just before its capture
+    Geometry,
+    Capture {
+        pane_id: String,
+        window: String,
+    },
+}
+
+#[derive(Default)]
+struct ConnectionState {
+    subscribers: HashSet<String>,
+    pending: VecDeque<Request>,
+    panes: HashMap<String, PaneStream>,
+    geometry: Option<[u32; 4]>,
+    exit_reason: Option<String>,
+}
+
+/// A `tmux -C` client attached to one session
+struct Connection {
+    session: String,
+    stdin: tokio::sync::Mutex<ChildStdin>,
+    state: Mutex<ConnectionState>,
+}
+
+impl Connection {
+    /// Send a command line. Requests are queued in the order they're
+    /// written, which is the order tmux replies in.
+    async fn send(&self, request: Request, command: &str) -> Result<(), String> {
+        let mut stdin = self.stdin.lock().await;
+        self.state
+            .lock()
+            .map_err(|e| e.to_string())?
+            .pending
+            .push_back(request);
+        stdin
+            .write_all(format!("{}\n", command).as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write to tmux: {}", e))
+    }
+
+    /// Ask for a pane's screen, to be sent to `window`
+    async fn request_snapshot(&self, pane_id: &str, window: &str) -> Result<(), String> {
+        if !is_pane_id(pane_id) {
+            return Err(format!("Invalid pane ID: {}", pane_id));
+        }
+        self.send(
+            Request::Geometry,
+            &format!(
+                "display-message -p -t {} '#{{cursor_x}} #{{cursor_y}} #{{pane_width}} #{{pane_height}}'",
+                pane_id
+            ),
+        )
+        .await?;
+        self.send(
+            Request::Capture {
+                pane_id: pane_id.to_string(),
+                window: window.to_string(),
+            },
+            &format!("capture-pane -p -e -t {}", pane_id),
+        )
+        .await
+    }
+
+    fn subscribers(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .map(|state| state.subscribers.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn emit_all<S: Serialize + Clone>(&self, app: &AppHandle, channel: &str, payload: S) {
+        for window in self.subscribers() {
+            let _ = app.emit_to(window.as_str(), channel, payload.clone());
+        }
+    }
+
+    fn handle(&self, app: &AppHandle, message: ControlMessage) {
+        match message {
+            ControlMessage::Notification(Notification::Output { pane_id, data }) => {
+                let (seq, data) = {
+                    let Ok(mut state) = self.state.lock() else {
+                        return;
+                    };
+                    state.panes.entry(pane_id.clone()).or_default().push(&data)
+                };
+                if data.is_empty() {
+                    return;
+                }
+                let output = PaneOutput {
+                    session: self.session.clone(),
+                    pane_id,
+                    seq,
+                    data,
+                };
+                self.emit_all(app, TMUX_OUTPUT_CHANNEL, output);
+            }
+            ControlMessage::Notification(Notification::Exit { reason }) => {
+                if let Ok(mut state) = self.state.lock() {
+                    state.exit_reason = reason;
+                }
+            }
+            ControlMessage::Notification(notification) => {
+                let notification = SessionNotification {
+                    session: self.session.clone(),
+                    notification,
+                };
+                self.emit_all(app, TMUX_NOTIFICATION_CHANNEL, notification);
+            }
+            ControlMessage::Reply {
+                from_client: true,
+                ok,
+                lines,
+                ..
+            } => self.handle_reply(app, ok, lines),
+            // Replies to commands run elsewhere, like the initial attach
+            ControlMessage::Reply { .. } => {}
+        }
+    }
+
+    fn handle_reply(&self, app: &AppHandle, ok: bool, lines: Vec<Vec<u8>>) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let Some(request) = state.pending.pop_front() else {
+            return;
+        };
+        match request {
+            Request::Detach => {}
+            Request::Geometry => {
+                let numbers: Vec<u32> = lines
+                    .first()
+                    .map(|line| {
+                        String::from_utf8_lossy(line)
+                            .split(' ')
+                            .filter_map(|n| n.parse().ok())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                state.geometry = numbers.try_into().ok().filter(|_| ok);
+            }
+            Request::Capture { pane_id, window } => {
+                let geometry = state.geometry.take();
+                if !ok {
+                    log::warn!(
+                        "Failed to capture tmux pane {}: {}",
+                        pane_id,
+                        lines
+                            .first()
+                            .map(|line| String::from_utf8_lossy(line).into_owned())
+                            .unwrap_or_default()
+                    );
+                    return;
+                }
+                let [cursor_x, cursor_y, width, height] = geometry.unwrap_or([0, 0, 80, 24]);
+                let snapshot = PaneSnapshot {
+                    session: self.session.clone(),
+                    pane_id: pane_id.clone(),
+                    seq: state.panes.get(&pane_id).map(PaneStream::seq).unwrap_or(0),
+                    content: lines
+                        .iter()
+                        .map(|line| String::from_utf8_lossy(line))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    cursor_x,
+                    cursor_y,
+                    width,
+                    height,
+                };
+                drop(state);
+                let _ = app.emit_to(window.as_str(), TMUX_SNAPSHOT_CHANNEL, snapshot);
+            }
+        }
+    }
+}
+
+/// Pane IDs are `%` and a number; anything else could smuggle commands
+/// onto the control connection
+fn is_pane_id(pane_id: &str) -> bool {
+    pane_id
+        .strip_prefix('%')
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Open control-mode connections by session name, and the panes the UI
+/// won't send input to
+#[derive(Default)]
+pub struct TmuxState {
+    connections: Mutex<HashMap<String, Arc<Connection>>>,
+    read_only: Mutex<HashSet<String>>,
+}
+
+impl TmuxState {
+    /// The session's connection, attaching one if there isn't one yet
+    async fn connect(&self, app: &AppHandle, session: &str) -> Result<Arc<Connection>, String> {
+        if let Some(connection) = self.get(session)? {
+            return Ok(connection);
+        }
+
+        let found = Command::new("tmux")
+            .args(["has-session", "-t", session])
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run tmux: {}", e))?;
+        if !found.status.success() {
+            return Err(format!("Tmux session not found: {}", session));
+        }
+
+        self.attach(app, session)
+    }
+
+    /// Start a control-mode client unless another caller got there first
+    fn attach(&self, app: &AppHandle, session: &str) -> Result<Arc<Connection>, String> {
+        let mut connections = self.connections.lock().map_err(|e| e.to_string())?;
+        if let Some(connection) = connections.get(session) {
+            return Ok(connection.clone());
+        }
+        // Watching shouldn't shrink the session to this client's size. The
+        // flag has to be set once attached, and older tmux rejects it, which
+        // is harmless.
+        let mut child = Command::new("tmux")
+            .args(["-C", "attach-session", "-t", session])
+            .args([";", "refresh-client", "-f", "ignore-size"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start tmux control mode: {}", e))?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err("Failed to open tmux control mode pipes".to_string());
+        };
+
+        let connection = Arc::new(Connection {
+            session: session.to_string(),
+            stdin: tokio::sync::Mutex::new(stdin),
+            state: Mutex::new(ConnectionState::default()),
+        });
+        connections.insert(session.to_string(), connection.clone());
+        tauri::async_runtime::spawn(read_control(app.clone(), connection.clone(), child, stdout));
+        Ok(connection)
+    }
+
+    fn get(&self, session: &str) -> Result<Option<Arc<Connection>>, String> {
+        let connections = self.connections.lock().map_err(|e| e.to_string())?;
+        Ok(connections.get(session).cloned())
+    }
+}
+
+/// Read a connection until tmux closes it, then forget it and tell its
+/// subscribers
+async fn read_control(
+    app: AppHandle,
+    connection: Arc<Connection>,
+    mut child: Child,
+    stdout: ChildStdout,
+) {
+    let mut reader = BufReader::new(stdout);
+    let mut parser = ControlParser::default();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                log::warn!("Failed to read tmux control mode output: {}", e);
+                break;
+            }
+        }
+        if let Some(message) = parser.feed(&line) {
+            connection.handle(&app, message);
+        }
+    }
+    let _ = child.kill().await;
+
+    let state = app.state::<TmuxState>();
+    if let Ok(mut connections) = state.connections.lock() {
+        if connections
+            .get(&connection.session)
+            .is_some_and(|current| Arc::ptr_eq(current, &connection))
+        {
+            connections.remove(&connection.session);
+        }
+    }
+    let reason = connection
+        .state
+        .lock()
+        .ok()
+        .and_then(|mut state| state.exit_reason.take());
+    let notification = SessionNotification {
+        session: connection.session.clone(),
+        notification: Notification::Exit { reason },
+    };
+    connection.emit_all(&app, TMUX_NOTIFICATION_CHANNEL, notification);
+}
+
+/// Stream a session's pane output to this window. Each pane (or just
+/// `pane_id`) is snapshotted first; subscribing again re-sends snapshots.
+#[tauri::command]
+pub async fn subscribe_tmux_session(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, TmuxState>,
+    session: String,
+    pane_id: Option<String>,
+) -> Result<(), String> {
+    let connection = state.connect(&app, &session).await?;
+    connection
+        .state
+        .lock()
+        .map_err(|e| e.to_string())?
+        .subscribers
+        .insert(window.label().to_string());
+
+    let panes = match pane_id {
+        Some(pane_id) => vec![pane_id],
+        None => gastown::get_tmux_panes(session)
+            .await?
+            .into_iter()
+            .map(|pane| pane.pane_id)
+            .collect(),
+    };
+    for pane_id in panes {
+        connection
+            .request_snapshot(&pane_id, window.label())
+            .await?;
+    }
+    Ok(())
+}
+
+/// Stop streaming a session to this window. The connection detaches once
+/// no window is watching.
+#[tauri::command]
+pub async fn unsubscribe_tmux_session(
+    window: WebviewWindow,
+    state: State<'_, TmuxState>,
+    session: String,
+) -> Result<(), String> {
+    let Some(connection) = state.get(&session)? else {
+        return Ok(());
+    };
+    let idle = {
+        let mut connection_state = connection.state.lock().map_err(|e| e.to_string())?;
+        connection_state.subscribers.remove(window.label());
+        connection_state.subscribers.is_empty()
+    };
+    if idle {
+        state
+            .connections
+            .lock()
+            .map_err(|e| e.to_string())?
+            .remove(&session);
+        connection.send(Request::Detach, "detach-client").await?;
+    }
+    Ok(())
+}
+
+/// gt names the sessions its agents run in `gt-...`
+pub const AGENT_SESSION_PREFIX: &str = "gt-";
+
+/// Input for a pane
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PaneInput {
+    /// Typed as-is, with no key names looked up
+    Text { text: String },
+    /// tmux key names, e.g. `Enter`, `Up` or `C-c`
+    Keys { keys: Vec<String> },
+    /// Pasted in one go through a tmux buffer, bracketed if the program
+    /// asked for that
+    Paste { text: String },
+    /// An existing tmux paste buffer
+    Buffer { name: String },
+}
+
+impl PaneInput {
+    /// The tmux commands that deliver the input, in order. The last one is
+    /// what puts it into the pane, and what a confirmation token has to be
+    /// issued for.
+    pub fn commands(&self, pane_id: &str) -> Vec<Vec<String>> {
+        let command = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        match self {
+            PaneInput::Text { text } => {
+                vec![command(&["send-keys", "-t", pane_id, "-l", "--", text])]
+            }
+            PaneInput::Keys { keys } => {
+                let mut args = command(&["send-keys", "-t", pane_id, "--"]);
+                args.extend(keys.iter().cloned());
+                vec![args]
+            }
+            PaneInput::Paste { text } => {
+                let buffer = format!("gastownui-{}", pane_id.trim_start_matches('%'));
+                vec![
+                    command(&["set-buffer", "-b", &buffer, "--", text]),
+                    command(&["paste-buffer", "-p", "-d", "-b", &buffer, "-t", pane_id]),
+                ]
+            }
+            PaneInput::Buffer { name } => {
+                vec![command(&["paste-buffer", "-p", "-b", name, "-t", pane_id])]
+            }
+        }
+    }
+
+    /// Whether this sends Ctrl-C, as a key name or a raw control character
+    pub fn interrupts(&self) -> bool {
+        match self {
+            PaneInput::Text { text } | PaneInput::Paste { text } => text.contains('\u{3}'),
+            PaneInput::Keys { keys } => keys.iter().any(|key| {
+                key.eq_ignore_ascii_case("C-c")
+                    || key.eq_ignore_ascii_case("^c")
+                    || key.contains('\u{3}')
+            }),
+            PaneInput::Buffer { .. } => false,
+        }
+    }
+
+    /// Whether an agent's session has to have this confirmed: Ctrl-C, or a
+    /// paste buffer, whose content we can't see
+    pub fn needs_confirmation(&self) -> bool {
+        self.interrupts() || matches!(self, PaneInput::Buffer { .. })
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            PaneInput::Text { text } | PaneInput::Paste { text } => text.is_empty(),
+            PaneInput::Keys { keys } => keys.is_empty(),
+            PaneInput::Buffer { name } => name.is_empty(),
+        }
+    }
+}
+
+/// The session a pane is in, and whether tmux has its input turned off
+async fn pane_target(pane_id: &str) -> Result<(String, bool), String> {
+    let output = Command::new("tmux")
+        .args([
+            "display-message",
+            "-p",
+            "-t",
+            pane_id,
+            "#{session_name}\t#{pane_input_off}",
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run tmux: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Tmux pane not found: {}", pane_id));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (session, input_off) = stdout.trim_end().split_once('\t').unwrap_or((&stdout, "0"));
+    Ok((session.to_string(), input_off == "1"))
+}
+
+/// Type, press keys or paste into a pane. Read-only panes, and panes tmux
+/// has input turned off for, refuse input. Ctrl-C or a paste buffer to an
+/// agent's session is treated as destructive and needs a confirmation token
+/// from `request_pane_input_confirmation`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn send_keys_to_pane(
+    app: AppHandle,
+    tmux_state: State<'_, TmuxState>,
+    executor_state: State<'_, ExecutorState>,
+    runner_state: State<'_, RunnerState>,
+    pane_id: String,
+    input: PaneInput,
+    confirmation_token: Option<String>,
+    origin: Option<CommandOrigin>,
+) -> Result<(), String> {
+    if !is_pane_id(&pane_id) {
+        return Err(format!("Invalid pane ID: {}", pane_id));
+    }
+    if input.is_empty() {
+        return Err("Nothing to send".to_string());
+    }
+    let origin = origin.unwrap_or_default();
+    let commands = input.commands(&pane_id);
+
+    let read_only = tmux_state
+        .read_only
+        .lock()
+        .map_err(|e| e.to_string())?
+        .contains(&pane_id);
+    let (session, input_off) = pane_target(&pane_id).await?;
+    if read_only || input_off {
+        let e = format!("Pane {} is read-only", pane_id);
+        let mut argv = vec!["tmux".to_string()];
+        argv.extend(commands[0].iter().cloned());
+        audit::record(&app, &AuditEntry::denied(origin, argv, None, &e));
+        return Err(e);
+    }
+    let needs_confirmation =
+        input.needs_confirmation() && session.starts_with(AGENT_SESSION_PREFIX);
+
+    // Typing into a pane isn't on the general tmux allowlist, so it's
+    // classified here
+    for (i, args) in commands.iter().enumerate() {
+        let (risk, token) = if i + 1 == commands.len() && needs_confirmation {
+            (RiskClass::Destructive, confirmation_token.as_deref())
+        } else {
+            (RiskClass::Mutating, None)
+        };
+        let output = gastown::execute_as(
+            &app,
+            &executor_state,
+            &runner_state,
+            origin,
+            "tmux",
+            args,
+            risk,
+            None,
+            token,
+            None,
+            runner::DEFAULT_TIMEOUT,
+        )
+        .await?;
+        if !output.success {
+            return Err(format!(
+                "Failed to send input to {}: {}",
+                pane_id,
+                output.stderr.trim()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Ask the user, in a native dialog, to confirm sending `input` to a pane,
+/// and issue a token for the tmux command that delivers it
+#[tauri::command]
+pub async fn request_pane_input_confirmation(
+    app: AppHandle,
+    executor_state: State<'_, ExecutorState>,
+    pane_id: String,
+    input: PaneInput,
+) -> Result<CommandConfirmation, String> {
+    if !is_pane_id(&pane_id) {
+        return Err(format!("Invalid pane ID: {}", pane_id));
+    }
+    if input.is_empty() {
+        return Err("Nothing to send".to_string());
+    }
+    let mut argv = vec!["tmux".to_string()];
+    argv.extend(input.commands(&pane_id).pop().unwrap_or_default());
+    executor::confirm(&app, &executor_state, argv, RiskClass::Destructive).await
+}
+
+/// Stop (or allow again) input to a pane from the UI. Returns the panes
+/// that are read-only now.
+#[tauri::command]
+pub async fn set_pane_read_only(
+    state: State<'_, TmuxState>,
+    pane_id: String,
+    read_only: bool,
+) -> Result<Vec<String>, String> {
+    let mut panes = state.read_only.lock().map_err(|e| e.to_string())?;
+    if read_only {
+        panes.insert(pane_id);
+    } else {
+        panes.remove(&pane_id);
+    }
+    let mut panes: Vec<String> = panes.iter().cloned().collect();
+    panes.sort();
+    Ok(panes)
+}
+
+/// Panes the UI won't send input to
+#[tauri::command]
+pub async fn list_read_only_panes(state: State<'_, TmuxState>) -> Result<Vec<String>, String> {
+    let mut panes: Vec<String> = state
+        .read_only
+        .lock()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .cloned()
+        .collect();
+    panes.sort();
+    Ok(panes)
+}
+
+/// How `capture_tmux_pane_styled` lays out the styled text, besides the
+/// raw escape sequences
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureLayout {
+    /// Escape sequences only
+    #[default]
+    Raw,
+    /// Lines of styled spans
+    Spans,
+    /// Rows of `width` styled cells
+    Cells,
+}
+
+/// A pane's text with its colors and styling, and where the cursor is
+#[derive(Debug, Clone, Serialize)]
+pub struct PaneCapture {
+    pub target: String,
+    /// Captured lines with their SGR escape sequences
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spans: Option<Vec<Vec<StyledSpan>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cells: Option<Vec<Vec<Cell>>>,
+    /// Line in the capture where the visible screen starts; lines before
+    /// it are scrollback
+    pub screen_top: usize,
+    /// Cursor column and row, from the top of the visible screen
+    pub cursor_x: u32,
+    pub cursor_y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Capture a pane keeping its colors and styling, the last `lines` lines of
+/// scrollback (default 30) included, optionally parsed into spans or cells
+#[tauri::command]
+pub async fn capture_tmux_pane_styled(
+    target: String,
+    lines: Option<i32>,
+    layout: Option<CaptureLayout>,
+) -> Result<PaneCapture, String> {
+    let line_count = lines.unwrap_or(30).max(1);
+    let geometry = Command::new("tmux")
+        .args([
+            "display-message",
+            "-p",
+            "-t",
+            &target,
+            "#{cursor_x} #{cursor_y} #{pane_width} #{pane_height}",
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to capture tmux pane: {}", e))?;
+    if !geometry.status.success() {
+        let stderr = String::from_utf8_lossy(&geometry.stderr);
+        return Err(format!("Failed to capture tmux pane: {}", stderr));
+    }
+    let numbers: Vec<u32> = String::from_utf8_lossy(&geometry.stdout)
+        .split_whitespace()
+        .filter_map(|n| n.parse().ok())
+        .collect();
+    let [cursor_x, cursor_y, width, height]: [u32; 4] = numbers
+        .try_into()
+        .map_err(|_| "Failed to read tmux pane size".to_string())?;
+
+    let output = Command::new("tmux")
+        .args([
+            "capture-pane",
+            "-p",
+            "-e",
+            "-t",
+            &target,
+            "-S",
+            &format!("-{}", line_count),
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to capture tmux pane: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to capture tmux pane: {}", stderr));
+    }
+    let content = String::from_utf8_lossy(&output.stdout).into_owned();
+
+    // The visible screen is always captured in full, at the end
+    let screen_top = content.lines().count().saturating_sub(height as usize);
+    let layout = layout.unwrap_or_default();
+    Ok(PaneCapture {
+        target,
+        spans: (layout == CaptureLayout::Spans).then(|| ansi::parse_spans(&content)),
+        cells: (layout == CaptureLayout::Cells)
+            .then(|| ansi::parse_cells(&content, width as usize)),
+        content,
+        screen_top,
+        cursor_x,
+        cursor_y,
+        width,
+        height,
+    })
+}
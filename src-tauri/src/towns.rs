@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::beads;
+use crate::client::{BdCli, Cli, GtCli};
+use crate::events;
+
+/// File in the app config dir the registry is saved to
+const TOWNS_FILE: &str = "towns.json";
+
+/// Event pushed with the new active `Town` (or null) when it changes
+pub const ACTIVE_TOWN_CHANNEL: &str = "active-town-changed";
+
+/// A Gas Town workspace the UI can drive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Town {
+    pub id: String,
+    pub name: String,
+    pub root: PathBuf,
+}
+
+/// Registered towns, and the one commands use when not given a town ID
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TownRegistry {
+    #[serde(default)]
+    pub towns: Vec<Town>,
+    #[serde(default)]
+    pub active: Option<String>,
+}
+
+impl TownRegistry {
+    /// Register the town at `root`, named after its directory unless `name`
+    /// is given. The first town registered becomes the active one.
+    pub fn add(&mut self, root: &Path, name: Option<String>) -> Result<Town, String> {
+        let root = fs::canonicalize(root)
+            .map_err(|e| format!("Failed to read town directory {}: {}", root.display(), e))?;
+        if !root.is_dir() {
+            return Err(format!("Not a directory: {}", root.display()));
+        }
+        if let Some(existing) = self.towns.iter().find(|town| town.root == root) {
+            return Err(format!(
+                "{} is already registered as {}",
+                root.display(),
+                existing.id
+            ));
+        }
+
+        let name = name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .or_else(|| root.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "town".to_string());
+        let town = Town {
+            id: self.unique_id(&name),
+            name,
+            root,
+        };
+        self.towns.push(town.clone());
+        if self.active.is_none() {
+            self.active = Some(town.id.clone());
+        }
+        Ok(town)
+    }
+
+    /// Unregister a town. Removing the active town activates the first one
+    /// left, if any.
+    pub fn remove(&mut self, id: &str) -> Result<Town, String> {
+        let index = self
+            .towns
+            .iter()
+            .position(|town| town.id == id)
+            .ok_or_else(|| format!("Town not found: {}", id))?;
+        let town = self.towns.remove(index);
+        if self.active.as_deref() == Some(id) {
+            self.active = self.towns.first().map(|town| town.id.clone());
+        }
+        Ok(town)
+    }
+
+    /// Make a town the one commands use by default
+    pub fn set_active(&mut self, id: &str) -> Result<Town, String> {
+        let town = self
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("Town not found: {}", id))?;
+        self.active = Some(town.id.clone());
+        Ok(town)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Town> {
+        self.towns.iter().find(|town| town.id == id)
+    }
+
+    pub fn active(&self) -> Option<&Town> {
+        self.active.as_deref().and_then(|id| self.get(id))
+    }
+
+    /// The town named by `town_id`, or the active one. None when no town is
+    /// registered, so callers fall back to finding one from the environment.
+    pub fn select(&self, town_id: Option<&str>) -> Result<Option<&Town>, String> {
+        match town_id {
+            Some(id) => self
+                .get(id)
+                .map(Some)
+                .ok_or_else(|| format!("Town not found: {}", id)),
+            None => Ok(self.active()),
+        }
+    }
+
+    /// A slug of `name` that no registered town uses yet
+    fn unique_id(&self, name: &str) -> String {
+        let slug = name
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let base = if slug.is_empty() {
+            "town".to_string()
+        } else {
+            slug
+        };
+
+        let mut id = base.clone();
+        let mut n = 2;
+        while self.get(&id).is_some() {
+            id = format!("{}-{}", base, n);
+            n += 1;
+        }
+        id
+    }
+}
+
+/// The registry, loaded from the app config dir at startup
+#[derive(Default)]
+pub struct TownRegistryState {
+    registry: Mutex<TownRegistry>,
+}
+
+fn towns_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config directory: {}", e))?;
+    Ok(dir.join(TOWNS_FILE))
+}
+
+/// Load the saved registry into app state; missing or unreadable config
+/// means no registered towns
+pub fn load_registry(app: &AppHandle) {
+    let Ok(path) = towns_path(app) else {
+        return;
+    };
+    let registry = match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid town registry in {:?}: {}", path, e);
+            TownRegistry::default()
+        }),
+        Err(_) => TownRegistry::default(),
+    };
+    log::info!("Loaded {} registered towns", registry.towns.len());
+    *app.state::<TownRegistryState>().registry.lock().unwrap() = registry;
+}
+
+fn save(app: &AppHandle, registry: &TownRegistry) -> Result<(), String> {
+    let path = towns_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(registry)
+        .map_err(|e| format!("Failed to serialize town registry: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save town registry: {}", e))
+}
+
+/// Root of the town named by `town_id`, or of the active town. None when no
+/// town is registered.
+pub fn selected_root(app: &AppHandle, town_id: Option<&str>) -> Result<Option<PathBuf>, String> {
+    let state = app.state::<TownRegistryState>();
+    let registry = state.registry.lock().map_err(|e| e.to_string())?;
+    Ok(registry.select(town_id)?.map(|town| town.root.clone()))
+}
+
+/// ID of the town named by `town_id`, or of the active town. None when no
+/// town is registered.
+pub fn selected_id(app: &AppHandle, town_id: Option<&str>) -> Result<Option<String>, String> {
+    let state = app.state::<TownRegistryState>();
+    let registry = state.registry.lock().map_err(|e| e.to_string())?;
+    Ok(registry.select(town_id)?.map(|town| town.id.clone()))
+}
+
+/// Whether `town_id` names the active town; None always does
+pub fn is_active(app: &AppHandle, town_id: Option<&str>) -> bool {
+    town_id.is_none() || selected_id(app, town_id).ok() == selected_id(app, None).ok()
+}
+
+/// Root of the selected town, falling back to `GT_ROOT` or `~/gt` when no
+/// town is registered
+pub fn root(app: &AppHandle, town_id: Option<&str>) -> Result<PathBuf, String> {
+    match selected_root(app, town_id)? {
+        Some(root) => Ok(root),
+        None => default_root(),
+    }
+}
+
+/// Town root without a registry: `GT_ROOT` if set, otherwise `~/gt`
+pub fn default_root() -> Result<PathBuf, String> {
+    if let Ok(root) = std::env::var("GT_ROOT") {
+        return Ok(PathBuf::from(root));
+    }
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home.join("gt"))
+}
+
+/// An explicit beads dir, else the selected town's, else one found from the
+/// current directory
+pub fn beads_dir(
+    app: &AppHandle,
+    town_id: Option<&str>,
+    beads_dir: Option<String>,
+) -> Result<PathBuf, String> {
+    if beads_dir.is_none() {
+        if let Some(root) = selected_root(app, town_id)? {
+            return Ok(beads::resolve_redirect(&root.join(".beads")));
+        }
+    }
+    beads::resolve_beads_dir(beads_dir)
+}
+
+/// `gt`, run in the selected town
+pub fn gt(app: &AppHandle, town_id: Option<&str>) -> Result<GtCli, String> {
+    Ok(GtCli::new(
+        Cli::new("gt").in_town(selected_root(app, town_id)?),
+    ))
+}
+
+/// `bd`, run in the selected town
+pub fn bd(app: &AppHandle, town_id: Option<&str>) -> Result<BdCli, String> {
+    Ok(BdCli::new(
+        Cli::new("bd").in_town(selected_root(app, town_id)?),
+    ))
+}
+
+/// Change the registry and save it. If the active town changed, tell the
+/// frontend and move the events watcher over to it.
+fn update<T>(
+    app: &AppHandle,
+    state: &TownRegistryState,
+    change: impl FnOnce(&mut TownRegistry) -> Result<T, String>,
+) -> Result<T, String> {
+    // The events watcher reads the registry, so release it before following
+    let (result, registry, previous) = {
+        let mut registry = state.registry.lock().map_err(|e| e.to_string())?;
+        let previous = registry.active.clone();
+        let result = change(&mut registry)?;
+        (result, registry.clone(), previous)
+    };
+    save(app, &registry)?;
+    if registry.active != previous {
+        let _ = app.emit(ACTIVE_TOWN_CHANNEL, registry.active().cloned());
+        events::follow_active_town(app);
+    }
+    Ok(result)
+}
+
+/// List registered towns and which one is active
+#[tauri::command]
+pub async fn list_towns(state: State<'_, TownRegistryState>) -> Result<TownRegistry, String> {
+    Ok(state.registry.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Register a town by its root directory
+#[tauri::command]
+pub async fn add_town(
+    app: AppHandle,
+    state: State<'_, TownRegistryState>,
+    root: String,
+    name: Option<String>,
+) -> Result<Town, String> {
+    update(&app, &state, |registry| {
+        registry.add(Path::new(&root), name)
+    })
+}
+
+/// Unregister a town. Its files are left alone.
+#[tauri::command]
+pub async fn remove_town(
+    app: AppHandle,
+    state: State<'_, TownRegistryState>,
+    id: String,
+) -> Result<Town, String> {
+    update(&app, &state, |registry| registry.remove(&id))
+}
+
+/// Switch the town commands use when not given a town ID
+#[tauri::command]
+pub async fn set_active_town(
+    app: AppHandle,
+    state: State<'_, TownRegistryState>,
+    id: String,
+) -> Result<Town, String> {
+    update(&app, &state, |registry| registry.set_active(&id))
+}
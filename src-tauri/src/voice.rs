@@ -524,7 +524,7 @@ const SYSTEM_PROMPT_ASR: &str = "Perform ASR.";
 const SYSTEM_PROMPT_TTS: &str = "Perform TTS.";
 
 /// Agent persona types for Gas Town roles
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum AgentPersona {
     #[default]
@@ -622,6 +622,74 @@ Voice commands you understand:
 - "What's blocking?" - Show blockers
 - "How much today?" - Cost summary"#;
 
+/// Have the voice server speak a short summary of `lines` in a persona's voice
+pub(crate) async fn summarize_in_persona(
+    state: &VoiceServerState,
+    persona: &AgentPersona,
+    lines: &[String],
+) -> Result<VoiceResponse, String> {
+    let (url, is_ready) = {
+        let url = state.server_url.lock().map_err(|e| e.to_string())?;
+        let ready = state.is_ready.lock().map_err(|e| e.to_string())?;
+        (url.clone(), *ready)
+    };
+
+    if !is_ready {
+        return Err("Voice server not ready".to_string());
+    }
+
+    let client = reqwest::Client::new();
+    let api_url = format!("{}/v1/chat/completions", url);
+
+    let prompt = format!(
+        "Summarize what just happened in Gas Town in one or two spoken sentences:\n- {}",
+        lines.join("\n- ")
+    );
+    let payload = serde_json::json!({
+        "model": "",
+        "messages": [
+            {"role": "system", "content": get_persona_prompt(persona, None)},
+            {"role": "user", "content": prompt}
+        ],
+        "stream": false,
+        "max_tokens": 256,
+        "extra_body": {"reset_context": true}
+    });
+
+    let response = client
+        .post(&api_url)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Server error {}: {}", status, body));
+    }
+
+    let resp_json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let text = resp_json["choices"][0]["message"]["content"]
+        .as_str()
+        .unwrap_or("")
+        .to_string();
+
+    let audio_base64 = resp_json["choices"][0]["message"]["audio_chunk"]
+        .as_str()
+        .map(|s| s.to_string());
+
+    Ok(VoiceResponse {
+        text,
+        audio_base64,
+        audio_sample_rate: 24000,
+    })
+}
+
 /// Voice input configuration with optional persona
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct VoiceInputConfig {
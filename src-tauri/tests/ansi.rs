@@ -0,0 +1,69 @@
+use gastownui_lib::ansi::{parse_cells, parse_spans, Color, Style};
+
+fn span(spans: &[gastownui_lib::ansi::StyledSpan]) -> Vec<(&str, &Style)> {
+    spans.iter().map(|s| (s.text.as_str(), &s.style)).collect()
+}
+
+#[test]
+fn splits_capture_into_styled_spans() {
+    let content = "\x1b[1m\x1b[32m+ added\x1b[0m plain\n\x1b[31m- removed\nstill red\x1b[39m\n";
+
+    let lines = parse_spans(content);
+
+    assert_eq!(lines.len(), 3);
+    let bold_green = Style {
+        fg: Some(Color::Indexed(2)),
+        bold: true,
+        ..Style::default()
+    };
+    assert_eq!(
+        span(&lines[0]),
+        [("+ added", &bold_green), (" plain", &Style::default())]
+    );
+    let red = Style {
+        fg: Some(Color::Indexed(1)),
+        ..Style::default()
+    };
+    assert_eq!(span(&lines[1]), [("- removed", &red)]);
+    // tmux only writes changes, so the style carries over
+    assert_eq!(span(&lines[2]), [("still red", &red)]);
+}
+
+#[test]
+fn reads_extended_colors_and_skips_other_sequences() {
+    let content = "\x1b[38;5;208;48;2;0;0;255mx\x1b[38:2::255:0:0;4my\x1b]8;;https://x\x07\x1b[2Kz";
+
+    let lines = parse_spans(content);
+
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0][0].style.fg, Some(Color::Indexed(208)));
+    assert_eq!(
+        lines[0][0].style.bg,
+        Some(Color::Rgb("#0000ff".to_string()))
+    );
+    assert_eq!(lines[0][1].text, "yz");
+    assert_eq!(
+        lines[0][1].style.fg,
+        Some(Color::Rgb("#ff0000".to_string()))
+    );
+    assert!(lines[0][1].style.underline);
+
+    let json = serde_json::to_value(&lines[0][1]).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"text": "yz", "fg": "#ff0000", "bg": "#0000ff", "underline": true})
+    );
+}
+
+#[test]
+fn lays_out_cells() {
+    let rows = parse_cells("a\u{6f22}e\u{301}\n\x1b[7mb", 5);
+
+    assert_eq!(rows.len(), 2);
+    let chars: Vec<&str> = rows[0].iter().map(|c| c.ch.as_str()).collect();
+    // The wide character takes two cells and the accent joins its letter
+    assert_eq!(chars, ["a", "\u{6f22}", "", "e\u{301}", " "]);
+    assert!(rows[1][0].style.reverse);
+    assert_eq!(rows[1][1].ch, " ");
+    assert!(!rows[1][1].style.reverse);
+}
@@ -0,0 +1,427 @@
+mod common;
+
+use common::{Reply, TestTown};
+use gastownui_lib::beads::{find_beads_dir, BeadsIndex, HISTORY_LIMIT};
+use gastownui_lib::client::{BdCli, GtCli};
+use gastownui_lib::gastown::{activity_page, active_molecules};
+use gastownui_lib::runner::ProcessOutput;
+use gastownui_lib::setup::{check_dependencies, init_workspace};
+use serde_json::json;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn molecule(id: &str, progress: f64) -> String {
+    json!({
+        "id": id,
+        "name": format!("Molecule {}", id),
+        "description": null,
+        "steps": [],
+        "current_step": null,
+        "progress": progress,
+        "status": "in_progress",
+    })
+    .to_string()
+}
+
+/// Run `gt` the way `create_workspace` does, minus the runner and audit log
+fn run_gt(args: Vec<String>, cwd: Option<PathBuf>) -> std::future::Ready<Result<ProcessOutput, String>> {
+    let mut command = Command::new("gt");
+    command.args(&args);
+    if let Some(dir) = cwd {
+        command.current_dir(dir);
+    }
+    std::future::ready(
+        command
+            .output()
+            .map(ProcessOutput::from)
+            .map_err(|e| format!("Failed to run gt: {}", e)),
+    )
+}
+
+#[test]
+fn find_beads_dir_follows_rig_redirect() {
+    let town = TestTown::new("redirect");
+    let rig = town.add_redirected_rig("gastown");
+    let nested = rig.join("src");
+    fs::create_dir_all(&nested).unwrap();
+    let _env = town.activate(&nested);
+
+    let found = find_beads_dir().expect("beads dir");
+    assert_eq!(
+        fs::canonicalize(found).unwrap(),
+        fs::canonicalize(town.beads_dir()).unwrap()
+    );
+}
+
+#[test]
+fn find_beads_dir_falls_back_to_home_town() {
+    let town = TestTown::new("fallback");
+    let _env = town.activate(&town.bin);
+
+    assert_eq!(find_beads_dir(), Some(town.beads_dir()));
+}
+
+#[test]
+fn activity_feed_pages_newest_first() {
+    let town = TestTown::new("activity");
+    town.write_issues(&[
+        json!({"id": "gt-1", "title": "First", "status": "closed",
+               "created_at": "2026-01-01T10:00:00Z", "closed_at": "2026-01-01T12:00:00Z"}),
+        json!({"id": "gt-2", "title": "Second", "status": "open",
+               "created_at": "2026-01-01T11:00:00Z"}),
+    ]);
+
+    let index = BeadsIndex::open(&town.beads_dir()).unwrap();
+    let first = activity_page(&index, None, 2);
+    let seen: Vec<(&str, &str)> = first
+        .events
+        .iter()
+        .map(|e| (e.event_type.as_str(), e.target_id.as_str()))
+        .collect();
+    assert_eq!(seen, vec![("closed", "gt-1"), ("created", "gt-2")]);
+
+    let second = activity_page(&index, first.next_cursor.as_deref(), 2);
+    assert_eq!(second.events.len(), 1);
+    assert_eq!(second.events[0].event_type, "created");
+    assert_eq!(second.events[0].target_id, "gt-1");
+    assert_eq!(second.next_cursor, None);
+}
+
+fn append_issue(town: &TestTown, issue: serde_json::Value) {
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(town.beads_dir().join("issues.jsonl"))
+        .unwrap();
+    writeln!(file, "{}", issue).unwrap();
+}
+
+#[test]
+fn updates_record_each_transition() {
+    let town = TestTown::new("transitions");
+    town.write_issues(&[json!({"id": "gt-1", "title": "First", "status": "open",
+        "labels": ["old"], "created_at": "2026-01-01T10:00:00Z",
+        "updated_at": "2026-01-01T10:00:00Z"})]);
+    let mut index = BeadsIndex::open(&town.beads_dir()).unwrap();
+
+    // One update claims the bead, swaps a label for two others and reprioritizes
+    append_issue(
+        &town,
+        json!({"id": "gt-1", "title": "First", "status": "in_progress", "assignee": "toast",
+            "priority": 1, "labels": ["a", "b"], "created_at": "2026-01-01T10:00:00Z",
+            "updated_at": "2026-01-01T11:00:00Z"}),
+    );
+    let delta = index.refresh().unwrap();
+
+    let changes: Vec<(&str, Option<&str>, Option<&str>)> = delta
+        .activity
+        .iter()
+        .map(|e| {
+            (
+                e.event_type.as_str(),
+                e.old_value.as_deref(),
+                e.new_value.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            ("claimed", Some("open"), Some("in_progress")),
+            ("assigned", None, Some("toast")),
+            ("priority_changed", None, Some("1")),
+            ("labeled", None, Some("a")),
+            ("labeled", None, Some("b")),
+            ("unlabeled", Some("old"), None),
+        ]
+    );
+    assert!(delta
+        .activity
+        .iter()
+        .all(|e| e.timestamp == "2026-01-01T11:00:00Z"));
+}
+
+#[test]
+fn history_cursor_resumes_within_a_timestamp() {
+    let town = TestTown::new("cursor");
+    town.write_issues(&[json!({"id": "gt-1", "title": "First", "status": "open",
+        "created_at": "2026-01-01T10:00:00Z", "updated_at": "2026-01-01T10:00:00Z"})]);
+    let mut index = BeadsIndex::open(&town.beads_dir()).unwrap();
+    append_issue(
+        &town,
+        json!({"id": "gt-1", "title": "First", "status": "open", "labels": ["a", "b", "c"],
+            "created_at": "2026-01-01T10:00:00Z", "updated_at": "2026-01-01T11:00:00Z"}),
+    );
+    index.refresh().unwrap();
+
+    let mut seen = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let (page, next) = index.history_page(cursor.as_deref(), 2);
+        seen.extend(
+            page.iter()
+                .map(|e| (e.event_type.clone(), e.new_value.clone())),
+        );
+        match next {
+            Some(next) => {
+                // "<timestamp>#<seq>"
+                assert!(next.starts_with("2026-01-01T1"));
+                assert!(next.contains('#'));
+                cursor = Some(next);
+            }
+            None => break,
+        }
+    }
+
+    // Newest first; events sharing a timestamp come latest-recorded first
+    let label = |l: &str| ("labeled".to_string(), Some(l.to_string()));
+    assert_eq!(
+        seen,
+        [
+            label("c"),
+            label("b"),
+            label("a"),
+            ("created".to_string(), None)
+        ]
+    );
+}
+
+#[test]
+fn history_keeps_only_the_newest_events() {
+    let town = TestTown::new("history-limit");
+    let at = |i: usize| format!("2026-01-01T{:02}:{:02}:{:02}Z", i / 3600, i / 60 % 60, i % 60);
+    let issues: Vec<_> = (0..HISTORY_LIMIT + 5)
+        .map(|i| {
+            json!({"id": format!("gt-{}", i), "title": "Bead", "status": "open",
+                "created_at": at(i), "updated_at": at(i)})
+        })
+        .collect();
+    town.write_issues(&issues);
+    let index = BeadsIndex::open(&town.beads_dir()).unwrap();
+
+    let (newest, _) = index.history_page(None, 1);
+    assert_eq!(newest[0].target_id, format!("gt-{}", HISTORY_LIMIT + 4));
+
+    // Page to the end: the five oldest are gone
+    let mut seen = 0;
+    let mut oldest = None;
+    let mut cursor: Option<String> = None;
+    loop {
+        let (page, next) = index.history_page(cursor.as_deref(), 1_000);
+        seen += page.len();
+        oldest = page.last().map(|e| e.target_id.clone()).or(oldest);
+        match next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(seen, HISTORY_LIMIT);
+    assert_eq!(oldest.as_deref(), Some("gt-5"));
+}
+
+#[test]
+fn lines_written_just_before_rotation_are_kept() {
+    let town = TestTown::new("rotation");
+    let issue = |labels: &[&str], updated: &str| {
+        json!({"id": "gt-1", "title": "First", "status": "open", "labels": labels,
+            "created_at": "2026-01-01T10:00:00Z", "updated_at": updated})
+    };
+    town.write_issues(&[issue(&[], "2026-01-01T10:00:00Z")]);
+    let interactions = town.beads_dir().join("interactions.jsonl");
+    fs::write(&interactions, "").unwrap();
+    let mut index = BeadsIndex::open(&town.beads_dir()).unwrap();
+
+    // Both files get one more line, then are rotated away and replaced
+    append_issue(&town, issue(&["urgent"], "2026-01-01T11:00:00Z"));
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(&interactions)
+        .unwrap();
+    writeln!(
+        file,
+        "{}",
+        json!({"timestamp": "2026-01-01T11:30:00Z", "type": "comment",
+            "issue_id": "gt-1", "actor": "mayor"})
+    )
+    .unwrap();
+    let issues = town.beads_dir().join("issues.jsonl");
+    fs::rename(&issues, town.beads_dir().join("issues.jsonl.1")).unwrap();
+    fs::rename(&interactions, town.beads_dir().join("interactions.jsonl.1")).unwrap();
+    town.write_issues(&[issue(&[], "2026-01-01T12:00:00Z")]);
+    fs::write(&interactions, "").unwrap();
+
+    let delta = index.refresh().unwrap();
+
+    let seen: Vec<(&str, &str)> = delta
+        .activity
+        .iter()
+        .map(|e| (e.event_type.as_str(), e.timestamp.as_str()))
+        .collect();
+    assert_eq!(
+        seen,
+        [
+            ("labeled", "2026-01-01T11:00:00Z"),
+            ("unlabeled", "2026-01-01T12:00:00Z"),
+            ("comment", "2026-01-01T11:30:00Z"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn active_molecules_come_from_in_progress_beads() {
+    let town = TestTown::new("molecules");
+    town.stub(
+        "bd",
+        &[Reply::ok(
+            "list --json --status=in_progress --type=molecule",
+            &json!([{"id": "gt-10"}, {"id": "gt-11"}]).to_string(),
+        )],
+    );
+    town.stub(
+        "gt",
+        &[
+            Reply::ok("mol progress gt-10 --json", &molecule("gt-10", 50.0)),
+            Reply::fail("mol progress gt-11 --json", "no molecule", 1),
+        ],
+    );
+    let _env = town.activate(&town.town);
+
+    let molecules = active_molecules(&BdCli::default(), &GtCli::default()).await.unwrap();
+    assert_eq!(molecules.len(), 1);
+    assert_eq!(molecules[0].id, "gt-10");
+    assert_eq!(molecules[0].progress, 50.0);
+    assert_eq!(town.calls("gt").len(), 2);
+}
+
+#[tokio::test]
+async fn active_molecules_are_empty_when_bd_fails() {
+    let town = TestTown::new("molecules-fail");
+    town.stub("bd", &[Reply::fail("list *", "database locked", 1)]);
+    town.stub("gt", &[]);
+    let _env = town.activate(&town.town);
+
+    assert!(active_molecules(&BdCli::default(), &GtCli::default()).await.unwrap().is_empty());
+    assert!(town.calls("gt").is_empty());
+}
+
+#[tokio::test]
+async fn active_molecules_error_without_bd() {
+    let town = TestTown::new("molecules-missing");
+    let _env = town.activate(&town.town);
+
+    assert!(active_molecules(&BdCli::default(), &GtCli::default()).await.is_err());
+}
+
+#[tokio::test]
+async fn dependencies_report_versions_and_missing_tools() {
+    let town = TestTown::new("deps");
+    town.stub("git", &[Reply::ok("--version", "git version 2.43.0\n")]);
+    town.stub("tmux", &[Reply::ok("-V", "tmux 3.4\n")]);
+    town.stub("gt", &[Reply::ok("--version", "gt version 0.2.1\n")]);
+    town.stub("bd", &[Reply::ok("--version", "bd version 0.42.9\n")]);
+    let _env = town.activate(&town.town);
+
+    let status = check_dependencies().await.unwrap();
+    let installed = |name: &str| {
+        status
+            .dependencies
+            .iter()
+            .find(|d| d.name == name)
+            .unwrap_or_else(|| panic!("{} not checked", name))
+            .installed
+    };
+    assert!(installed("Git"));
+    assert!(installed("tmux"));
+    assert!(installed("gt (Gas Town CLI)"));
+    // Present but older than the minimum
+    assert!(!installed("bd (Beads CLI)"));
+    assert!(!installed("Go"));
+    assert_eq!(status.missing_count, 2);
+    // ~/gt has no .gt marker
+    assert!(!status.workspace_exists);
+    assert!(!status.ready);
+}
+
+#[tokio::test]
+async fn dependencies_ready_with_tools_and_workspace() {
+    let town = TestTown::new("deps-ready");
+    town.stub("git", &[Reply::ok("--version", "git version 2.43.0\n")]);
+    town.stub("go", &[Reply::ok("version", "go version go1.22.0 linux/amd64\n")]);
+    town.stub("tmux", &[Reply::ok("-V", "tmux 3.4\n")]);
+    town.stub("gt", &[Reply::ok("--version", "gt version 0.2.1\n")]);
+    town.stub("bd", &[Reply::ok("--version", "bd version 0.43.0\n")]);
+    fs::create_dir_all(town.town.join(".gt")).unwrap();
+    let _env = town.activate(&town.town);
+
+    let status = check_dependencies().await.unwrap();
+    assert_eq!(status.missing_count, 0);
+    assert!(status.workspace_exists);
+    assert_eq!(
+        status.workspace_path,
+        Some(town.town.to_string_lossy().to_string())
+    );
+    assert!(status.ready);
+}
+
+#[tokio::test]
+async fn create_workspace_runs_gt_install() {
+    let town = TestTown::new("install");
+    town.stub("gt", &[Reply::ok("install *", "Created HQ\n")]);
+    let _env = town.activate(&town.home);
+
+    let result = init_workspace(Some("~/new-town".to_string()), run_gt)
+        .await
+        .unwrap();
+    let path = town.home.join("new-town");
+    assert!(result.success, "{}", result.message);
+    assert!(path.is_dir());
+    assert_eq!(town.calls("gt"), vec![format!("install {}", path.display())]);
+}
+
+#[tokio::test]
+async fn create_workspace_falls_back_to_gt_init() {
+    let town = TestTown::new("init");
+    town.stub(
+        "gt",
+        &[
+            Reply::fail("install *", "unknown command \"install\"", 1),
+            Reply::ok("init", "Initialized\n"),
+        ],
+    );
+    let _env = town.activate(&town.home);
+
+    let result = init_workspace(Some("~/old-town".to_string()), run_gt)
+        .await
+        .unwrap();
+    assert!(result.success, "{}", result.message);
+    assert!(result.message.contains("gt init"));
+    assert_eq!(town.calls("gt").len(), 2);
+    assert_eq!(town.calls("gt")[1], "init");
+}
+
+#[tokio::test]
+async fn create_workspace_refuses_non_empty_directory() {
+    let town = TestTown::new("non-empty");
+    town.stub("gt", &[]);
+    let _env = town.activate(&town.home);
+
+    // ~/gt already holds .beads and .events.jsonl but no .gt marker
+    let result = init_workspace(None, run_gt).await.unwrap();
+    assert!(!result.success);
+    assert!(result.message.contains("not empty"));
+    assert!(town.calls("gt").is_empty());
+}
+
+#[tokio::test]
+async fn create_workspace_reports_gt_failure() {
+    let town = TestTown::new("install-fail");
+    town.stub("gt", &[Reply::fail("*", "permission denied", 2)]);
+    let _env = town.activate(&town.home);
+
+    let result = init_workspace(Some("~/broken".to_string()), run_gt)
+        .await
+        .unwrap();
+    assert!(!result.success);
+    assert!(result.message.contains("permission denied"));
+}
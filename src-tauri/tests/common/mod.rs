@@ -0,0 +1,214 @@
+// A throwaway Gas Town for backend integration tests: a town directory with
+// beads and events files, and scripted `gt`/`bd`/`tmux` stubs on PATH.
+#![allow(dead_code)]
+
+use std::ffi::OsString;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// PATH, HOME and the working directory are process-wide, so tests that
+/// activate a town take turns
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// One scripted response: when the stub's args match `pattern` (a shell
+/// `case` pattern over "$*"), print `stdout`/`stderr` and exit with `code`
+pub struct Reply {
+    pub pattern: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub code: i32,
+}
+
+impl Reply {
+    pub fn ok(pattern: &str, stdout: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            code: 0,
+        }
+    }
+
+    pub fn fail(pattern: &str, stderr: &str, code: i32) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+            code,
+        }
+    }
+}
+
+/// A scratch home directory holding a town at `~/gt` and a `bin` of stubs
+pub struct TestTown {
+    pub root: PathBuf,
+    pub home: PathBuf,
+    pub town: PathBuf,
+    pub bin: PathBuf,
+}
+
+impl TestTown {
+    pub fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!(
+            "gastownui-town-{}-{}-{}",
+            name,
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = fs::remove_dir_all(&root);
+        let home = root.join("home");
+        let town = home.join("gt");
+        let bin = root.join("bin");
+        fs::create_dir_all(town.join(".beads")).unwrap();
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(town.join(".beads/issues.jsonl"), "").unwrap();
+        fs::write(town.join(".events.jsonl"), "").unwrap();
+
+        // `which` is the one real binary the backend needs on PATH
+        if let Some(which) = find_on_path("which").and_then(|p| fs::canonicalize(p).ok()) {
+            std::os::unix::fs::symlink(which, bin.join("which")).unwrap();
+        }
+
+        Self {
+            root,
+            home,
+            town,
+            bin,
+        }
+    }
+
+    pub fn beads_dir(&self) -> PathBuf {
+        self.town.join(".beads")
+    }
+
+    /// Write issues.jsonl, one JSON object per line
+    pub fn write_issues(&self, issues: &[serde_json::Value]) {
+        write_jsonl(&self.beads_dir().join("issues.jsonl"), issues);
+    }
+
+    /// Write the town's .events.jsonl
+    pub fn write_events(&self, events: &[serde_json::Value]) {
+        write_jsonl(&self.town.join(".events.jsonl"), events);
+    }
+
+    /// Make a rig whose `.beads` redirects to the town's beads. Returns the rig dir.
+    pub fn add_redirected_rig(&self, name: &str) -> PathBuf {
+        let rig = self.town.join(name);
+        fs::create_dir_all(rig.join(".beads")).unwrap();
+        fs::write(rig.join(".beads/redirect"), "../../.beads\n").unwrap();
+        rig
+    }
+
+    /// Install a stub binary that answers with the first matching reply and
+    /// fails for anything else. Every call's args are appended to `<name>.log`.
+    pub fn stub(&self, name: &str, replies: &[Reply]) {
+        let mut script = String::from("#!/bin/sh\n");
+        script.push_str(&format!(
+            "printf '%s\\n' \"$*\" >> {}\n",
+            quote(&self.calls_path(name).to_string_lossy())
+        ));
+        script.push_str("case \"$*\" in\n");
+        for reply in replies {
+            // Spaces separate args in "$*"; keep them literal in the pattern
+            script.push_str(&format!("  {})\n", reply.pattern.replace(' ', "\\ ")));
+            if !reply.stdout.is_empty() {
+                script.push_str(&format!("    printf '%s' {}\n", quote(&reply.stdout)));
+            }
+            if !reply.stderr.is_empty() {
+                script.push_str(&format!("    printf '%s' {} >&2\n", quote(&reply.stderr)));
+            }
+            script.push_str(&format!("    exit {}\n    ;;\n", reply.code));
+        }
+        script.push_str(&format!(
+            "  *)\n    printf '%s\\n' \"{}: unexpected args: $*\" >&2\n    exit 127\n    ;;\nesac\n",
+            name
+        ));
+
+        let path = self.bin.join(name);
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    /// Args of every call made to a stub, in order
+    pub fn calls(&self, name: &str) -> Vec<String> {
+        fs::read_to_string(self.calls_path(name))
+            .map(|log| log.lines().map(|l| l.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    fn calls_path(&self, name: &str) -> PathBuf {
+        self.root.join(format!("{}.log", name))
+    }
+
+    /// Point PATH at the stubs only, HOME at the scratch home and the working
+    /// directory at `cwd`, until the guard is dropped
+    pub fn activate(&self, cwd: &Path) -> EnvGuard {
+        let lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let guard = EnvGuard {
+            _lock: lock,
+            path: std::env::var_os("PATH"),
+            home: std::env::var_os("HOME"),
+            gt_root: std::env::var_os("GT_ROOT"),
+            cwd: std::env::current_dir().unwrap(),
+        };
+        std::env::set_var("PATH", &self.bin);
+        std::env::set_var("HOME", &self.home);
+        std::env::remove_var("GT_ROOT");
+        std::env::set_current_dir(cwd).unwrap();
+        guard
+    }
+}
+
+impl Drop for TestTown {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Restores the environment a `TestTown` was activated over
+pub struct EnvGuard {
+    _lock: MutexGuard<'static, ()>,
+    path: Option<OsString>,
+    home: Option<OsString>,
+    gt_root: Option<OsString>,
+    cwd: PathBuf,
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.cwd);
+        restore("PATH", self.path.take());
+        restore("HOME", self.home.take());
+        restore("GT_ROOT", self.gt_root.take());
+    }
+}
+
+fn restore(key: &str, value: Option<OsString>) {
+    match value {
+        Some(value) => std::env::set_var(key, value),
+        None => std::env::remove_var(key),
+    }
+}
+
+fn write_jsonl(path: &Path, values: &[serde_json::Value]) {
+    let content: String = values.iter().map(|v| format!("{}\n", v)).collect();
+    fs::write(path, content).unwrap();
+}
+
+/// Single-quote a string for sh
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    std::env::split_paths(&path)
+        .chain([PathBuf::from("/usr/bin"), PathBuf::from("/bin")])
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::TestTown;
+use gastownui_lib::beads::BeadsIndex;
+use gastownui_lib::client::Convoy;
+use gastownui_lib::convoy::summarize;
+use serde_json::json;
+
+/// 2026-01-01T00:00:00Z
+const JAN_1: i64 = 1_767_225_600_000;
+
+fn convoy(beads: &[&str]) -> Convoy {
+    Convoy {
+        id: "cv-1".to_string(),
+        name: "Auth rewrite".to_string(),
+        status: "running".to_string(),
+        beads: beads.iter().map(|b| b.to_string()).collect(),
+        polecats: vec!["nux".to_string()],
+        created: "2026-01-01T00:00:00Z".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn joins_member_beads_and_extrapolates_close_rate() {
+    let town = TestTown::new("convoy-rate");
+    town.write_issues(&[
+        json!({"id": "gt-1", "title": "Schema", "status": "closed", "assignee": "nux",
+               "created_at": "2026-01-01T00:00:00Z", "closed_at": "2026-01-01T02:00:00Z"}),
+        json!({"id": "gt-2", "title": "Login", "status": "in_progress", "assignee": "furiosa",
+               "created_at": "2026-01-01T00:00:00Z"}),
+        json!({"id": "gt-3", "title": "Logout", "status": "open",
+               "created_at": "2026-01-01T00:00:00Z"}),
+    ]);
+    let index = BeadsIndex::open(&town.beads_dir()).unwrap();
+
+    let detail = summarize(&convoy(&["gt-1", "gt-2", "gt-3", "gt-404"]), Some(&index), JAN_1);
+
+    let statuses: Vec<(&str, &str)> = detail
+        .beads
+        .iter()
+        .map(|b| (b.id.as_str(), b.status.as_str()))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ("gt-1", "closed"),
+            ("gt-2", "in_progress"),
+            ("gt-3", "open"),
+            ("gt-404", "unknown"),
+        ]
+    );
+    assert_eq!(detail.polecats, vec!["furiosa", "nux"]);
+    assert_eq!(detail.active_polecats, 1);
+    assert_eq!((detail.beads_closed, detail.beads_total), (1, 4));
+    assert_eq!(detail.progress, 25.0);
+    assert_eq!(detail.status, "running");
+    // One bead closed two hours in; three more at that rate
+    assert_eq!(detail.eta.as_deref(), Some("2026-01-01T08:00:00Z"));
+}
+
+#[test]
+fn estimates_from_cycle_times_before_any_member_closes() {
+    let town = TestTown::new("convoy-cycle");
+    town.write_issues(&[
+        json!({"id": "old-1", "status": "closed",
+               "created_at": "2025-12-01T00:00:00Z", "closed_at": "2025-12-01T01:00:00Z"}),
+        json!({"id": "old-2", "status": "closed",
+               "created_at": "2025-12-02T00:00:00Z", "closed_at": "2025-12-02T03:00:00Z"}),
+        json!({"id": "old-3", "status": "closed",
+               "created_at": "2025-12-03T00:00:00Z", "closed_at": "2025-12-03T09:00:00Z"}),
+        json!({"id": "gt-1", "status": "hooked", "assignee": "nux",
+               "created_at": "2026-01-01T00:00:00Z"}),
+        json!({"id": "gt-2", "status": "open", "created_at": "2026-01-01T00:00:00Z"}),
+    ]);
+    let index = BeadsIndex::open(&town.beads_dir()).unwrap();
+
+    let detail = summarize(&convoy(&["gt-1", "gt-2"]), Some(&index), JAN_1);
+
+    // Median cycle time is three hours; one polecat works the two beads in turn
+    assert_eq!(detail.progress, 0.0);
+    assert_eq!(detail.eta.as_deref(), Some("2026-01-01T06:00:00Z"));
+}
+
+#[test]
+fn completed_convoy_has_no_eta() {
+    let town = TestTown::new("convoy-done");
+    town.write_issues(&[json!({"id": "gt-1", "status": "closed",
+        "created_at": "2026-01-01T00:00:00Z", "closed_at": "2026-01-01T01:00:00Z"})]);
+    let index = BeadsIndex::open(&town.beads_dir()).unwrap();
+
+    let detail = summarize(&convoy(&["gt-1"]), Some(&index), JAN_1);
+    assert_eq!(detail.status, "completed");
+    assert_eq!(detail.progress, 100.0);
+    assert_eq!(detail.eta, None);
+}
+
+#[test]
+fn falls_back_to_gt_without_a_beads_index() {
+    let mut from_gt = convoy(&["gt-1", "gt-2"]);
+    from_gt.progress = 40.0;
+    from_gt.eta = Some("2026-01-02T00:00:00Z".to_string());
+
+    let detail = summarize(&from_gt, None, JAN_1);
+    assert_eq!(detail.progress, 40.0);
+    assert_eq!(detail.eta.as_deref(), Some("2026-01-02T00:00:00Z"));
+    assert!(detail.beads.iter().all(|b| b.status == "unknown"));
+}
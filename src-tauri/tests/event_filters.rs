@@ -0,0 +1,74 @@
+use gastownui_lib::event_filters::{glob_match, EventFilters};
+use gastownui_lib::events::GasTownEvent;
+use serde_json::json;
+
+fn event(event_type: &str, actor: &str) -> GasTownEvent {
+    serde_json::from_value(json!({
+        "ts": "2026-01-01T10:00:00Z",
+        "source": "gt",
+        "actor": actor,
+        "type": event_type,
+        "payload": {},
+    }))
+    .unwrap()
+}
+
+fn filters(rules: serde_json::Value) -> EventFilters {
+    serde_json::from_value(json!({ "rules": rules })).unwrap()
+}
+
+#[test]
+fn matches_globs() {
+    assert!(glob_match("*/polecats/*", "gastown/polecats/toast"));
+    assert!(!glob_match("*/polecats/*", "gastown/witness"));
+    assert!(glob_match("to?st", "toast"));
+    assert!(!glob_match("to?st", "tost"));
+    assert!(glob_match("*", ""));
+    assert!(glob_match("a*b*c", "aXbYbZc"));
+    assert!(!glob_match("a*b", "aXbY"));
+    // A `*` in the pattern is a wildcard even where the text has a `*`
+    assert!(glob_match("*a", "*ba"));
+    assert!(glob_match("x*", "x*"));
+    assert!(!glob_match("", "x"));
+}
+
+#[test]
+fn highest_priority_rule_decides() {
+    let rules = filters(json!([
+        {"action": "deny", "priority": 0, "actor": "*/polecats/*"},
+        {"action": "allow", "priority": 5, "event_types": ["error"]},
+    ]));
+
+    // The error rule outranks the polecat rule
+    assert_eq!(
+        rules.decide(&event("error", "gastown/polecats/toast")),
+        Some(true)
+    );
+    assert_eq!(
+        rules.decide(&event("nudge", "gastown/polecats/toast")),
+        Some(false)
+    );
+    // No rule matches: left to the verbosity level
+    assert_eq!(rules.decide(&event("nudge", "mayor")), None);
+    assert_eq!(
+        EventFilters::default().decide(&event("error", "mayor")),
+        None
+    );
+}
+
+#[test]
+fn deny_wins_a_tie() {
+    let allow_first = filters(json!([
+        {"action": "allow", "priority": 1, "actor": "mayor"},
+        {"action": "deny", "priority": 1, "event_types": ["mail"]},
+    ]));
+    let deny_first = filters(json!([
+        {"action": "deny", "priority": 1, "event_types": ["mail"]},
+        {"action": "allow", "priority": 1, "actor": "mayor"},
+    ]));
+
+    for rules in [allow_first, deny_first] {
+        assert_eq!(rules.decide(&event("mail", "mayor")), Some(false));
+        assert_eq!(rules.decide(&event("nudge", "mayor")), Some(true));
+    }
+}
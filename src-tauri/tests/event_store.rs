@@ -0,0 +1,179 @@
+use gastownui_lib::event_store::{format_timestamp, parse_timestamp, EventStore, HistoryQuery};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Fresh scratch directory for one test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "gastownui-event-store-{}-{}-{}",
+        name,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn line(ts: &str, actor: &str) -> String {
+    format!(
+        r#"{{"ts":"{}","source":"gt","actor":"{}","type":"nudge","payload":{{}}}}"#,
+        ts, actor
+    )
+}
+
+fn append(path: &Path, text: &str) {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .unwrap();
+    file.write_all(text.as_bytes()).unwrap();
+}
+
+/// (ts, actor) of every stored event, oldest first
+fn stored(store: &EventStore) -> Vec<(String, String)> {
+    let query = HistoryQuery {
+        limit: Some(1000),
+        ..Default::default()
+    };
+    let mut events: Vec<_> = store
+        .query(&query)
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.ts, e.actor))
+        .collect();
+    events.reverse();
+    events
+}
+
+#[test]
+fn parses_and_formats_timestamps() {
+    assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+    assert_eq!(parse_timestamp("1970-01-01T00:00:01.5Z"), Some(1500));
+    assert_eq!(
+        parse_timestamp("2026-01-01T10:00:00+02:00"),
+        parse_timestamp("2026-01-01T08:00:00Z")
+    );
+    assert_eq!(
+        parse_timestamp("2024-02-29T00:00:00Z").unwrap()
+            - parse_timestamp("2024-02-28T00:00:00Z").unwrap(),
+        86_400_000
+    );
+    assert_eq!(parse_timestamp("1969-12-31T23:59:59Z"), Some(-1000));
+    assert_eq!(parse_timestamp("2026-13-01T00:00:00Z"), None);
+    assert_eq!(parse_timestamp("not a time"), None);
+    assert_eq!(parse_timestamp("2026-01-01T00:00:00.12é"), None);
+    assert_eq!(parse_timestamp("2026-01-01Téé:00:00Z"), None);
+    assert_eq!(parse_timestamp("2026-01-01T25:99:00Z"), None);
+    assert_eq!(parse_timestamp("2026-01-01T10:00:60Z"), None);
+    assert_eq!(parse_timestamp("2026-01-01T10:00:00+24:00"), None);
+    assert_eq!(parse_timestamp("2026-01-01T10:00:00+02:60"), None);
+    assert_eq!(parse_timestamp("2026-01-01T+1:00:00Z"), None);
+
+    for ts in [
+        "1970-01-01T00:00:00Z",
+        "2000-02-29T12:34:56Z",
+        "2100-03-01T00:00:00Z",
+        "1969-07-20T20:17:40Z",
+    ] {
+        let ms = parse_timestamp(ts).unwrap();
+        assert_eq!(parse_timestamp(&format_timestamp(ms)), Some(ms), "{}", ts);
+    }
+}
+
+#[test]
+fn keeps_late_events_in_order_and_drops_duplicates() {
+    let dir = temp_dir("order");
+    let mut store = EventStore::open(&dir).unwrap();
+
+    assert!(store.ingest(&line("2026-01-01T10:00:00Z", "a")).unwrap());
+    assert!(store.ingest(&line("2026-01-01T12:00:00Z", "c")).unwrap());
+    // Late, and older than the dedupe window
+    assert!(store
+        .ingest(&line("2026-01-01T09:00:00Z", "early"))
+        .unwrap());
+    assert!(store.ingest(&line("2026-01-01T11:00:00Z", "b")).unwrap());
+
+    // Replays of both recent and old events are dropped
+    assert!(!store.ingest(&line("2026-01-01T11:00:00Z", "b")).unwrap());
+    assert!(!store
+        .ingest(&line("2026-01-01T09:00:00Z", "early"))
+        .unwrap());
+    assert!(!store.ingest(&line("2026-01-01T10:00:00Z", "a")).unwrap());
+    // A different event at a stored timestamp isn't a duplicate
+    assert!(store.ingest(&line("2026-01-01T10:00:00Z", "a2")).unwrap());
+
+    let actors: Vec<_> = stored(&store).into_iter().map(|(_, a)| a).collect();
+    assert_eq!(actors, ["early", "a", "a2", "b", "c"]);
+
+    // Order and dedupe survive reopening
+    drop(store);
+    let mut store = EventStore::open(&dir).unwrap();
+    assert!(!store
+        .ingest(&line("2026-01-01T09:00:00Z", "early"))
+        .unwrap());
+    let actors: Vec<_> = stored(&store).into_iter().map(|(_, a)| a).collect();
+    assert_eq!(actors, ["early", "a", "a2", "b", "c"]);
+}
+
+#[test]
+fn repairs_torn_lines_and_missing_index_records() {
+    let dir = temp_dir("repair");
+    let mut store = EventStore::open(&dir).unwrap();
+    store.ingest(&line("2026-01-01T10:00:00Z", "a")).unwrap();
+    store.ingest(&line("2026-01-01T10:00:01Z", "b")).unwrap();
+    drop(store);
+
+    // A line written but never indexed, then one torn mid-write
+    append(
+        &dir.join("events.jsonl"),
+        &line("2026-01-01T10:00:02Z", "c"),
+    );
+    append(&dir.join("events.jsonl"), "\n{\"ts\":\"2026-01-01T1");
+    let index = fs::read(dir.join("events.idx")).unwrap();
+    fs::write(dir.join("events.idx"), &index[..index.len() - 3]).unwrap();
+
+    let mut store = EventStore::open(&dir).unwrap();
+    let actors: Vec<_> = stored(&store).into_iter().map(|(_, a)| a).collect();
+    assert_eq!(actors, ["a", "b", "c"]);
+
+    // The next event starts on a line of its own
+    assert!(store.ingest(&line("2026-01-01T10:00:03Z", "d")).unwrap());
+    drop(store);
+    let store = EventStore::open(&dir).unwrap();
+    let actors: Vec<_> = stored(&store).into_iter().map(|(_, a)| a).collect();
+    assert_eq!(actors, ["a", "b", "c", "d"]);
+}
+
+#[test]
+fn catch_up_resumes_from_the_saved_offset() {
+    let dir = temp_dir("catch-up");
+    let events = dir.join("source.jsonl");
+    append(&events, &format!("{}\n", line("2026-01-01T10:00:00Z", "a")));
+    append(&events, &format!("{}\n", line("2026-01-01T10:00:01Z", "b")));
+
+    let mut store = EventStore::open(&dir.join("history")).unwrap();
+    assert_eq!(store.catch_up(&events).unwrap(), 2);
+
+    // Rewrite the first line in place: a full rescan would store it
+    let content = fs::read_to_string(&events).unwrap();
+    fs::write(&events, content.replacen("\"a\"", "\"x\"", 1)).unwrap();
+    append(&events, &format!("{}\n", line("2026-01-01T10:00:02Z", "c")));
+    drop(store);
+
+    let mut store = EventStore::open(&dir.join("history")).unwrap();
+    assert_eq!(store.catch_up(&events).unwrap(), 1);
+    assert_eq!(store.catch_up(&events).unwrap(), 0);
+
+    // A truncated log is read from the start again
+    fs::write(&events, format!("{}\n", line("2026-01-01T10:00:03Z", "d"))).unwrap();
+    assert_eq!(store.catch_up(&events).unwrap(), 1);
+
+    let actors: Vec<_> = stored(&store).into_iter().map(|(_, a)| a).collect();
+    assert_eq!(actors, ["a", "b", "c", "d"]);
+}
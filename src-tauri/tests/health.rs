@@ -0,0 +1,132 @@
+use gastownui_lib::health::{
+    assess, is_agent, is_shell, HealthSignal, HealthState, HealthTracker, PaneSample, SessionSample,
+};
+
+fn session(name: &str, created: i64, command: &str, pid: u32, content: &str) -> SessionSample {
+    SessionSample {
+        name: name.to_string(),
+        created,
+        activity: Some(1_000),
+        panes: vec![PaneSample {
+            pane_id: "%1".to_string(),
+            command: command.to_string(),
+            pid,
+            dead: false,
+            content: content.to_string(),
+        }],
+    }
+}
+
+#[test]
+fn tells_shells_from_agents() {
+    assert!(is_shell("bash"));
+    assert!(is_shell("-zsh"));
+    assert!(!is_shell("claude"));
+    assert!(is_agent("claude"));
+    // Claude Code shows up under its version number
+    assert!(is_agent("2.0.14"));
+    assert!(!is_agent("vim"));
+}
+
+#[test]
+fn flags_prompts_dead_panes_and_exited_agents() {
+    let waiting = session(
+        "gt-polecat",
+        1,
+        "claude",
+        10,
+        "Edit file?\nDo you want to proceed? (y/n)\n\n",
+    );
+    let (state, signals) = assess(&waiting, 0, 5);
+    assert_eq!(state, HealthState::Stuck);
+    assert_eq!(
+        signals,
+        [HealthSignal::AwaitingInput {
+            pane_id: "%1".to_string(),
+            line: "Do you want to proceed? (y/n)".to_string(),
+        }]
+    );
+
+    let failed = session(
+        "gt-polecat",
+        1,
+        "claude",
+        10,
+        "Error: API Error: 529 overloaded\n",
+    );
+    // An error only counts once the agent has stopped moving
+    assert_eq!(assess(&failed, 0, 5), (HealthState::Responding, vec![]));
+    assert!(matches!(
+        assess(&failed, 0, 600).1[..],
+        [
+            HealthSignal::ErrorPrompt { .. },
+            HealthSignal::Unchanged { secs: 600 }
+        ]
+    ));
+    let build = session("gt-polecat", 1, "cargo", 10, "error: could not compile\n");
+    assert!(!assess(&build, 0, 600)
+        .1
+        .iter()
+        .any(|signal| matches!(signal, HealthSignal::ErrorPrompt { .. })));
+
+    let exited = session("gt-polecat", 1, "bash", 10, "$ ");
+    assert!(matches!(
+        assess(&exited, 0, 5).1[..],
+        [HealthSignal::AgentExited { .. }]
+    ));
+
+    let mut dead = session("gt-polecat", 1, "claude", 10, "");
+    dead.panes[0].dead = true;
+    assert_eq!(
+        assess(&dead, 0, 5),
+        (
+            HealthState::Stuck,
+            vec![HealthSignal::DeadProcess {
+                pane_id: "%1".to_string()
+            }]
+        )
+    );
+
+    // A plain shell session is just idle or responding
+    let shell = session("scratch", 1, "zsh", 10, "$ ");
+    assert_eq!(assess(&shell, 0, 5), (HealthState::Responding, vec![]));
+    assert_eq!(assess(&shell, 0, 3_600), (HealthState::Idle, vec![]));
+}
+
+#[test]
+fn tracks_unchanged_screens_and_restarts() {
+    let mut tracker = HealthTracker::default();
+
+    let report = tracker.observe(&session("gt-mayor", 1, "claude", 10, "thinking"), 1_010);
+    assert_eq!(report.state, HealthState::Responding);
+    assert_eq!(report.previous, None);
+
+    // Same screen for over ten minutes
+    let report = tracker.observe(&session("gt-mayor", 1, "claude", 10, "thinking"), 1_700);
+    assert_eq!(report.state, HealthState::Stuck);
+    assert_eq!(report.previous, Some(HealthState::Responding));
+    assert_eq!(report.signals, [HealthSignal::Unchanged { secs: 700 }]);
+
+    // The screen moved again
+    let report = tracker.observe(&session("gt-mayor", 1, "claude", 10, "done"), 1_710);
+    assert_eq!(report.state, HealthState::Responding);
+
+    // Respawned panes and recreated sessions count as restarts
+    tracker.observe(&session("gt-mayor", 1, "claude", 11, "done"), 1_720);
+    tracker.observe(&session("gt-mayor", 2, "claude", 12, "done"), 1_730);
+    let report = tracker.observe(&session("gt-mayor", 3, "claude", 13, "done"), 1_740);
+    assert_eq!(report.state, HealthState::Cycling);
+    assert!(report
+        .signals
+        .contains(&HealthSignal::Restarts { count: 5 }));
+
+    // Restarts age out of the window
+    let report = tracker.observe(&session("gt-mayor", 3, "claude", 13, "working"), 2_000);
+    assert_eq!(report.state, HealthState::Cycling);
+    let report = tracker.observe(
+        &session("gt-mayor", 3, "claude", 13, "still working"),
+        2_500,
+    );
+    assert_eq!(report.state, HealthState::Responding);
+    assert_eq!(report.previous, Some(HealthState::Cycling));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::{Reply, TestTown};
+use gastownui_lib::beads::Issue;
+use gastownui_lib::client::Polecat;
+use gastownui_lib::polecats::join;
+use serde_json::json;
+use std::fs;
+
+fn polecat(rig: &str, name: &str, current_work: Option<&str>) -> Polecat {
+    Polecat {
+        name: name.to_string(),
+        rig: rig.to_string(),
+        status: "active".to_string(),
+        current_work: current_work.map(str::to_string),
+        last_activity: None,
+    }
+}
+
+fn issue(value: serde_json::Value) -> Issue {
+    serde_json::from_value(value).unwrap()
+}
+
+#[tokio::test]
+async fn joins_sessions_worktrees_and_hooked_beads() {
+    let town = TestTown::new("polecats");
+    let nux_tree = town.town.join("gastown/polecats/nux");
+    fs::create_dir_all(&nux_tree).unwrap();
+    town.stub(
+        "tmux",
+        &[Reply::ok(
+            "list-sessions *",
+            "gt-gastown-nux\t1\t0\t1767225600\ngt-gastown-witness\t1\t0\t1767225600\n",
+        )],
+    );
+    town.stub(
+        "git",
+        &[
+            Reply::ok("-C * rev-parse --abbrev-ref HEAD", "polecat/nux\n"),
+            Reply::ok("-C * status --porcelain", " M src/main.rs\n?? notes.md\n"),
+        ],
+    );
+    let _env = town.activate(&town.town);
+
+    let claimed = vec![
+        issue(json!({"id": "gt-1", "title": "Old work", "status": "in_progress",
+                     "assignee": "gastown/polecats/nux"})),
+        issue(json!({"id": "gt-2", "title": "Hooked work", "status": "hooked",
+                     "assignee": "gastown/polecats/nux"})),
+        issue(json!({"id": "gt-3", "title": "Toast's", "status": "hooked",
+                     "assignee": "gastown/toast"})),
+    ];
+    let polecats = vec![
+        polecat("gastown", "nux", None),
+        polecat("gastown", "toast", None),
+        polecat("gastown", "slit", Some("gt-9")),
+    ];
+
+    let joined = join(polecats, &town.town, &claimed).await;
+
+    let nux = &joined[0];
+    assert_eq!(nux.session.as_ref().map(|s| s.name.as_str()), Some("gt-gastown-nux"));
+    let worktree = nux.worktree.as_ref().expect("nux worktree");
+    assert_eq!(worktree.path, nux_tree.to_string_lossy());
+    assert_eq!(worktree.branch.as_deref(), Some("polecat/nux"));
+    assert_eq!(worktree.changed_files, 2);
+    assert_eq!(nux.hooked_bead.as_ref().map(|b| b.id.as_str()), Some("gt-2"));
+
+    let toast = &joined[1];
+    assert!(toast.session.is_none());
+    assert!(toast.worktree.is_none());
+    assert_eq!(toast.hooked_bead.as_ref().map(|b| b.id.as_str()), Some("gt-3"));
+
+    // gt's own report of the current work wins, even for unindexed beads
+    let slit = joined[2].hooked_bead.as_ref().unwrap();
+    assert_eq!((slit.id.as_str(), slit.status.as_str()), ("gt-9", "unknown"));
+}
+
+#[tokio::test]
+async fn joins_without_tmux_or_git() {
+    let town = TestTown::new("polecats-bare");
+    fs::create_dir_all(town.town.join("gastown/polecats/nux")).unwrap();
+    let _env = town.activate(&town.town);
+
+    let joined = join(vec![polecat("gastown", "nux", None)], &town.town, &[]).await;
+
+    assert!(joined[0].session.is_none());
+    let worktree = joined[0].worktree.as_ref().unwrap();
+    assert_eq!(worktree.branch, None);
+    assert_eq!(worktree.changed_files, 0);
+    assert!(joined[0].hooked_bead.is_none());
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{Reply, TestTown};
+use gastownui_lib::beads::BeadsIndex;
+use gastownui_lib::client::{GtCli, GtClient, MergeRequest};
+use gastownui_lib::refinery::enrich;
+use serde_json::json;
+
+#[tokio::test]
+async fn reads_queue_from_gt() {
+    let town = TestTown::new("refinery-queue");
+    town.stub(
+        "gt",
+        &[Reply::ok(
+            "refinery queue gastown --json",
+            &json!([
+                {"id": "gt-mr-2", "rig": "gastown", "branch": "polecat/nux/gt-7",
+                 "worker": "nux", "position": 0, "status": "processing",
+                 "conflicts": false, "ci": "passed"},
+                {"id": "gt-mr-5", "rig": "gastown", "position": 1, "status": "queued"},
+            ])
+            .to_string(),
+        )],
+    );
+    let _env = town.activate(&town.town);
+
+    let queue = GtCli::default().refinery_queue(Some("gastown")).await.unwrap();
+
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue[0].author.as_deref(), Some("nux"));
+    assert_eq!(queue[0].has_conflicts, Some(false));
+    assert_eq!(queue[0].ci_status.as_deref(), Some("passed"));
+    assert_eq!(queue[1].position, 1);
+    assert_eq!(queue[1].has_conflicts, None);
+}
+
+#[test]
+fn fills_gaps_from_merge_request_beads() {
+    let town = TestTown::new("refinery-enrich");
+    town.write_issues(&[json!({
+        "id": "gt-mr-5",
+        "title": "Merge polecat/toast/gt-9",
+        "status": "open",
+        "issue_type": "merge-request",
+        "description": "branch: polecat/toast/gt-9\ntarget: main\nsource_issue: gt-9\nrig: gastown\nworker: toast\nconflicts: true\nci: failed",
+    })]);
+    let index = BeadsIndex::open(&town.beads_dir()).unwrap();
+
+    let mut entries = vec![
+        MergeRequest {
+            id: "gt-mr-5".to_string(),
+            ci_status: Some("pending".to_string()),
+            ..Default::default()
+        },
+        MergeRequest {
+            id: "gt-mr-404".to_string(),
+            ..Default::default()
+        },
+    ];
+    enrich(&mut entries, &index);
+
+    let mr = &entries[0];
+    assert_eq!(mr.branch, "polecat/toast/gt-9");
+    assert_eq!(mr.rig, "gastown");
+    assert_eq!(mr.target.as_deref(), Some("main"));
+    assert_eq!(mr.author.as_deref(), Some("toast"));
+    assert_eq!(mr.source_issue.as_deref(), Some("gt-9"));
+    assert_eq!(mr.has_conflicts, Some(true));
+    // What gt reported wins over the bead
+    assert_eq!(mr.ci_status.as_deref(), Some("pending"));
+
+    assert_eq!(
+        entries[1],
+        MergeRequest {
+            id: "gt-mr-404".to_string(),
+            ..Default::default()
+        }
+    );
+}
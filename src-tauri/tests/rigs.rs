@@ -0,0 +1,102 @@
+mod common;
+
+use common::TestTown;
+use gastownui_lib::beads::BeadsIndex;
+use gastownui_lib::rigs::{beads_cwd, discover, summarize};
+use serde_json::json;
+use std::fs;
+
+#[test]
+fn discovers_rigs_and_resolves_their_beads() {
+    let town = TestTown::new("rigs-discover");
+    town.add_redirected_rig("gastown");
+    fs::create_dir_all(town.town.join("beads/.beads")).unwrap();
+    fs::create_dir_all(town.town.join("beads/polecats/nux")).unwrap();
+    fs::create_dir_all(town.town.join("empty/refinery")).unwrap();
+    fs::create_dir_all(town.town.join("mayor/.beads")).unwrap();
+    fs::create_dir_all(town.town.join("docs")).unwrap();
+
+    let rigs = discover(&town.town).unwrap();
+
+    let names: Vec<&str> = rigs.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["beads", "empty", "gastown"]);
+
+    assert_eq!(rigs[0].beads_dir, Some(town.town.join("beads/.beads")));
+    assert!(!rigs[0].shared_beads);
+    // No beads of its own, so it uses the town's
+    assert_eq!(rigs[1].beads_dir, Some(town.beads_dir()));
+    assert!(rigs[1].shared_beads);
+    let gastown_beads = fs::canonicalize(rigs[2].beads_dir.as_ref().unwrap()).unwrap();
+    assert_eq!(gastown_beads, fs::canonicalize(town.beads_dir()).unwrap());
+    assert!(rigs[2].shared_beads);
+}
+
+#[test]
+fn summarizes_a_rig_from_shared_beads() {
+    let town = TestTown::new("rigs-summary");
+    town.add_redirected_rig("gastown");
+    fs::create_dir_all(town.town.join("gastown/polecats/nux")).unwrap();
+    fs::create_dir_all(town.town.join("gastown/polecats/toast")).unwrap();
+    fs::create_dir_all(town.town.join("beads/polecats")).unwrap();
+    town.write_issues(&[
+        json!({"id": "gt-1", "status": "open", "rig": "gastown",
+               "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z"}),
+        json!({"id": "gt-2", "status": "open", "rig": "gastown",
+               "dependencies": [{"issue_id": "gt-2", "depends_on_id": "gt-3", "type": "blocks"}],
+               "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z"}),
+        json!({"id": "gt-3", "status": "in_progress", "rig": "gastown",
+               "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-02T09:30:00Z"}),
+        json!({"id": "gt-4", "status": "open", "rig": "gastown",
+               "dependencies": [{"issue_id": "gt-4", "depends_on_id": "gt-5", "type": "blocks"}],
+               "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z"}),
+        json!({"id": "gt-5", "status": "closed", "rig": "gastown",
+               "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z",
+               "closed_at": "2026-01-01T12:00:00Z"}),
+        json!({"id": "gt-6", "status": "hooked", "rig": "gastown",
+               "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z"}),
+        json!({"id": "gt-mr-1", "status": "open", "issue_type": "merge-request",
+               "description": "branch: polecat/nux/gt-3\nrig: gastown",
+               "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z"}),
+        json!({"id": "gt-mr-0", "status": "closed", "issue_type": "merge-request",
+               "description": "rig: gastown",
+               "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z"}),
+        json!({"id": "bd-1", "status": "open", "rig": "beads",
+               "created_at": "2026-03-01T00:00:00Z", "updated_at": "2026-03-01T00:00:00Z"}),
+    ]);
+    let index = BeadsIndex::open(&town.beads_dir()).unwrap();
+    let rigs = discover(&town.town).unwrap();
+    let gastown = rigs.iter().find(|r| r.name == "gastown").unwrap();
+
+    let summary = summarize(gastown, index.issues());
+
+    assert_eq!(summary.polecat_count, 2);
+    // gt-1 and gt-4, whose blocker is closed
+    assert_eq!(summary.ready, 2);
+    assert_eq!(summary.blocked, 1);
+    assert_eq!(summary.in_progress, 2);
+    assert_eq!(summary.closed, 1);
+    assert_eq!(summary.queue_depth, 1);
+    // bd-1 is newer but belongs to another rig
+    assert_eq!(
+        summary.last_activity.as_deref(),
+        Some("2026-01-02T09:30:00Z")
+    );
+
+    let beads = rigs.iter().find(|r| r.name == "beads").unwrap();
+    let summary = summarize(beads, index.issues());
+    assert_eq!(summary.polecat_count, 0);
+    assert_eq!(summary.ready, 1);
+    assert_eq!(summary.queue_depth, 0);
+}
+
+#[test]
+fn runs_bd_where_the_rig_keeps_its_beads() {
+    let town = TestTown::new("rigs-beads-cwd");
+    fs::create_dir_all(town.town.join("beads/.beads")).unwrap();
+    fs::create_dir_all(town.town.join("empty/refinery")).unwrap();
+
+    assert_eq!(beads_cwd(&town.town, "beads"), town.town.join("beads"));
+    assert_eq!(beads_cwd(&town.town, "empty"), town.town);
+    assert_eq!(beads_cwd(&town.town, "../beads"), town.town);
+    assert_eq!(beads_cwd(&town.town, ""), town.town);
+}
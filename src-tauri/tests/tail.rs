@@ -0,0 +1,172 @@
+use gastownui_lib::tail::JsonlTail;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Fresh scratch directory for one test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "gastownui-tail-{}-{}-{}",
+        name,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn append(path: &PathBuf, text: &str) {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .unwrap();
+    file.write_all(text.as_bytes()).unwrap();
+}
+
+#[test]
+fn reads_appended_lines() {
+    let path = temp_dir("append").join(".events.jsonl");
+    append(&path, "{\"n\":1}\n");
+
+    let mut tail = JsonlTail::new(&path);
+    assert_eq!(tail.poll().unwrap().lines, vec!["{\"n\":1}"]);
+
+    append(&path, "{\"n\":2}\n{\"n\":3}\n");
+    let chunk = tail.poll().unwrap();
+    assert!(!chunk.reset);
+    assert_eq!(chunk.lines, vec!["{\"n\":2}", "{\"n\":3}"]);
+    assert!(tail.poll().unwrap().lines.is_empty());
+}
+
+#[test]
+fn from_end_skips_existing_lines() {
+    let path = temp_dir("from-end").join(".events.jsonl");
+    append(&path, "{\"old\":1}\n{\"old\":2}\n");
+
+    let mut tail = JsonlTail::from_end(&path);
+    assert!(tail.poll().unwrap().lines.is_empty());
+
+    append(&path, "{\"new\":1}\n");
+    assert_eq!(tail.poll().unwrap().lines, vec!["{\"new\":1}"]);
+}
+
+#[test]
+fn buffers_partial_trailing_line() {
+    let path = temp_dir("partial").join(".events.jsonl");
+    append(&path, "{\"n\":1}\n{\"n\":");
+
+    let mut tail = JsonlTail::new(&path);
+    assert_eq!(tail.poll().unwrap().lines, vec!["{\"n\":1}"]);
+
+    // Still incomplete: nothing new
+    append(&path, "2");
+    assert!(tail.poll().unwrap().lines.is_empty());
+
+    append(&path, "}\n");
+    assert_eq!(tail.poll().unwrap().lines, vec!["{\"n\":2}"]);
+}
+
+#[test]
+fn from_end_does_not_split_partial_line() {
+    let path = temp_dir("from-end-partial").join(".events.jsonl");
+    append(&path, "{\"old\":1}\n{\"half\":");
+
+    let mut tail = JsonlTail::from_end(&path);
+    assert!(tail.poll().unwrap().lines.is_empty());
+
+    append(&path, "1}\n");
+    assert_eq!(tail.poll().unwrap().lines, vec!["{\"half\":1}"]);
+}
+
+#[test]
+fn recovers_from_truncation() {
+    let path = temp_dir("truncate").join(".events.jsonl");
+    append(&path, "{\"n\":1}\n{\"n\":2}\n");
+
+    let mut tail = JsonlTail::new(&path);
+    assert_eq!(tail.poll().unwrap().lines.len(), 2);
+
+    // copytruncate-style rotation: same file, emptied, then written again
+    OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(&path)
+        .unwrap();
+    append(&path, "{\"n\":3}\n");
+
+    let chunk = tail.poll().unwrap();
+    assert!(chunk.reset);
+    assert_eq!(chunk.lines, vec!["{\"n\":3}"]);
+}
+
+#[test]
+fn detects_in_place_rewrite_of_same_size() {
+    let path = temp_dir("rewrite").join(".events.jsonl");
+    append(&path, "{\"n\":1}\n");
+
+    let mut tail = JsonlTail::new(&path);
+    assert_eq!(tail.poll().unwrap().lines.len(), 1);
+
+    fs::write(&path, "{\"n\":9}\n").unwrap();
+    let chunk = tail.poll().unwrap();
+    assert!(chunk.reset);
+    assert_eq!(chunk.lines, vec!["{\"n\":9}"]);
+}
+
+#[test]
+fn follows_rename_rotation_without_losing_lines() {
+    let dir = temp_dir("rotate");
+    let path = dir.join(".events.jsonl");
+    append(&path, "{\"n\":1}\n");
+
+    let mut tail = JsonlTail::new(&path);
+    assert_eq!(tail.poll().unwrap().lines.len(), 1);
+
+    // Written to the old file just before it is rotated away
+    append(&path, "{\"n\":2}\n");
+    fs::rename(&path, dir.join(".events.jsonl.1")).unwrap();
+    append(&path, "{\"n\":3}\n");
+
+    let chunk = tail.poll().unwrap();
+    assert!(chunk.reset);
+    assert_eq!(chunk.previous, vec!["{\"n\":2}"]);
+    assert_eq!(chunk.lines, vec!["{\"n\":3}"]);
+}
+
+#[test]
+fn recovers_after_delete_and_recreate() {
+    let path = temp_dir("recreate").join(".events.jsonl");
+    append(&path, "{\"n\":1}\n");
+
+    let mut tail = JsonlTail::new(&path);
+    assert_eq!(tail.poll().unwrap().lines.len(), 1);
+
+    fs::remove_file(&path).unwrap();
+    let chunk = tail.poll().unwrap();
+    assert!(chunk.reset);
+    assert!(chunk.lines.is_empty());
+
+    // Nothing there yet: polling is quiet rather than an error
+    let chunk = tail.poll().unwrap();
+    assert!(!chunk.reset);
+    assert!(chunk.lines.is_empty());
+
+    append(&path, "{\"n\":2}\n");
+    assert_eq!(tail.poll().unwrap().lines, vec!["{\"n\":2}"]);
+}
+
+#[test]
+fn starts_before_file_exists() {
+    let path = temp_dir("missing").join(".events.jsonl");
+
+    let mut tail = JsonlTail::from_end(&path);
+    assert!(tail.poll().unwrap().lines.is_empty());
+
+    append(&path, "{\"n\":1}\n");
+    assert_eq!(tail.poll().unwrap().lines, vec!["{\"n\":1}"]);
+}
@@ -0,0 +1,142 @@
+use gastownui_lib::terminal::{
+    custom_argv, detect, is_plain_session_name, launch_argv, TerminalConfig,
+};
+use std::ffi::OsString;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+/// Fresh directory holding fake executables
+fn bin_dir(name: &str, programs: &[&str]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "gastownui-terminal-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for program in programs {
+        let path = dir.join(program);
+        fs::write(&path, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    dir
+}
+
+fn config(launcher: &str, custom_command: Option<&str>) -> TerminalConfig {
+    TerminalConfig {
+        launcher: Some(launcher.to_string()),
+        custom_command: custom_command.map(str::to_string),
+    }
+}
+
+#[test]
+fn passes_the_session_as_one_argument() {
+    let session = "gt-x; rm -rf ~ $(whoami)";
+
+    assert_eq!(
+        launch_argv(&config("gnome-terminal", None), session, None).unwrap(),
+        [
+            "gnome-terminal",
+            "--",
+            "tmux",
+            "attach-session",
+            "-t",
+            session
+        ]
+    );
+    assert_eq!(
+        launch_argv(&config("wezterm", None), session, None).unwrap(),
+        [
+            "wezterm",
+            "start",
+            "--",
+            "tmux",
+            "attach-session",
+            "-t",
+            session
+        ]
+    );
+    assert_eq!(
+        launch_argv(&config("kitty", None), session, None).unwrap(),
+        ["kitty", "tmux", "attach-session", "-t", session]
+    );
+    assert!(launch_argv(&config("hyper", None), session, None).is_err());
+}
+
+#[test]
+fn fills_in_custom_commands() {
+    let session = "gt-x";
+
+    assert_eq!(
+        custom_argv(r#"st -t "tmux: {session}" -e {command}"#, session).unwrap(),
+        [
+            "st",
+            "-t",
+            "tmux: gt-x",
+            "-e",
+            "tmux",
+            "attach-session",
+            "-t",
+            session
+        ]
+    );
+    // The command goes last when the template doesn't say where
+    assert_eq!(
+        custom_argv("urxvt -e", session).unwrap(),
+        ["urxvt", "-e", "tmux", "attach-session", "-t", session]
+    );
+    assert!(custom_argv("xterm -e sh -c '{command}", session).is_err());
+    assert!(custom_argv("sh -c 'x {command}'", session).is_err());
+    assert!(custom_argv("  ", session).is_err());
+
+    // A session name that would break out of a shell is only ever passed as
+    // an argument of its own
+    let injected = "x; touch /tmp/pwned #";
+    assert!(custom_argv(r#"sh -c "tmux attach -t {session}""#, injected).is_err());
+    assert!(custom_argv(r#"xterm -T {session} -e {command}"#, injected).is_err());
+    assert_eq!(
+        custom_argv("xterm -e {command}", injected).unwrap(),
+        ["xterm", "-e", "tmux", "attach-session", "-t", injected]
+    );
+    assert!(launch_argv(&config("custom", None), session, None).is_err());
+}
+
+#[test]
+fn detects_installed_terminals_in_order() {
+    let first = bin_dir("first", &["xterm"]);
+    let second = bin_dir("second", &["alacritty", "foot"]);
+    let path: OsString = std::env::join_paths([&first, &second]).unwrap();
+
+    assert_eq!(detect(Some(&path)).map(|l| l.id), Some("alacritty"));
+    assert_eq!(
+        launch_argv(&TerminalConfig::default(), "gt-mayor", Some(&path)).unwrap(),
+        [
+            "alacritty",
+            "-e",
+            "tmux",
+            "attach-session",
+            "-t",
+            "gt-mayor"
+        ]
+    );
+
+    let empty = bin_dir("empty", &[]);
+    assert!(detect(Some(empty.as_os_str())).is_none());
+    assert!(launch_argv(
+        &TerminalConfig::default(),
+        "gt-mayor",
+        Some(empty.as_os_str())
+    )
+    .is_err());
+}
+
+#[test]
+fn only_plain_session_names_go_on_a_command_line() {
+    assert!(is_plain_session_name("gt-gastown-polecat_1"));
+    assert!(!is_plain_session_name(""));
+    assert!(!is_plain_session_name("a\" & do shell script \"id"));
+    assert!(!is_plain_session_name("x & calc"));
+    assert!(!is_plain_session_name("a\\b"));
+    assert!(!is_plain_session_name("my session"));
+}
@@ -0,0 +1,205 @@
+use gastownui_lib::tmux::{
+    parse_notification, unescape, ControlMessage, ControlParser, Notification, PaneInput,
+    PaneStream,
+};
+
+#[test]
+fn unescapes_pane_output() {
+    assert_eq!(unescape(b"ls\\015\\012"), b"ls\r\n");
+    assert_eq!(unescape(b"C:\\134dir"), b"C:\\dir");
+    assert_eq!(unescape(b"\\033[31mred"), b"\x1b[31mred");
+    // Not an octal escape
+    assert_eq!(unescape(b"a\\9b\\"), b"a\\9b\\");
+}
+
+#[test]
+fn parses_notifications() {
+    assert_eq!(
+        parse_notification(b"%output %3 hello\\015\\012"),
+        Some(Notification::Output {
+            pane_id: "%3".to_string(),
+            data: b"hello\r\n".to_vec(),
+        })
+    );
+    assert_eq!(
+        parse_notification(b"%extended-output %3 120 : late"),
+        Some(Notification::Output {
+            pane_id: "%3".to_string(),
+            data: b"late".to_vec(),
+        })
+    );
+    assert_eq!(
+        parse_notification(b"%window-add @4"),
+        Some(Notification::WindowAdd {
+            window_id: "@4".to_string()
+        })
+    );
+    assert_eq!(
+        parse_notification(b"%window-renamed @4 polecat nux"),
+        Some(Notification::WindowRenamed {
+            window_id: "@4".to_string(),
+            name: "polecat nux".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_notification(b"%session-changed $1 gt-gastown"),
+        Some(Notification::SessionChanged {
+            session_id: "$1".to_string(),
+            name: "gt-gastown".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_notification(b"%layout-change @4 b25d,80x24,0,0,2 b25d,80x24,0,0,2 *"),
+        Some(Notification::LayoutChange {
+            window_id: "@4".to_string(),
+            layout: "b25d,80x24,0,0,2".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_notification(b"%session-renamed $1 work"),
+        Some(Notification::SessionRenamed {
+            name: "work".to_string()
+        })
+    );
+    assert_eq!(
+        parse_notification(b"%sessions-changed"),
+        Some(Notification::SessionsChanged)
+    );
+    assert_eq!(
+        parse_notification(b"%exit"),
+        Some(Notification::Exit { reason: None })
+    );
+    assert_eq!(
+        parse_notification(b"%exit server exited"),
+        Some(Notification::Exit {
+            reason: Some("server exited".to_string())
+        })
+    );
+    assert_eq!(
+        parse_notification(b"%unlinked-window-add @9"),
+        Some(Notification::Other {
+            name: "unlinked-window-add".to_string(),
+            args: "@9".to_string(),
+        })
+    );
+    assert_eq!(parse_notification(b""), None);
+}
+
+#[test]
+fn collects_reply_blocks() {
+    let mut parser = ControlParser::default();
+
+    assert_eq!(parser.feed(b"%begin 1700000000 12 1\n"), None);
+    assert_eq!(parser.feed(b"$ echo %end\n"), None);
+    // Another command's guard doesn't end the block
+    assert_eq!(parser.feed(b"%end 1700000000 11 1\n"), None);
+    assert_eq!(
+        parser.feed(b"%end 1700000000 12 1\n"),
+        Some(ControlMessage::Reply {
+            number: 12,
+            from_client: true,
+            ok: true,
+            lines: vec![b"$ echo %end".to_vec(), b"%end 1700000000 11 1".to_vec()],
+        })
+    );
+
+    assert_eq!(parser.feed(b"%begin 1700000001 13 0\n"), None);
+    assert_eq!(parser.feed(b"can't find pane: %99\n"), None);
+    assert_eq!(
+        parser.feed(b"%error 1700000001 13 0\n"),
+        Some(ControlMessage::Reply {
+            number: 13,
+            from_client: false,
+            ok: false,
+            lines: vec![b"can't find pane: %99".to_vec()],
+        })
+    );
+
+    assert_eq!(
+        parser.feed(b"%window-close @2\n"),
+        Some(ControlMessage::Notification(Notification::WindowClose {
+            window_id: "@2".to_string()
+        }))
+    );
+}
+
+#[test]
+fn numbers_output_and_joins_split_characters() {
+    let mut stream = PaneStream::default();
+    let check = "\u{2713}".as_bytes();
+
+    assert_eq!(stream.push(b"ok "), (1, "ok ".to_string()));
+    // The first two bytes of a three-byte character wait for the last
+    assert_eq!(stream.push(&check[..2]), (1, String::new()));
+    assert_eq!(stream.push(&check[2..]), (2, "\u{2713}".to_string()));
+    assert_eq!(stream.seq(), 2);
+}
+
+fn argv(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+#[test]
+fn builds_send_keys_commands() {
+    let text = PaneInput::Text {
+        text: "-rf Enter".to_string(),
+    };
+    assert_eq!(
+        text.commands("%4"),
+        [argv(&["send-keys", "-t", "%4", "-l", "--", "-rf Enter"])]
+    );
+
+    let keys = PaneInput::Keys {
+        keys: vec!["Up".to_string(), "Enter".to_string()],
+    };
+    assert_eq!(
+        keys.commands("%4"),
+        [argv(&["send-keys", "-t", "%4", "--", "Up", "Enter"])]
+    );
+
+    let paste = PaneInput::Paste {
+        text: "line one\nline two".to_string(),
+    };
+    assert_eq!(
+        paste.commands("%4"),
+        [
+            argv(&[
+                "set-buffer",
+                "-b",
+                "gastownui-4",
+                "--",
+                "line one\nline two"
+            ]),
+            argv(&["paste-buffer", "-p", "-d", "-b", "gastownui-4", "-t", "%4"]),
+        ]
+    );
+}
+
+#[test]
+fn spots_interrupts() {
+    let keys = |keys: &[&str]| PaneInput::Keys { keys: argv(keys) };
+    assert!(keys(&["C-c"]).interrupts());
+    assert!(keys(&["Escape", "^C"]).interrupts());
+    assert!(!keys(&["C-d", "c"]).interrupts());
+    assert!(keys(&["\u{3}"]).interrupts());
+    assert!(PaneInput::Text {
+        text: "stop\u{3}".to_string()
+    }
+    .interrupts());
+    assert!(!PaneInput::Text {
+        text: "C-c".to_string()
+    }
+    .interrupts());
+    assert!(!PaneInput::Buffer {
+        name: "notes".to_string()
+    }
+    .interrupts());
+
+    // Agents need Ctrl-C and paste buffers confirmed
+    assert!(keys(&["C-c"]).needs_confirmation());
+    assert!(PaneInput::Buffer {
+        name: "notes".to_string()
+    }
+    .needs_confirmation());
+    assert!(!keys(&["Enter"]).needs_confirmation());
+}
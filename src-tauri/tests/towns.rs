@@ -0,0 +1,70 @@
+mod common;
+
+use common::TestTown;
+use gastownui_lib::client::Cli;
+use gastownui_lib::towns::TownRegistry;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+#[test]
+fn registers_switches_and_removes_towns() {
+    let first = TestTown::new("towns-first");
+    let second = TestTown::new("towns-second");
+    let mut registry = TownRegistry::default();
+
+    let home = registry.add(&first.town, None).unwrap();
+    assert_eq!(home.id, "gt");
+    assert_eq!(home.root, fs::canonicalize(&first.town).unwrap());
+    // The first town becomes active
+    assert_eq!(registry.select(None).unwrap(), Some(&home));
+
+    let work = registry
+        .add(&second.town, Some("Work Town".to_string()))
+        .unwrap();
+    assert_eq!(work.id, "work-town");
+    assert!(registry.add(&second.town, None).is_err());
+    assert!(registry.add(&second.root.join("missing"), None).is_err());
+
+    assert_eq!(registry.select(Some("work-town")).unwrap(), Some(&work));
+    assert!(registry.select(Some("nowhere")).is_err());
+    registry.set_active("work-town").unwrap();
+    assert_eq!(registry.select(None).unwrap(), Some(&work));
+
+    // Removing the active town falls back to the one left
+    registry.remove("work-town").unwrap();
+    assert_eq!(registry.select(None).unwrap(), Some(&home));
+    registry.remove("gt").unwrap();
+    assert_eq!(registry.select(None).unwrap(), None);
+    assert!(registry.remove("gt").is_err());
+}
+
+#[test]
+fn ids_stay_unique() {
+    let first = TestTown::new("towns-ids-a");
+    let second = TestTown::new("towns-ids-b");
+    let mut registry = TownRegistry::default();
+
+    let a = registry.add(&first.town, Some("HQ".to_string())).unwrap();
+    let b = registry.add(&second.town, Some("hq".to_string())).unwrap();
+    assert_eq!(a.id, "hq");
+    assert_eq!(b.id, "hq-2");
+}
+
+#[tokio::test]
+async fn cli_runs_in_the_town_root() {
+    let town = TestTown::new("towns-cli");
+    let script = town.bin.join("gt");
+    fs::write(&script, "#!/bin/sh\necho \"$PWD|$GT_ROOT\"\n").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    let _env = town.activate(&town.home);
+
+    let root = fs::canonicalize(&town.town).unwrap();
+    let output = Cli::new("gt")
+        .in_town(Some(root.clone()))
+        .run(&["status"])
+        .await
+        .unwrap();
+
+    let expected = format!("{}|{}", root.display(), root.display());
+    assert_eq!(output.stdout.trim(), expected);
+}
@@ -0,0 +1,90 @@
+import { useState } from 'react'
+import { Send, Lock, Unlock, OctagonX } from 'lucide-react'
+import { useSendKeysToPane, useReadOnlyPanes, useSetPaneReadOnly } from '../hooks/useTmux'
+import type { PaneInput } from '../types/tmux'
+import { focusRingClasses } from '../lib/a11y'
+
+interface PaneInputBarProps {
+  sessionName: string
+  paneId: string
+}
+
+/**
+ * Type into a pane without attaching. Ctrl-C to an agent's session asks
+ * first; a locked pane takes no input.
+ */
+export function PaneInputBar({ sessionName, paneId }: PaneInputBarProps) {
+  const [text, setText] = useState('')
+  const sendMutation = useSendKeysToPane()
+  const { data: readOnlyPanes } = useReadOnlyPanes()
+  const setReadOnly = useSetPaneReadOnly()
+  const readOnly = readOnlyPanes?.includes(paneId) ?? false
+  const isAgent = sessionName.startsWith('gt-')
+
+  const send = async (input: PaneInput, confirm?: boolean) => {
+    await sendMutation.mutateAsync({ paneId, input, confirm })
+  }
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault()
+    if (!text) return
+    try {
+      await send({ kind: 'text', text })
+      await send({ kind: 'keys', keys: ['Enter'] })
+      setText('')
+    } catch {
+      // Shown below the input
+    }
+  }
+
+  const handleInterrupt = () => {
+    send({ kind: 'keys', keys: ['C-c'] }, isAgent).catch(() => {})
+  }
+
+  return (
+    <div className="px-4 pb-3">
+      <form onSubmit={handleSubmit} className="flex items-center gap-2">
+        <input
+          type="text"
+          value={text}
+          onChange={(e) => setText(e.target.value)}
+          disabled={readOnly}
+          placeholder={readOnly ? 'Pane is read-only' : `Send to ${paneId}...`}
+          aria-label={`Input for pane ${paneId}`}
+          className={`flex-1 px-3 py-1.5 bg-slate-900 border border-slate-600 rounded text-sm text-slate-200 font-mono disabled:opacity-50 ${focusRingClasses}`}
+        />
+        <button
+          type="submit"
+          disabled={readOnly || !text || sendMutation.isPending}
+          className={`p-2 bg-cyan-500 hover:bg-cyan-600 disabled:bg-cyan-500/50 text-white rounded ${focusRingClasses}`}
+          aria-label="Send input and press Enter"
+        >
+          <Send className="w-4 h-4" aria-hidden="true" />
+        </button>
+        <button
+          type="button"
+          onClick={handleInterrupt}
+          disabled={readOnly || sendMutation.isPending}
+          className={`p-2 bg-slate-700 hover:bg-red-500/30 disabled:opacity-50 text-red-400 rounded ${focusRingClasses}`}
+          aria-label="Send Ctrl-C"
+          title="Send Ctrl-C"
+        >
+          <OctagonX className="w-4 h-4" aria-hidden="true" />
+        </button>
+        <button
+          type="button"
+          onClick={() => setReadOnly.mutate({ paneId, readOnly: !readOnly })}
+          className={`p-2 bg-slate-700 hover:bg-slate-600 text-slate-300 rounded ${focusRingClasses}`}
+          aria-label={readOnly ? 'Allow input to this pane' : 'Make this pane read-only'}
+          aria-pressed={readOnly}
+          title={readOnly ? 'Read-only' : 'Accepting input'}
+        >
+          {readOnly ? <Lock className="w-4 h-4" aria-hidden="true" /> : <Unlock className="w-4 h-4" aria-hidden="true" />}
+        </button>
+      </form>
+      {sendMutation.error && (
+        <p className="mt-1 text-xs text-red-400">{String(sendMutation.error)}</p>
+      )}
+    </div>
+  )
+}
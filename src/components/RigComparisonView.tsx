@@ -12,6 +12,7 @@ import {
   ChevronDown,
   Link2,
   Unlink2,
+  GitMerge,
 } from 'lucide-react'
 
 interface RigComparisonViewProps {
@@ -101,7 +102,7 @@ function RigPanel({
           </div>
           <div className="flex items-center gap-1">
             <Circle className="w-3 h-3 fill-current text-cyan-400" />
-            <span>{rig.beads_count.open} open</span>
+            <span>{rig.ready} ready</span>
           </div>
           <div className="flex items-center gap-1">
             <Circle className="w-3 h-3 fill-current text-amber-400" />
@@ -113,6 +114,12 @@ function RigPanel({
               <span>{rig.beads_count.blocked} blocked</span>
             </div>
           )}
+          {rig.queue_depth > 0 && (
+            <div className="flex items-center gap-1">
+              <GitMerge className="w-3 h-3" />
+              <span>{rig.queue_depth} queued</span>
+            </div>
+          )}
         </div>
       </div>
 
@@ -0,0 +1,91 @@
+import { useEffect, useState } from 'react'
+import { useTerminalLaunchers, useSetTerminalConfig } from '../hooks/useTmux'
+import { focusRingClasses } from '../lib/a11y'
+
+const AUTO = 'auto'
+const CUSTOM = 'custom'
+
+/**
+ * Pick the terminal Attach opens: auto-detected, one of the known ones, or
+ * a custom command. The custom command runs without a shell: `{session}`
+ * is filled in within its argument and `{command}` becomes the tmux command.
+ */
+export function TerminalLauncherSelect() {
+  const { data } = useTerminalLaunchers()
+  const setConfig = useSetTerminalConfig()
+  const [customCommand, setCustomCommand] = useState('')
+  // Custom is only saved once there's a command, so track the choice here
+  const [selected, setSelected] = useState(AUTO)
+
+  const savedLauncher = data?.config.launcher ?? AUTO
+  const savedCommand = data?.config.custom_command ?? ''
+  useEffect(() => {
+    setSelected(savedLauncher)
+    setCustomCommand(savedCommand)
+  }, [savedLauncher, savedCommand])
+
+  if (!data) return null
+
+  const detected = data.launchers.find((l) => l.id === data.detected)
+
+  const handleSelect = (value: string) => {
+    setSelected(value)
+    setConfig.reset()
+    if (value === CUSTOM) return
+    setConfig.mutate({
+      launcher: value === AUTO ? null : value,
+      custom_command: customCommand || null,
+    })
+  }
+
+  const handleCustomSubmit = (e: React.FormEvent) => {
+    e.preventDefault()
+    setConfig.mutate({ launcher: CUSTOM, custom_command: customCommand || null })
+  }
+
+  return (
+    <div className="flex flex-col items-end gap-1">
+      <label className="flex items-center gap-2 text-sm text-slate-400">
+        Terminal
+        <select
+          value={selected}
+          onChange={(e) => handleSelect(e.target.value)}
+          className={`px-2 py-1 bg-slate-800 border border-slate-600 rounded text-slate-200 ${focusRingClasses}`}
+        >
+          <option value={AUTO}>Auto{detected ? ` (${detected.name})` : ''}</option>
+          {data.launchers.map((launcher) => (
+            <option key={launcher.id} value={launcher.id}>
+              {launcher.name}
+              {launcher.installed ? '' : ' (not found)'}
+            </option>
+          ))}
+          <option value={CUSTOM}>Custom command...</option>
+        </select>
+      </label>
+      {selected === CUSTOM && (
+        <form onSubmit={handleCustomSubmit} className="flex items-center gap-2">
+          <input
+            type="text"
+            value={customCommand}
+            onChange={(e) => setCustomCommand(e.target.value)}
+            placeholder="st -t {session} -e {command}"
+            aria-label="Custom terminal command"
+            className={`w-64 px-2 py-1 bg-slate-900 border border-slate-600 rounded text-xs text-slate-200 font-mono ${focusRingClasses}`}
+          />
+          <button
+            type="submit"
+            disabled={setConfig.isPending}
+            className={`px-2 py-1 bg-slate-700 hover:bg-slate-600 text-slate-200 text-xs rounded ${focusRingClasses}`}
+          >
+            Save
+          </button>
+        </form>
+      )}
+      {setConfig.error && (
+        <p className="text-xs text-red-400" role="alert">
+          {String(setConfig.error)}
+        </p>
+      )}
+    </div>
+  )
+}
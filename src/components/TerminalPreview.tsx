@@ -1,11 +1,17 @@
-import { useRef, useEffect } from 'react'
+import { useRef, useEffect, Fragment, type ReactNode } from 'react'
+import type { StyledSpan } from '../types/tmux'
+import { cellStyle } from '../lib/ansi'
 
 interface TerminalPreviewProps {
   content: string
+  /** Styled lines from capture_tmux_pane_styled; shown instead of `content` */
+  spans?: StyledSpan[][]
+  /** Cursor row and column within `spans` */
+  cursor?: { row: number; column: number }
   className?: string
 }
 
-export function TerminalPreview({ content, className = '' }: TerminalPreviewProps) {
+export function TerminalPreview({ content, spans, cursor, className = '' }: TerminalPreviewProps) {
   const preRef = useRef<HTMLPreElement>(null)
 
   // Auto-scroll to bottom when content changes
@@ -13,7 +19,48 @@ export function TerminalPreview({ content, className = '' }: TerminalPreviewProp
     if (preRef.current) {
       preRef.current.scrollTop = preRef.current.scrollHeight
     }
-  }, [content])
+  }, [content, spans])
+
+  const renderLine = (line: StyledSpan[], row: number) => {
+    if (!cursor || cursor.row !== row) {
+      return line.map((span, i) => (
+        <span key={i} style={cellStyle(span)}>
+          {span.text}
+        </span>
+      ))
+    }
+
+    // Split the span under the cursor to draw it
+    const parts: ReactNode[] = []
+    let column = 0
+    line.forEach((span, i) => {
+      const chars = Array.from(span.text)
+      const at = cursor.column - column
+      column += chars.length
+      if (at < 0 || at >= chars.length) {
+        parts.push(<span key={i} style={cellStyle(span)}>{span.text}</span>)
+        return
+      }
+      parts.push(
+        <span key={i} style={cellStyle(span)}>
+          {chars.slice(0, at).join('')}
+          <span className="bg-[#f5e0dc] text-[#1e1e2e]">{chars[at]}</span>
+          {chars.slice(at + 1).join('')}
+        </span>
+      )
+    })
+    if (cursor.column >= column) {
+      parts.push(
+        <Fragment key="cursor">
+          {' '.repeat(cursor.column - column)}
+          <span className="bg-[#f5e0dc]"> </span>
+        </Fragment>
+      )
+    }
+    return parts
+  }
+
+  const hasSpans = spans && spans.length > 0
 
   return (
     <pre
@@ -25,9 +72,16 @@ export function TerminalPreview({ content, className = '' }: TerminalPreviewProp
         fontFamily: 'Monaco, Menlo, "DejaVu Sans Mono", monospace',
       }}
     >
-      {content || (
-        <span className="text-slate-500 italic">No output available</span>
-      )}
+      {hasSpans
+        ? spans.map((line, row) => (
+            <Fragment key={row}>
+              {renderLine(line, row)}
+              {'\n'}
+            </Fragment>
+          ))
+        : content || (
+            <span className="text-slate-500 italic">No output available</span>
+          )}
     </pre>
   )
 }
@@ -0,0 +1,68 @@
+import { useEffect, useRef, useState } from 'react'
+import { Terminal } from '@xterm/xterm'
+import '@xterm/xterm/css/xterm.css'
+import { useTmuxStream } from '../hooks/useTmux'
+
+interface TmuxPaneStreamProps {
+  sessionName: string
+  paneId: string
+  className?: string
+}
+
+/**
+ * Read-only live view of a tmux pane, sized to match it
+ */
+export function TmuxPaneStream({ sessionName, paneId, className = '' }: TmuxPaneStreamProps) {
+  const containerRef = useRef<HTMLDivElement>(null)
+  const terminalRef = useRef<Terminal | null>(null)
+  const [exited, setExited] = useState(false)
+
+  useEffect(() => {
+    if (!containerRef.current) return
+
+    const terminal = new Terminal({
+      disableStdin: true,
+      cursorBlink: false,
+      convertEol: false,
+      fontFamily: 'Monaco, Menlo, "DejaVu Sans Mono", monospace',
+      fontSize: 12,
+      theme: {
+        background: '#1e1e2e',
+        foreground: '#cdd6f4',
+      },
+    })
+    terminal.open(containerRef.current)
+    terminalRef.current = terminal
+
+    return () => {
+      terminalRef.current = null
+      terminal.dispose()
+    }
+  }, [])
+
+  useTmuxStream(sessionName, paneId, {
+    onSnapshot: (snapshot) => {
+      const terminal = terminalRef.current
+      if (!terminal) return
+      setExited(false)
+      terminal.reset()
+      terminal.resize(snapshot.width, snapshot.height)
+      // Redraw the screen, then put the cursor where later output expects it
+      terminal.write(snapshot.content.replace(/\n/g, '\r\n'))
+      terminal.write(`\x1b[${snapshot.cursor_y + 1};${snapshot.cursor_x + 1}H`)
+    },
+    onOutput: (data) => {
+      terminalRef.current?.write(data)
+    },
+    onExit: () => setExited(true),
+  })
+
+  return (
+    <div className={`bg-[#1e1e2e] p-2 overflow-auto rounded-lg ${className}`}>
+      <div ref={containerRef} />
+      {exited && (
+        <div className="text-xs text-slate-500 italic mt-1">Stream closed</div>
+      )}
+    </div>
+  )
+}
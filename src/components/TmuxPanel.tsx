@@ -13,37 +13,53 @@ import {
 import {
   useTmuxSessions,
   useTmuxSessionDetail,
-  useTmuxPaneContent,
+  useSessionHealth,
   useAttachTmuxSession,
   useCopyConnectionString,
 } from '../hooks/useTmux'
-import { TerminalPreview } from './TerminalPreview'
-import type { SessionHealth, TmuxSession } from '../types/tmux'
+import { TmuxPaneStream } from './TmuxPaneStream'
+import { PaneInputBar } from './PaneInputBar'
+import { TerminalLauncherSelect } from './TerminalLauncherSelect'
+import type { HealthSignal, SessionHealth, SessionHealthReport, TmuxSession } from '../types/tmux'
 import { focusRingClasses } from '../lib/a11y'
 import { isTauriEnvironment } from '../lib/setupDetector'
 
 const healthColors: Record<SessionHealth, { dot: string; text: string; label: string; tooltip: string }> = {
-  active: { dot: 'text-green-400', text: 'text-green-400', label: 'Active', tooltip: 'Session is actively responding' },
-  processing: { dot: 'text-yellow-400', text: 'text-yellow-400', label: 'Processing', tooltip: 'Running a command or task' },
+  responding: { dot: 'text-green-400', text: 'text-green-400', label: 'Responding', tooltip: 'Session is actively responding' },
   idle: { dot: 'text-slate-400', text: 'text-slate-400', label: 'Idle', tooltip: 'Session is idle at shell prompt' },
-  stuck: { dot: 'text-red-400 animate-pulse', text: 'text-red-400', label: 'Stuck', tooltip: 'Session may be stuck - no recent activity' },
+  stuck: { dot: 'text-red-400 animate-pulse', text: 'text-red-400', label: 'Stuck', tooltip: 'Session needs attention' },
+  cycling: { dot: 'text-orange-400 animate-pulse', text: 'text-orange-400', label: 'Cycling', tooltip: 'Session keeps restarting' },
+}
+
+function describeSignal(signal: HealthSignal): string {
+  switch (signal.kind) {
+    case 'restarts':
+      return `Restarted ${signal.count} times in the last 10 minutes`
+    case 'dead_process':
+      return `Process in pane ${signal.pane_id} has exited`
+    case 'error_prompt':
+      return `Error: ${signal.line}`
+    case 'awaiting_input':
+      return `Waiting for input: ${signal.line}`
+    case 'agent_exited':
+      return `Agent exited to ${signal.command}`
+    case 'unchanged':
+      return `No output for ${Math.floor(signal.secs / 60)} minutes`
+  }
 }
 
 interface SessionCardProps {
   session: TmuxSession
+  report?: SessionHealthReport
   isExpanded: boolean
   onToggle: () => void
 }
 
-function SessionCard({ session, isExpanded, onToggle }: SessionCardProps) {
+function SessionCard({ session, report, isExpanded, onToggle }: SessionCardProps) {
   const { data: details, isLoading } = useTmuxSessionDetail(
     isExpanded ? session.name : null
   )
-  const { data: paneContent } = useTmuxPaneContent(
-    isExpanded && details?.panes[0]?.pane_id
-      ? details.panes[0].pane_id
-      : null
-  )
+  const paneId = details?.panes[0]?.pane_id ?? null
   const attachMutation = useAttachTmuxSession()
   const copyMutation = useCopyConnectionString()
   const [copySuccess, setCopySuccess] = useState(false)
@@ -56,8 +72,10 @@ function SessionCard({ session, isExpanded, onToggle }: SessionCardProps) {
     }
   }, [copySuccess])
 
-  const health = details?.health ?? 'idle'
+  const health = details?.health ?? report?.state ?? 'idle'
+  const signals = details?.health_signals ?? report?.signals ?? []
   const colors = healthColors[health]
+  const tooltip = signals.length > 0 ? signals.map(describeSignal).join('. ') : colors.tooltip
   const connectionString = details?.connection_string ?? `tmux attach -t ${session.name}`
 
   const handleAttach = () => {
@@ -101,7 +119,8 @@ function SessionCard({ session, isExpanded, onToggle }: SessionCardProps) {
         <div
           className="flex items-center gap-2"
           role="status"
-          aria-label={`Session health: ${colors.label}. ${colors.tooltip}`}
+          aria-label={`Session health: ${colors.label}. ${tooltip}`}
+          title={tooltip}
         >
           <Circle className={`w-3 h-3 fill-current ${colors.dot}`} aria-hidden="true" />
           <span className={`text-sm ${colors.text}`}>{colors.label}</span>
@@ -118,9 +137,10 @@ function SessionCard({ session, isExpanded, onToggle }: SessionCardProps) {
                 <RefreshCw className="w-5 h-5 animate-spin mr-2" />
                 Loading...
               </div>
-            ) : paneContent ? (
-              <TerminalPreview
-                content={paneContent}
+            ) : paneId ? (
+              <TmuxPaneStream
+                sessionName={session.name}
+                paneId={paneId}
                 className="h-48 border border-slate-600"
               />
             ) : (
@@ -130,6 +150,9 @@ function SessionCard({ session, isExpanded, onToggle }: SessionCardProps) {
             )}
           </div>
 
+          {/* Pane Input */}
+          {paneId && <PaneInputBar sessionName={session.name} paneId={paneId} />}
+
           {/* Pane Info */}
           {details?.panes && details.panes.length > 0 && (
             <div className="px-4 pb-3">
@@ -164,6 +187,11 @@ function SessionCard({ session, isExpanded, onToggle }: SessionCardProps) {
               {copySuccess ? 'Copied!' : 'Copy'}
             </button>
           </div>
+          {attachMutation.error && (
+            <p className="px-4 pb-4 -mt-2 text-xs text-red-400" role="alert">
+              {String(attachMutation.error)}
+            </p>
+          )}
 
           {/* Connection String */}
           <div className="px-4 pb-4">
@@ -188,6 +216,7 @@ export default function TmuxPanel() {
   const isTauri = typeof window !== 'undefined' && isTauriEnvironment()
 
   const { data: sessions, isLoading, error, refetch } = useTmuxSessions()
+  const { data: healthReports } = useSessionHealth({ enabled: isTauri })
   const [expandedSessions, setExpandedSessions] = useState<Set<string>>(new Set())
 
   // Show browser fallback if not in Tauri environment
@@ -256,9 +285,12 @@ export default function TmuxPanel() {
           <Terminal className="w-6 h-6 text-cyan-400" />
           <h2 className="text-xl font-bold text-white">TMUX SESSIONS</h2>
         </div>
-        <div className="flex items-center gap-2 text-sm text-slate-400">
-          <kbd className="px-2 py-1 bg-slate-700 rounded text-xs">Cmd+T</kbd>
-          <span>to jump</span>
+        <div className="flex items-start gap-4">
+          <TerminalLauncherSelect />
+          <div className="flex items-center gap-2 text-sm text-slate-400">
+            <kbd className="px-2 py-1 bg-slate-700 rounded text-xs">Cmd+T</kbd>
+            <span>to jump</span>
+          </div>
         </div>
       </div>
 
@@ -282,6 +314,7 @@ export default function TmuxPanel() {
             <SessionCard
               key={session.name}
               session={session}
+              report={healthReports?.find((r) => r.session === session.name)}
               isExpanded={expandedSessions.has(session.name)}
               onToggle={() => toggleSession(session.name)}
             />
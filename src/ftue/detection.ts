@@ -90,10 +90,13 @@ export class SetupDetector {
   /** Check if Go is installed */
   async checkGo(): Promise<{ hasGo: boolean; goVersion?: string }> {
     try {
-      const result = await runCommand('go', ['version'])
-      const match = result.stdout.match(/go(\d+\.\d+(\.\d+)?)/)
+      const status = await invoke<{ dependencies: { name: string; installed: boolean; version?: string }[] }>(
+        'check_dependencies'
+      )
+      const go = status.dependencies.find(dep => dep.name === 'Go')
+      const match = go?.version?.match(/go(\d+\.\d+(\.\d+)?)/)
       return {
-        hasGo: true,
+        hasGo: !!go?.installed,
         goVersion: match?.[1],
       }
     } catch {
@@ -214,8 +217,7 @@ export class SetupDetector {
   /** Check if Homebrew is installed (macOS) */
   async checkHomebrew(): Promise<boolean> {
     try {
-      await runCommand('brew', ['--version'])
-      return true
+      return await invoke<boolean>('check_homebrew')
     } catch {
       return false
     }
@@ -224,10 +226,7 @@ export class SetupDetector {
   /** Check if GOPATH/bin is in PATH */
   async checkGobinInPath(): Promise<boolean> {
     try {
-      const result = await runCommand('go', ['env', 'GOPATH'])
-      const gopath = result.stdout.trim()
-      const pathResult = await invoke<string>('get_env', { name: 'PATH' })
-      return pathResult.includes(`${gopath}/bin`) || pathResult.includes(`${gopath}\\bin`)
+      return await invoke<boolean>('check_gobin_in_path')
     } catch {
       return false
     }
@@ -0,0 +1,64 @@
+/**
+ * Command audit log.
+ *
+ * Every mutating or destructive command run through the backend, and every
+ * refused one, is recorded with where it came from, its argv, exit code and
+ * duration. Entries can be re-run through the same allowlist checks.
+ */
+
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
+import { invoke } from '@tauri-apps/api/core'
+import type { CommandResult } from '../types/gastown'
+
+export type CommandOrigin = 'ui' | 'voice' | 'self_test'
+
+export type AuditOutcome = 'ran' | 'denied' | 'failed'
+
+export interface AuditEntry {
+  id: string
+  timestamp: string
+  origin: CommandOrigin
+  argv: string[]
+  risk: 'read_only' | 'mutating' | 'destructive' | null
+  outcome: AuditOutcome
+  exit_code: number | null
+  duration_ms: number | null
+  error: string | null
+}
+
+export interface AuditQuery {
+  since?: string
+  until?: string
+  origin?: CommandOrigin
+  program?: string
+  outcome?: AuditOutcome
+  limit?: number
+}
+
+/**
+ * Audit log entries matching a query, newest first
+ */
+export function useAuditLog(query: AuditQuery = {}) {
+  return useQuery({
+    queryKey: ['auditLog', query],
+    queryFn: () => invoke<AuditEntry[]>('query_audit_log', { query }),
+    refetchInterval: 10000,
+  })
+}
+
+/**
+ * Run an audited command again. Destructive commands need a confirmation
+ * token from request_command_confirmation, which asks the user in a native
+ * dialog, as with run_gt_command.
+ */
+export function useRerunCommand() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: ({ id, confirmationToken }: { id: string; confirmationToken?: string }) =>
+      invoke<CommandResult>('rerun_audit_entry', { id, confirmationToken }),
+    onSettled: () => {
+      queryClient.invalidateQueries({ queryKey: ['auditLog'] })
+    },
+  })
+}
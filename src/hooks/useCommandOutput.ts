@@ -0,0 +1,68 @@
+/**
+ * Live output from long-running backend commands.
+ *
+ * Commands started with a `jobId` (run_gt_command, install_dependency)
+ * stream each stdout/stderr line on the `command-output` channel and can be
+ * cancelled with cancel_command.
+ */
+
+import { useCallback, useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen, UnlistenFn } from '@tauri-apps/api/event'
+
+/** Backend channel streamed output lines are emitted on */
+export const COMMAND_OUTPUT_CHANNEL = 'command-output'
+
+/** A line of output from a running job */
+export interface CommandOutputLine {
+  job_id: string
+  stream: 'stdout' | 'stderr'
+  line: string
+}
+
+/** A fresh job ID to pass to a streaming command */
+export function newJobId(): string {
+  return crypto.randomUUID()
+}
+
+/** Kill a running command. Resolves false if the job already finished. */
+export function cancelCommand(jobId: string): Promise<boolean> {
+  return invoke<boolean>('cancel_command', { jobId })
+}
+
+/**
+ * Collect the output lines of one job as they arrive
+ */
+export function useCommandOutput(jobId: string | null, maxLines = 500) {
+  const [lines, setLines] = useState<CommandOutputLine[]>([])
+
+  useEffect(() => {
+    setLines([])
+    if (!jobId) return
+
+    let unlisten: UnlistenFn | null = null
+    let cancelled = false
+
+    listen<CommandOutputLine>(COMMAND_OUTPUT_CHANNEL, (event) => {
+      if (event.payload.job_id !== jobId) return
+      setLines((prev) => [...prev, event.payload].slice(-maxLines))
+    }).then((fn) => {
+      if (cancelled) {
+        fn()
+      } else {
+        unlisten = fn
+      }
+    })
+
+    return () => {
+      cancelled = true
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [jobId, maxLines])
+
+  const cancel = useCallback(() => (jobId ? cancelCommand(jobId) : Promise.resolve(false)), [jobId])
+
+  return { lines, cancel }
+}
@@ -15,22 +15,130 @@ import { listen, UnlistenFn } from '@tauri-apps/api/event';
 // Types
 // ============================================================================
 
+/** Tauri event channel the backend events watcher emits on */
+export const GASTOWN_EVENT_CHANNEL = 'gastown-event';
+
 export type Verbosity = 'quiet' | 'normal' | 'chatty';
 
-export interface GasTownEvent {
+/** Tauri event channel spoken summaries of event batches arrive on */
+export const GASTOWN_EVENT_SUMMARY_CHANNEL = 'gastown-event-summary';
+
+/** Tauri event channel grouped, rate-limited spoken announcements arrive on */
+export const GASTOWN_ANNOUNCEMENT_CHANNEL = 'gastown-announcement';
+
+/** One or more events condensed into a line to speak */
+export interface Announcement {
+  text: string;
+  event_count: number;
+  /** Critical events skip grouping and the per-minute cap */
+  critical: boolean;
+}
+
+export interface EventSummary {
+  text: string;
+  audio_base64: string | null;
+  audio_sample_rate: number;
+  event_count: number;
+}
+
+export type AgentPersona =
+  | 'default'
+  | 'mayor'
+  | 'witness'
+  | 'refinery'
+  | 'deacon'
+  | 'polecat'
+  | 'crew';
+
+/** Commentary templates (by event type, then persona) and summary settings */
+export interface CommentaryConfig {
+  /** Narrate everything as this persona; null uses each actor's role */
+  persona: AgentPersona | null;
+  templates: Record<string, Partial<Record<AgentPersona, string>>>;
+  summaries: {
+    enabled: boolean;
+    batch_size: number;
+    max_wait_secs: number;
+  };
+  digest: {
+    window_secs: number;
+    max_per_minute: number;
+    critical_types: string[];
+  };
+}
+
+/** Payload fields beyond the typed ones are passed through unchanged */
+type Payload<T> = T & Record<string, unknown>;
+
+/** Event type and payload, discriminated on `type` */
+export type GasTownEventKind =
+  | { type: 'mail'; payload: Payload<{ from?: string; to?: string; subject?: string }> }
+  | { type: 'session_start' | 'session_end'; payload: Payload<{ session?: string; rig?: string }> }
+  | { type: 'complete'; payload: Payload<{ task?: string; bead?: string }> }
+  | { type: 'assignment'; payload: Payload<{ bead?: string; assignee?: string }> }
+  | { type: 'stuck'; payload: Payload<{ reason?: string; bead?: string }> }
+  | { type: 'blocked'; payload: Payload<{ bead?: string; blocked_by?: string }> }
+  | { type: 'error'; payload: Payload<{ message?: string }> }
+  | { type: 'nudge'; payload: Payload<{ target?: string; message?: string }> }
+  | { type: 'merge'; payload: Payload<{ branch?: string; bead?: string }> }
+  | { type: 'convoy_start' | 'convoy_complete'; payload: Payload<{ id?: string; name?: string }> }
+  | { type: 'escalation'; payload: Payload<{ to?: string; reason?: string }> };
+
+export type GasTownEvent = {
   ts: string;
   source: string;
-  event_type: string;
   actor: string;
-  payload: Record<string, unknown>;
+  visibility?: string;
+} & (
+  | GasTownEventKind
+  // Event types the backend doesn't model keep their raw payload
+  | { type: string; payload: unknown }
+);
+
+/** User-defined filter rule; the highest priority matching rule decides */
+export interface EventFilterRule {
+  name?: string;
+  action: 'allow' | 'deny';
+  priority?: number;
+  /** Event types to match; empty or omitted matches any type */
+  event_types?: string[];
+  /** Glob over the actor, e.g. `gastown/polecats/*` */
+  actor?: string;
+  rig?: string;
+  source?: string;
   visibility?: string;
 }
 
+export interface EventFilters {
+  rules: EventFilterRule[];
+}
+
 export interface EnrichedEvent {
   event: GasTownEvent;
   commentary: string | null;
 }
 
+/** Filter for `query_event_history` and `count_event_history` */
+export interface HistoryQuery {
+  /** RFC 3339, inclusive */
+  since?: string;
+  /** RFC 3339, exclusive */
+  until?: string;
+  event_types?: string[];
+  /** Glob over the actor */
+  actor?: string;
+  /** Glob over the rig */
+  rig?: string;
+  limit?: number;
+}
+
+/** Matching events in one time bucket; empty buckets are omitted */
+export interface EventBucket {
+  start: string;
+  count: number;
+  by_type: Record<string, number>;
+}
+
 export interface UseEventsStreamOptions {
   /** Auto-start watching on mount (default: true) */
   autoStart?: boolean;
@@ -55,6 +163,10 @@ export interface UseEventsStreamResult {
   stop: () => Promise<void>;
   /** Set verbosity level */
   setVerbosity: (level: Verbosity) => Promise<void>;
+  /** Saved filter rules, applied before the verbosity level */
+  filters: EventFilters;
+  /** Replace and save filter rules */
+  setFilters: (filters: EventFilters) => Promise<void>;
   /** Clear events buffer */
   clearEvents: () => void;
   /** Error state */
@@ -78,6 +190,7 @@ export function useEventsStream(
   const [events, setEvents] = useState<EnrichedEvent[]>([]);
   const [isWatching, setIsWatching] = useState(false);
   const [verbosity, setVerbosityState] = useState<Verbosity>(initialVerbosity);
+  const [filters, setFiltersState] = useState<EventFilters>({ rules: [] });
   const [error, setError] = useState<string | null>(null);
 
   // Start watching
@@ -111,6 +224,16 @@ export function useEventsStream(
     }
   }, []);
 
+  // Set filter rules
+  const setFilters = useCallback(async (next: EventFilters) => {
+    try {
+      await invoke('set_event_filters', { filters: next });
+      setFiltersState(next);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }, []);
+
   // Clear events
   const clearEvents = useCallback(() => {
     setEvents([]);
@@ -121,7 +244,7 @@ export function useEventsStream(
     let unlisten: UnlistenFn | null = null;
 
     const setupListener = async () => {
-      unlisten = await listen<EnrichedEvent>('gastwon-event', (event) => {
+      unlisten = await listen<EnrichedEvent>(GASTOWN_EVENT_CHANNEL, (event) => {
         const enrichedEvent = event.payload;
 
         setEvents((prev) => {
@@ -149,6 +272,19 @@ export function useEventsStream(
   // Auto-start and initial setup
   useEffect(() => {
     const init = async () => {
+      // The backend watcher outlives page reloads; pick up its current state
+      try {
+        setIsWatching(await invoke<boolean>('is_events_watcher_active'));
+      } catch {
+        // Ignore - treat as not watching
+      }
+
+      try {
+        setFiltersState(await invoke<EventFilters>('get_event_filters'));
+      } catch {
+        // Ignore - no rules
+      }
+
       // Set initial verbosity
       await setVerbosity(initialVerbosity);
 
@@ -170,10 +306,8 @@ export function useEventsStream(
 
     init();
 
-    // Cleanup on unmount
-    return () => {
-      stop();
-    };
+    // The watcher is owned by the backend and shared by every listener, so
+    // unmounting (or reloading) must not stop it.
   }, []); // Only run once on mount
 
   return {
@@ -183,6 +317,8 @@ export function useEventsStream(
     start,
     stop,
     setVerbosity,
+    filters,
+    setFilters,
     clearEvents,
     error,
   };
@@ -199,58 +335,45 @@ export interface UseEventsVoiceOptions {
   verbosity?: Verbosity;
   /** Speak function from voice hook */
   speak?: (text: string) => Promise<unknown>;
-  /** Debounce time between voice messages (ms) */
-  debounceMs?: number;
 }
 
 /**
- * Hook that automatically speaks event commentary
+ * Hook that automatically speaks event announcements. The backend groups
+ * bursts of events and caps announcements per minute, so each one can be
+ * spoken as it arrives.
  */
 export function useEventsVoice(options: UseEventsVoiceOptions = {}) {
   const {
     enabled = false,
     verbosity = 'normal',
     speak,
-    debounceMs = 3000,
   } = options;
 
-  const [lastSpokenAt, setLastSpokenAt] = useState(0);
-  const [pendingCommentary, setPendingCommentary] = useState<string | null>(null);
-
-  const handleEvent = useCallback((event: EnrichedEvent) => {
-    if (!enabled || !speak || !event.commentary) return;
-
-    const now = Date.now();
-    if (now - lastSpokenAt < debounceMs) {
-      // Queue for later if within debounce window
-      setPendingCommentary(event.commentary);
-      return;
-    }
-
-    setLastSpokenAt(now);
-    speak(event.commentary);
-  }, [enabled, speak, lastSpokenAt, debounceMs]);
-
-  // Process pending commentary after debounce
   useEffect(() => {
-    if (!pendingCommentary || !enabled || !speak) return;
-
-    const timer = setTimeout(() => {
-      const now = Date.now();
-      if (now - lastSpokenAt >= debounceMs) {
-        setLastSpokenAt(now);
-        speak(pendingCommentary);
-        setPendingCommentary(null);
+    if (!enabled || !speak) return;
+
+    let unlisten: UnlistenFn | null = null;
+    let cancelled = false;
+
+    listen<Announcement>(GASTOWN_ANNOUNCEMENT_CHANNEL, (event) => {
+      speak(event.payload.text);
+    }).then((fn) => {
+      if (cancelled) {
+        fn();
+      } else {
+        unlisten = fn;
       }
-    }, debounceMs);
+    });
 
-    return () => clearTimeout(timer);
-  }, [pendingCommentary, enabled, speak, lastSpokenAt, debounceMs]);
+    return () => {
+      cancelled = true;
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [enabled, speak]);
 
-  const { events, isWatching, ...rest } = useEventsStream({
-    verbosity,
-    onEvent: handleEvent,
-  });
+  const { events, isWatching, ...rest } = useEventsStream({ verbosity });
 
   return {
     events,
@@ -1,23 +1,30 @@
+import { useEffect } from 'react'
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
 import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import type {
   Bead,
   BeadStatus,
   Convoy,
+  ConvoyDetail,
   TownStatus,
   CommandResult,
   TmuxSession,
   ActivityItem,
   Rig,
+  RigSummary,
   Polecat,
 } from '../types/gastown'
 
 // Additional types for local use (re-export from types)
-export type { Bead, BeadStatus, Rig, Polecat }
+export type { Bead, BeadStatus, Rig, RigSummary, Polecat }
 
 // Extended Rig type with health status for comparison view
 export interface RigWithHealth extends Rig {
   health: 'healthy' | 'degraded' | 'unhealthy'
+  ready: number
+  queue_depth: number
+  last_activity: string | null
 }
 
 export interface CrewMember {
@@ -33,6 +40,44 @@ export interface MergeQueueItem {
   polecat: string
   status: 'pending' | 'processing' | 'conflict' | 'merged'
   position: number
+  priority?: number
+  ci_status?: string
+}
+
+// Merge request as returned by list_merge_queue and pushed on 'merge-queue-changed'
+export interface MergeRequest {
+  id: string
+  rig: string
+  branch: string
+  target: string | null
+  author: string | null
+  source_issue: string | null
+  position: number
+  priority: number | null
+  status: string
+  has_conflicts: boolean | null
+  ci_status: string | null
+  created: string
+}
+
+/** Pushed by the backend whenever a rig's merge queue changes */
+export const MERGE_QUEUE_CHANGED_CHANNEL = 'merge-queue-changed'
+
+function toMergeQueueItem(mr: MergeRequest): MergeQueueItem {
+  const status: MergeQueueItem['status'] = mr.has_conflicts
+    ? 'conflict'
+    : mr.status === 'processing' || mr.status === 'merged'
+      ? mr.status
+      : 'pending'
+  return {
+    id: mr.id,
+    branch: mr.branch || mr.id,
+    polecat: mr.author ?? 'unknown',
+    status,
+    position: mr.position + 1,
+    priority: mr.priority ?? undefined,
+    ci_status: mr.ci_status ?? undefined,
+  }
 }
 
 // Helper to run gt/bd commands via Tauri
@@ -40,6 +85,24 @@ export async function runCommand(cmd: string, args: string[]): Promise<CommandRe
   return invoke<CommandResult>('run_gt_command', { cmd, args })
 }
 
+interface CommandConfirmation {
+  token: string
+  argv: string[]
+  risk: 'read_only' | 'mutating' | 'destructive'
+  expires_in_secs: number
+}
+
+// Run a destructive command. The backend asks the user to confirm it in a
+// native dialog before it issues the token; cancelling rejects.
+export async function runConfirmedCommand(cmd: string, args: string[]): Promise<CommandResult> {
+  const confirmation = await invoke<CommandConfirmation>('request_command_confirmation', { cmd, args })
+  return invoke<CommandResult>('run_gt_command', {
+    cmd,
+    args,
+    confirmationToken: confirmation.token,
+  })
+}
+
 // Check if in browser (for SSR safety)
 const isBrowser = typeof window !== 'undefined'
 
@@ -115,55 +178,83 @@ function parseConvoyOutput(output: string): Convoy[] {
   return convoys
 }
 
-// Real-time convoy status (2s polling)
+/** Pushed by the backend whenever a convoy or one of its beads changes */
+export const CONVOY_UPDATED_CHANNEL = 'convoy-updated'
+
+function toConvoy(detail: ConvoyDetail): Convoy {
+  return {
+    id: detail.id,
+    name: detail.name,
+    beads: detail.beads.map((b) => b.id),
+    progress: detail.progress,
+    active_polecats: detail.active_polecats,
+    status: normalizeConvoyStatus(detail.status),
+    created: detail.created,
+    eta: detail.eta ?? undefined,
+    polecats: detail.polecats,
+  }
+}
+
+// Convoy status with per-bead progress and ETA, kept current by 'convoy-updated'
 export function useConvoys() {
+  const queryClient = useQueryClient()
+
+  useEffect(() => {
+    if (!isTauri()) return
+
+    let unlisten: UnlistenFn | null = null
+    let cancelled = false
+
+    listen<ConvoyDetail>(CONVOY_UPDATED_CHANNEL, (event) => {
+      const updated = toConvoy(event.payload)
+      queryClient.setQueryData<Convoy[]>(['convoys'], (prev) => {
+        if (!prev) return prev
+        return prev.some((c) => c.id === updated.id)
+          ? prev.map((c) => (c.id === updated.id ? updated : c))
+          : [...prev, updated]
+      })
+      queryClient.setQueryData(['convoy', updated.id], event.payload)
+    }).then((fn) => {
+      if (cancelled) {
+        fn()
+      } else {
+        unlisten = fn
+      }
+    })
+
+    return () => {
+      cancelled = true
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [queryClient])
+
   return useQuery({
     queryKey: ['convoys'],
     queryFn: async (): Promise<Convoy[]> => {
-      const result = await runCommand('gt', ['convoy', 'list', '--json'])
-      if (result.exit_code !== 0) {
-        console.warn('gt convoy list failed:', result.stderr)
+      try {
+        const convoys = await invoke<ConvoyDetail[]>('list_convoys')
+        return convoys.map(toConvoy)
+      } catch (e) {
+        console.warn('list_convoys failed:', e)
         return []
       }
-
-      return parseConvoyJson(result.stdout)
     },
-    refetchInterval: 2000,
+    refetchInterval: 10000,
     staleTime: 1000,
     enabled: isBrowser,
   })
 }
 
-/**
- * Parse convoy list JSON output from `gt convoy list --json`
- */
-function parseConvoyJson(output: string): Convoy[] {
-  if (!output.trim()) {
-    return []
-  }
-
-  try {
-    const data = JSON.parse(output)
-
-    // Handle both array and object with convoys key
-    const convoys = Array.isArray(data) ? data : data.convoys || []
-
-    return convoys.map((c: Record<string, unknown>) => ({
-      id: String(c.id || ''),
-      name: String(c.name || ''),
-      beads: Array.isArray(c.beads) ? c.beads.map(String) : [],
-      progress: typeof c.progress === 'number' ? c.progress : 0,
-      active_polecats: typeof c.active_polecats === 'number' ? c.active_polecats : 0,
-      status: normalizeConvoyStatus(c.status),
-      created: String(c.created || ''),
-      eta: c.eta ? String(c.eta) : undefined,
-      // Support additional fields from gt output
-      polecats: Array.isArray(c.polecats) ? c.polecats.map(String) : undefined,
-    })) as Convoy[]
-  } catch (e) {
-    console.warn('Failed to parse convoy JSON:', e)
-    return []
-  }
+// One convoy with its member beads, assigned polecats and ETA
+export function useConvoy(id: string | undefined) {
+  return useQuery({
+    queryKey: ['convoy', id],
+    queryFn: () => invoke<ConvoyDetail>('get_convoy', { id }),
+    enabled: isBrowser && !!id,
+    refetchInterval: 10000,
+  })
 }
 
 /**
@@ -178,17 +269,22 @@ function normalizeConvoyStatus(status: unknown): Convoy['status'] {
   return 'running' // Default to running for unknown statuses
 }
 
-// Fetch all rigs in the town
+// Every rig in the town with polecat, bead and merge queue counts
+export function useRigSummaries() {
+  return useQuery({
+    queryKey: ['rigs'],
+    queryFn: () => invoke<RigSummary[]>('list_rigs'),
+    refetchInterval: 10000,
+    enabled: isBrowser,
+  })
+}
+
+// Names of all rigs in the town
 export function useRigs() {
   return useQuery({
     queryKey: ['rigs'],
-    queryFn: async () => {
-      const result = await runCommand('gt', ['rigs'])
-      if (result.exit_code !== 0) {
-        throw new Error(result.stderr || 'Failed to list rigs')
-      }
-      return result.stdout.trim().split('\n').filter(Boolean)
-    },
+    queryFn: () => invoke<RigSummary[]>('list_rigs'),
+    select: (rigs) => rigs.map((rig) => rig.name),
     refetchInterval: 10000,
     enabled: isBrowser,
   })
@@ -235,46 +331,63 @@ export function usePolecats(rig: string) {
   })
 }
 
-// Fetch merge queue status for a rig
+// Merge queue for a rig, kept current by 'merge-queue-changed'
 export function useMergeQueue(rig: string) {
+  const queryClient = useQueryClient()
+
+  useEffect(() => {
+    if (!isTauri()) return
+
+    let unlisten: UnlistenFn | null = null
+    let cancelled = false
+
+    listen<{ rig: string | null; entries: MergeRequest[] }>(
+      MERGE_QUEUE_CHANGED_CHANNEL,
+      (event) => {
+        if (event.payload.rig !== rig) return
+        queryClient.setQueryData(['mergeQueue', rig], event.payload.entries.map(toMergeQueueItem))
+      }
+    ).then((fn) => {
+      if (cancelled) {
+        fn()
+      } else {
+        unlisten = fn
+      }
+    })
+
+    return () => {
+      cancelled = true
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [queryClient, rig])
+
   return useQuery({
     queryKey: ['mergeQueue', rig],
     queryFn: async () => {
-      const result = await runCommand('gt', ['refinery', 'queue', rig])
-      if (result.exit_code !== 0 && !result.stdout) {
+      try {
+        const queue = await invoke<MergeRequest[]>('list_merge_queue', { rig })
+        return queue.map(toMergeQueueItem)
+      } catch (e) {
+        console.warn('list_merge_queue failed:', e)
         return [] as MergeQueueItem[]
       }
-      const items: MergeQueueItem[] = []
-      const lines = result.stdout.trim().split('\n').filter(Boolean)
-      lines.forEach((line, idx) => {
-        const parts = line.split(/\s+/)
-        if (parts.length >= 2) {
-          items.push({
-            id: parts[0],
-            branch: parts[1] || parts[0],
-            polecat: parts[2] || 'unknown',
-            status: 'pending',
-            position: idx + 1,
-          })
-        }
-      })
-      return items
     },
-    refetchInterval: 5000,
+    refetchInterval: 10000,
     enabled: isBrowser,
   })
 }
 
 // Reorder merge queue items (drag and drop)
-// Uses bd update --priority to change the priority of merge-request beads
-// Lower priority number = higher in queue (P0 highest, P4 lowest)
+// Sets the priority of the merge-request bead; the refinery merges
+// lower priority numbers first (P0 highest, P4 lowest)
 export function useReorderMergeQueue(rig: string) {
   const queryClient = useQueryClient()
   return useMutation({
     mutationFn: async ({
       itemId,
       newPosition,
-      items,
     }: {
       itemId: string
       newPosition: number
@@ -285,31 +398,22 @@ export function useReorderMergeQueue(rig: string) {
         return { success: true, message: 'Mock reorder in browser mode' }
       }
 
-      // Calculate new priority based on position
       // Position 0 = P0 (highest), Position 4+ = P4 (lowest)
-      // We use position directly as priority (capped at 4)
       const newPriority = Math.min(newPosition, 4)
+      const queue = await invoke<MergeRequest[]>('reprioritize_merge_request', {
+        rig,
+        id: itemId,
+        priority: newPriority,
+      })
 
-      // Find the bead ID from the item (strip any prefixes if needed)
-      const beadId = itemId
-
-      // Update the priority using bd update
-      const result = await runCommand('bd', [
-        'update',
-        beadId,
-        '--priority',
-        newPriority.toString(),
-      ])
-
-      if (result.exit_code !== 0) {
-        throw new Error(result.stderr || 'Failed to update merge queue priority')
-      }
-
-      return { success: true, newPriority }
+      return { success: true, newPriority, queue }
     },
-    onSuccess: () => {
-      // Invalidate merge queue to refetch with new order
-      queryClient.invalidateQueries({ queryKey: ['mergeQueue', rig] })
+    onSuccess: (result) => {
+      if ('queue' in result && result.queue) {
+        queryClient.setQueryData(['mergeQueue', rig], result.queue.map(toMergeQueueItem))
+      } else {
+        queryClient.invalidateQueries({ queryKey: ['mergeQueue', rig] })
+      }
     },
     onError: (error) => {
       console.error('Failed to reorder merge queue:', error)
@@ -317,6 +421,28 @@ export function useReorderMergeQueue(rig: string) {
   })
 }
 
+// Reject a merge request, taking it out of the queue. Needs a confirmation token.
+export function useRemoveMergeRequest(rig: string) {
+  const queryClient = useQueryClient()
+  return useMutation({
+    mutationFn: async ({ id, reason }: { id: string; reason?: string }) => {
+      const confirmation = await invoke<CommandConfirmation>('request_command_confirmation', {
+        cmd: 'gt',
+        args: reason ? ['refinery', 'reject', id, '--reason', reason] : ['refinery', 'reject', id],
+      })
+      return invoke<MergeRequest[]>('remove_merge_request', {
+        rig,
+        id,
+        reason,
+        confirmationToken: confirmation.token,
+      })
+    },
+    onSuccess: (queue) => {
+      queryClient.setQueryData(['mergeQueue', rig], queue.map(toMergeQueueItem))
+    },
+  })
+}
+
 // Parse gt status --json output for town status
 function parseTownStatusOutput(statusOutput: string, rigsOutput: string): TownStatus {
   let healthy = true
@@ -536,6 +662,15 @@ async function checkDependency(
   versionRegex?: RegExp
 ): Promise<{ installed: boolean; version?: string }> {
   try {
+    // Go isn't an allowlisted command, so ask the backend's setup check
+    if (cmd === 'go') {
+      const status = await invoke<{ dependencies: { name: string; installed: boolean; version?: string }[] }>(
+        'check_dependencies'
+      )
+      const go = status.dependencies.find((dep) => dep.name === 'Go')
+      const version = versionRegex ? go?.version?.match(versionRegex)?.[1] : go?.version
+      return { installed: !!go?.installed, version: version ?? undefined }
+    }
     const result = await runCommand(cmd, args)
     if (result.exit_code !== 0) {
       return { installed: false }
@@ -755,50 +890,47 @@ function parsePolecatListForComparison(output: string, rigName: string): Polecat
   return polecats
 }
 
+/**
+ * A rig's summary joined with its polecats, rated by stuck polecats and
+ * blocked beads
+ */
+async function fetchRigWithHealth(rigName: string): Promise<RigWithHealth> {
+  const [summary, polecatResult] = await Promise.all([
+    invoke<RigSummary>('get_rig', { name: rigName }),
+    runCommand('gt', ['polecat', 'list', rigName]),
+  ])
+  const polecats = polecatResult.exit_code === 0
+    ? parsePolecatListForComparison(polecatResult.stdout, rigName)
+    : []
+
+  const hasErrors = polecats.some(p => p.status === 'stuck')
+  const hasBlocked = summary.blocked > 0
+  const health: RigWithHealth['health'] = hasErrors ? 'unhealthy' : hasBlocked ? 'degraded' : 'healthy'
+
+  return {
+    name: summary.name,
+    path: summary.path,
+    polecats,
+    beads_count: {
+      open: summary.ready + summary.blocked,
+      in_progress: summary.in_progress,
+      closed: summary.closed,
+      blocked: summary.blocked,
+    },
+    health,
+    ready: summary.ready,
+    queue_depth: summary.queue_depth,
+    last_activity: summary.last_activity,
+  }
+}
+
 /**
  * Hook for fetching detailed rig status with polecats and beads for comparison
  */
 export function useRigStatus(rigName: string) {
   return useQuery({
     queryKey: ['rig', rigName, 'status'],
-    queryFn: async (): Promise<RigWithHealth> => {
-      // Get polecat list for this rig
-      const polecatResult = await runCommand('gt', ['polecat', 'list', rigName])
-      const polecats = polecatResult.exit_code === 0
-        ? parsePolecatListForComparison(polecatResult.stdout, rigName)
-        : []
-
-      // Get beads stats for this rig
-      const statsResult = await runCommand('bd', ['stats', '--json'])
-      let beads_count = { open: 0, in_progress: 0, closed: 0, blocked: 0 }
-
-      if (statsResult.exit_code === 0) {
-        try {
-          const stats = JSON.parse(statsResult.stdout)
-          beads_count = {
-            open: stats.open || 0,
-            in_progress: stats.in_progress || 0,
-            closed: stats.closed || 0,
-            blocked: stats.blocked || 0,
-          }
-        } catch {
-          // Ignore parse errors
-        }
-      }
-
-      // Determine health based on blocked count and polecat status
-      const hasErrors = polecats.some(p => p.status === 'stuck')
-      const hasBlocked = beads_count.blocked > 0
-      const health: RigWithHealth['health'] = hasErrors ? 'unhealthy' : hasBlocked ? 'degraded' : 'healthy'
-
-      return {
-        name: rigName,
-        path: '',
-        polecats,
-        beads_count,
-        health,
-      }
-    },
+    queryFn: () => fetchRigWithHealth(rigName),
     refetchInterval: 5000,
     enabled: !!rigName,
   })
@@ -813,7 +945,7 @@ export function useStopAll() {
 
   return useMutation({
     mutationFn: async (): Promise<CommandResult> => {
-      return runCommand('gt', ['stop', '--all'])
+      return runConfirmedCommand('gt', ['stop', '--all'])
     },
     onSuccess: () => {
       // Invalidate all queries to refresh state after stop
@@ -832,30 +964,7 @@ export function useStopAll() {
 export function useRigComparison(rigNames: string[]) {
   return useQuery({
     queryKey: ['rigs', 'comparison', rigNames],
-    queryFn: async (): Promise<RigWithHealth[]> => {
-      const results = await Promise.all(
-        rigNames.map(async (name) => {
-          // Get polecat list for this rig
-          const polecatResult = await runCommand('gt', ['polecat', 'list', name])
-          const polecats = polecatResult.exit_code === 0
-            ? parsePolecatListForComparison(polecatResult.stdout, name)
-            : []
-
-          // Determine health
-          const hasErrors = polecats.some(p => p.status === 'stuck')
-          const health: RigWithHealth['health'] = hasErrors ? 'unhealthy' : 'healthy'
-
-          return {
-            name,
-            path: '',
-            polecats,
-            beads_count: { open: 0, in_progress: 0, closed: 0, blocked: 0 },
-            health,
-          }
-        })
-      )
-      return results
-    },
+    queryFn: () => Promise.all(rigNames.map(fetchRigWithHealth)),
     refetchInterval: 5000,
     enabled: rigNames.length > 0,
   })
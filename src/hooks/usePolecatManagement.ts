@@ -0,0 +1,115 @@
+/**
+ * Polecat management.
+ *
+ * Polecats from `gt polecat list`, joined with their tmux sessions, git
+ * worktrees and hooked beads, plus the actions the Polecats panel offers.
+ * Every action goes through the backend allowlist and is audited; restart
+ * and retire need a confirmation token from request_command_confirmation,
+ * which asks the user in a native dialog.
+ */
+
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
+import { invoke } from '@tauri-apps/api/core'
+import type { CommandOrigin } from './useAuditLog'
+
+export interface PolecatSession {
+  name: string
+  windows: number
+  attached: boolean
+  activity: number | null
+}
+
+export interface PolecatWorktree {
+  path: string
+  branch: string | null
+  changed_files: number
+}
+
+export interface HookedBead {
+  id: string
+  title: string
+  status: string
+}
+
+export interface PolecatInfo {
+  name: string
+  rig: string
+  status: string
+  last_activity: string | null
+  session: PolecatSession | null
+  worktree: PolecatWorktree | null
+  hooked_bead: HookedBead | null
+}
+
+export type PolecatAction = 'nudge' | 'restart' | 'retire' | 'reassign'
+
+export interface PolecatActionResult {
+  polecat: string
+  action: PolecatAction
+  success: boolean
+  exit_code: number
+  message: string
+}
+
+interface PolecatTarget {
+  rig: string
+  name: string
+  origin?: CommandOrigin
+}
+
+/**
+ * Polecats in one rig, or in every rig
+ */
+export function usePolecatList(rig?: string) {
+  return useQuery({
+    queryKey: ['polecatList', rig ?? null],
+    queryFn: () => invoke<PolecatInfo[]>('list_polecats', { rig }),
+    refetchInterval: 5000,
+  })
+}
+
+/**
+ * One polecat with its session, worktree and hooked bead
+ */
+export function usePolecat(rig: string, name: string) {
+  return useQuery({
+    queryKey: ['polecat', rig, name],
+    queryFn: () => invoke<PolecatInfo>('get_polecat', { rig, name }),
+    refetchInterval: 5000,
+    enabled: !!rig && !!name,
+  })
+}
+
+function usePolecatMutation<T extends PolecatTarget>(command: string) {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: (params: T) => invoke<PolecatActionResult>(command, { ...params }),
+    onSettled: () => {
+      queryClient.invalidateQueries({ queryKey: ['polecatList'] })
+      queryClient.invalidateQueries({ queryKey: ['polecat'] })
+      queryClient.invalidateQueries({ queryKey: ['auditLog'] })
+    },
+  })
+}
+
+export function useNudgePolecat() {
+  return usePolecatMutation<PolecatTarget & { message: string }>('nudge_polecat')
+}
+
+export function useRestartPolecat() {
+  return usePolecatMutation<PolecatTarget & { confirmationToken?: string }>('restart_polecat')
+}
+
+export function useRetirePolecat() {
+  return usePolecatMutation<PolecatTarget & { confirmationToken?: string }>('retire_polecat')
+}
+
+/**
+ * Sling a polecat's bead elsewhere; beadId defaults to the bead on its hook
+ */
+export function useReassignPolecatBead() {
+  return usePolecatMutation<PolecatTarget & { target: string; beadId?: string }>(
+    'reassign_polecat_bead'
+  )
+}
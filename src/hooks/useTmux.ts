@@ -1,6 +1,26 @@
+import { useEffect, useRef } from 'react'
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
 import { invoke } from '@tauri-apps/api/core'
-import type { TmuxSession, TmuxSessionDetail, TmuxPane } from '../types/tmux'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import type {
+  TmuxSession,
+  TmuxSessionDetail,
+  TmuxPane,
+  PaneOutput,
+  PaneSnapshot,
+  TmuxNotification,
+  PaneInput,
+  PaneCapture,
+  CaptureLayout,
+  SessionHealthReport,
+  TerminalConfig,
+  TerminalLaunchers,
+} from '../types/tmux'
+
+const TMUX_OUTPUT_CHANNEL = 'tmux-output'
+const TMUX_SNAPSHOT_CHANNEL = 'tmux-snapshot'
+const TMUX_NOTIFICATION_CHANNEL = 'tmux-notification'
+const SESSION_HEALTH_CHANNEL = 'session-health-changed'
 
 // List all tmux sessions
 export function useTmuxSessions(options?: { refetchInterval?: number }) {
@@ -27,6 +47,46 @@ export function useTmuxSessionDetail(
   })
 }
 
+/**
+ * Health of every session, kept current by `session-health-changed`
+ * events. A change also refreshes that session's details.
+ */
+export function useSessionHealth(options?: { enabled?: boolean }) {
+  const queryClient = useQueryClient()
+  const enabled = options?.enabled ?? true
+
+  useEffect(() => {
+    if (!enabled) return
+
+    let unlisten: UnlistenFn | undefined
+    let cancelled = false
+
+    listen<SessionHealthReport>(SESSION_HEALTH_CHANNEL, (event) => {
+      const report = event.payload
+      queryClient.setQueryData<SessionHealthReport[]>(['tmux', 'health'], (reports) => [
+        ...(reports ?? []).filter((r) => r.session !== report.session),
+        report,
+      ])
+      queryClient.invalidateQueries({ queryKey: ['tmux', 'session', report.session] })
+    }).then((fn) => {
+      if (cancelled) fn()
+      else unlisten = fn
+    })
+
+    return () => {
+      cancelled = true
+      unlisten?.()
+    }
+  }, [queryClient, enabled])
+
+  return useQuery({
+    queryKey: ['tmux', 'health'],
+    queryFn: () => invoke<SessionHealthReport[]>('list_session_health'),
+    enabled,
+    refetchInterval: 30000,
+  })
+}
+
 // Get panes for a session
 export function useTmuxPanes(sessionName: string | null) {
   return useQuery({
@@ -59,6 +119,105 @@ export function useTmuxPaneContent(
   })
 }
 
+export interface TmuxStreamHandlers {
+  /** Start over from the pane's current screen */
+  onSnapshot: (snapshot: PaneSnapshot) => void
+  /** Output written after the last snapshot, in order */
+  onOutput: (data: string) => void
+  onExit?: (reason: string | null) => void
+}
+
+/**
+ * Stream a pane live over tmux control mode. The pane's screen arrives
+ * first, then every chunk it writes; chunks the snapshot already includes
+ * are skipped. A layout change (e.g. a resize) fetches a fresh snapshot.
+ */
+export function useTmuxStream(
+  sessionName: string | null,
+  paneId: string | null,
+  handlers: TmuxStreamHandlers
+) {
+  const handlersRef = useRef(handlers)
+  handlersRef.current = handlers
+
+  useEffect(() => {
+    if (!sessionName || !paneId) return
+
+    const unlisteners: UnlistenFn[] = []
+    let cancelled = false
+    // Output up to this chunk is in the snapshot; null until one arrives
+    let seen: number | null = null
+
+    const subscribe = () =>
+      invoke('subscribe_tmux_session', { session: sessionName, paneId }).catch(
+        (error) => console.error('Failed to stream tmux pane:', error)
+      )
+
+    const setup = async () => {
+      const fns = await Promise.all([
+        listen<PaneSnapshot>(TMUX_SNAPSHOT_CHANNEL, (event) => {
+          const snapshot = event.payload
+          if (snapshot.session !== sessionName || snapshot.pane_id !== paneId) return
+          seen = snapshot.seq
+          handlersRef.current.onSnapshot(snapshot)
+        }),
+        listen<PaneOutput>(TMUX_OUTPUT_CHANNEL, (event) => {
+          const output = event.payload
+          if (output.session !== sessionName || output.pane_id !== paneId) return
+          if (seen === null || output.seq <= seen) return
+          seen = output.seq
+          handlersRef.current.onOutput(output.data)
+        }),
+        listen<TmuxNotification>(TMUX_NOTIFICATION_CHANNEL, (event) => {
+          const notification = event.payload
+          if (notification.session !== sessionName) return
+          if (notification.kind === 'layout_change') {
+            subscribe()
+          } else if (notification.kind === 'exit') {
+            handlersRef.current.onExit?.(notification.reason)
+          }
+        }),
+      ])
+      if (cancelled) {
+        fns.forEach((fn) => fn())
+        return
+      }
+      unlisteners.push(...fns)
+      await subscribe()
+    }
+
+    setup()
+
+    return () => {
+      cancelled = true
+      unlisteners.forEach((fn) => fn())
+      invoke('unsubscribe_tmux_session', { session: sessionName }).catch(() => {})
+    }
+  }, [sessionName, paneId])
+}
+
+// Capture pane content with colors, cursor and size, optionally parsed
+// into styled spans or cells
+export function useTmuxPaneCapture(
+  target: string | null,
+  options?: { layout?: CaptureLayout; lines?: number; refetchInterval?: number }
+) {
+  const layout = options?.layout ?? 'spans'
+  return useQuery({
+    queryKey: ['tmux', 'pane-capture', target, layout, options?.lines],
+    queryFn: () =>
+      target
+        ? invoke<PaneCapture>('capture_tmux_pane_styled', {
+            target,
+            lines: options?.lines ?? 30,
+            layout,
+          })
+        : Promise.reject('No pane selected'),
+    enabled: !!target,
+    refetchInterval: options?.refetchInterval ?? 1000,
+  })
+}
+
 // Attach to session mutation
 export function useAttachTmuxSession() {
   return useMutation({
@@ -67,6 +226,71 @@ export function useAttachTmuxSession() {
   })
 }
 
+// Terminals attach can open, and the chosen one
+export function useTerminalLaunchers() {
+  return useQuery({
+    queryKey: ['tmux', 'terminal-launchers'],
+    queryFn: () => invoke<TerminalLaunchers>('list_terminal_launchers'),
+  })
+}
+
+// Choose the terminal attach opens
+export function useSetTerminalConfig() {
+  const queryClient = useQueryClient()
+  return useMutation({
+    mutationFn: (config: TerminalConfig) =>
+      invoke<TerminalConfig>('set_terminal_config', { config }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['tmux', 'terminal-launchers'] })
+    },
+  })
+}
+
+// Send text, keys or a paste to a pane. Pass `confirm` to have the user
+// confirm it first in a native dialog, which agent sessions need for
+// Ctrl-C and paste buffers.
+export function useSendKeysToPane() {
+  return useMutation({
+    mutationFn: async ({
+      paneId,
+      input,
+      confirm,
+    }: {
+      paneId: string
+      input: PaneInput
+      confirm?: boolean
+    }) => {
+      const confirmation = confirm
+        ? await invoke<{ token: string }>('request_pane_input_confirmation', { paneId, input })
+        : null
+      return invoke('send_keys_to_pane', {
+        paneId,
+        input,
+        confirmationToken: confirmation?.token,
+      })
+    },
+  })
+}
+
+// Panes the UI won't send input to
+export function useReadOnlyPanes() {
+  return useQuery({
+    queryKey: ['tmux', 'read-only-panes'],
+    queryFn: () => invoke<string[]>('list_read_only_panes'),
+  })
+}
+
+export function useSetPaneReadOnly() {
+  const queryClient = useQueryClient()
+  return useMutation({
+    mutationFn: ({ paneId, readOnly }: { paneId: string; readOnly: boolean }) =>
+      invoke<string[]>('set_pane_read_only', { paneId, readOnly }),
+    onSuccess: (panes) => {
+      queryClient.setQueryData(['tmux', 'read-only-panes'], panes)
+    },
+  })
+}
+
 // Copy connection string to clipboard
 export function useCopyConnectionString() {
   return useMutation({
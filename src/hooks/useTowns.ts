@@ -0,0 +1,89 @@
+/**
+ * Town registry.
+ *
+ * The towns the UI can drive, and which one is active. Commands that take
+ * an optional `town` ID run gt and bd in that town's root; without one they
+ * use the active town. Switching towns refetches everything.
+ */
+
+import { useEffect } from 'react'
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+
+const ACTIVE_TOWN_CHANNEL = 'active-town-changed'
+
+export interface Town {
+  id: string
+  name: string
+  root: string
+}
+
+export interface TownRegistry {
+  towns: Town[]
+  active: string | null
+}
+
+/**
+ * Registered towns and the active one. Every query is refetched when the
+ * active town changes, from here or anywhere else.
+ */
+export function useTowns() {
+  const queryClient = useQueryClient()
+
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null
+    let cancelled = false
+
+    listen<Town | null>(ACTIVE_TOWN_CHANNEL, () => {
+      queryClient.invalidateQueries()
+    }).then((fn) => {
+      if (cancelled) {
+        fn()
+      } else {
+        unlisten = fn
+      }
+    })
+
+    return () => {
+      cancelled = true
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [queryClient])
+
+  return useQuery({
+    queryKey: ['towns'],
+    queryFn: () => invoke<TownRegistry>('list_towns'),
+  })
+}
+
+function useTownMutation<T>(command: string) {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: (params: T) => invoke<Town>(command, { ...params }),
+    onSettled: () => {
+      queryClient.invalidateQueries({ queryKey: ['towns'] })
+    },
+  })
+}
+
+/**
+ * Register a town by its root directory; the name defaults to the directory's
+ */
+export function useAddTown() {
+  return useTownMutation<{ root: string; name?: string }>('add_town')
+}
+
+/**
+ * Unregister a town. Its files are left alone.
+ */
+export function useRemoveTown() {
+  return useTownMutation<{ id: string }>('remove_town')
+}
+
+export function useSetActiveTown() {
+  return useTownMutation<{ id: string }>('set_active_town')
+}
@@ -0,0 +1,59 @@
+/**
+ * Terminal colors
+ *
+ * Turns the styles from capture_tmux_pane_styled into CSS, using the same
+ * Catppuccin-ish defaults as TerminalPreview.
+ */
+
+import type { CSSProperties } from 'react'
+import type { CellStyle, TerminalColor } from '../types/tmux'
+
+export const DEFAULT_FOREGROUND = '#cdd6f4'
+export const DEFAULT_BACKGROUND = '#1e1e2e'
+
+// The 16 standard colors: normal, then bright
+const BASE_PALETTE = [
+  '#45475a', '#f38ba8', '#a6e3a1', '#f9e2af', '#89b4fa', '#f5c2e7', '#94e2d5', '#bac2de',
+  '#585b70', '#f38ba8', '#a6e3a1', '#f9e2af', '#89b4fa', '#f5c2e7', '#94e2d5', '#a6adc8',
+]
+
+const CUBE_LEVELS = [0, 95, 135, 175, 215, 255]
+
+function hex(r: number, g: number, b: number): string {
+  return '#' + [r, g, b].map((n) => n.toString(16).padStart(2, '0')).join('')
+}
+
+/** CSS color for a palette index (xterm's 256 colors) or `#rrggbb` */
+export function terminalColor(color: TerminalColor): string {
+  if (typeof color === 'string') return color
+  if (color < 16) return BASE_PALETTE[color]
+  if (color < 232) {
+    const n = color - 16
+    return hex(CUBE_LEVELS[Math.floor(n / 36)], CUBE_LEVELS[Math.floor(n / 6) % 6], CUBE_LEVELS[n % 6])
+  }
+  const gray = 8 + (color - 232) * 10
+  return hex(gray, gray, gray)
+}
+
+/** Inline CSS for a styled span or cell */
+export function cellStyle(style: CellStyle): CSSProperties {
+  let fg = style.fg !== undefined ? terminalColor(style.fg) : undefined
+  let bg = style.bg !== undefined ? terminalColor(style.bg) : undefined
+  if (style.reverse) {
+    ;[fg, bg] = [bg ?? DEFAULT_BACKGROUND, fg ?? DEFAULT_FOREGROUND]
+  }
+
+  const decorations = [style.underline && 'underline', style.strikethrough && 'line-through']
+    .filter(Boolean)
+    .join(' ')
+
+  return {
+    color: fg,
+    backgroundColor: bg,
+    fontWeight: style.bold ? 'bold' : undefined,
+    opacity: style.dim ? 0.6 : undefined,
+    fontStyle: style.italic ? 'italic' : undefined,
+    textDecoration: decorations || undefined,
+    visibility: style.hidden ? 'hidden' : undefined,
+  }
+}
@@ -273,9 +273,22 @@ export async function executeVoiceCommand(
   }
 
   try {
+    // The backend asks the user to confirm an emergency stop in a native dialog
+    const confirmationToken =
+      cmd.intent === 'emergency_stop'
+        ? (
+            await invoke<{ token: string }>('request_command_confirmation', {
+              cmd: cmd.command,
+              args: cmd.args,
+            })
+          ).token
+        : undefined;
+
     const result = await invoke<CommandResult>('run_gt_command', {
       cmd: cmd.command,
       args: cmd.args,
+      confirmationToken,
+      origin: 'voice',
     });
 
     if (result.exit_code !== 0 && !result.stdout) {
@@ -209,22 +209,21 @@ function Dashboard() {
           title="Emergency Stop All"
           aria-label="Emergency Stop All Gas Town agents"
           disabled={stopAll.isPending}
-          onClick={() => {
-            if (window.confirm('This will stop ALL Gas Town agents. Convoys will be interrupted. Continue?')) {
-              stopAll.mutate(undefined, {
-                onSuccess: (result) => {
-                  if (result.exit_code === 0) {
-                    alert('Emergency stop complete. All agents stopped.')
-                  } else {
-                    alert(`Emergency stop failed: ${result.stderr || 'Unknown error'}`)
-                  }
-                },
-                onError: (error) => {
-                  alert(`Emergency stop failed: ${error instanceof Error ? error.message : String(error)}`)
-                },
-              })
-            }
-          }}
+          onClick={() =>
+            // The backend asks the user to confirm in a native dialog
+            stopAll.mutate(undefined, {
+              onSuccess: (result) => {
+                if (result.exit_code === 0) {
+                  alert('Emergency stop complete. All agents stopped.')
+                } else {
+                  alert(`Emergency stop failed: ${result.stderr || 'Unknown error'}`)
+                }
+              },
+              onError: (error) => {
+                alert(`Emergency stop failed: ${error instanceof Error ? error.message : String(error)}`)
+              },
+            })
+          }
         >
           <AlertTriangle className="w-6 h-6" aria-hidden="true" />
         </button>
@@ -41,6 +41,29 @@ export interface Convoy {
   polecats?: string[] // Names of polecats working on this convoy
 }
 
+export interface ConvoyBead {
+  id: string
+  title: string
+  status: string // bead status, or 'unknown' if not in the beads index
+  assignee: string | null
+  closed_at: string | null
+}
+
+// Convoy as returned by list_convoys / get_convoy and pushed on 'convoy-updated'
+export interface ConvoyDetail {
+  id: string
+  name: string
+  status: string
+  created: string
+  beads: ConvoyBead[]
+  polecats: string[]
+  active_polecats: number
+  beads_closed: number
+  beads_total: number
+  progress: number // 0-100
+  eta: string | null
+}
+
 export interface Rig {
   name: string
   path: string
@@ -53,6 +76,20 @@ export interface Rig {
   }
 }
 
+// Per-rig counts from the backend's list_rigs/get_rig
+export interface RigSummary {
+  name: string
+  path: string
+  beads_dir: string | null
+  polecat_count: number
+  ready: number // open with no open blockers
+  blocked: number
+  in_progress: number // includes hooked
+  closed: number
+  queue_depth: number // merge requests waiting in the refinery
+  last_activity: string | null
+}
+
 export interface Polecat {
   name: string
   rig: string
@@ -1,6 +1,24 @@
 // Types matching the Rust backend structs
 
-export type SessionHealth = 'active' | 'processing' | 'idle' | 'stuck'
+export type SessionHealth = 'responding' | 'idle' | 'stuck' | 'cycling'
+
+/** Why a session got its health */
+export type HealthSignal =
+  | { kind: 'restarts'; count: number }
+  | { kind: 'dead_process'; pane_id: string }
+  | { kind: 'error_prompt' | 'awaiting_input'; pane_id: string; line: string }
+  | { kind: 'agent_exited'; pane_id: string; command: string }
+  | { kind: 'unchanged'; secs: number }
+
+/** Pushed on `session-health-changed`; also from list_session_health */
+export interface SessionHealthReport {
+  session: string
+  state: SessionHealth
+  previous: SessionHealth | null
+  signals: HealthSignal[]
+  quiet_secs: number
+  checked_at: number
+}
 
 export interface TmuxSession {
   name: string
@@ -24,6 +42,7 @@ export interface TmuxSessionDetail {
   session: TmuxSession
   panes: TmuxPane[]
   health: SessionHealth
+  health_signals: HealthSignal[]
   connection_string: string
 }
 
@@ -32,3 +51,114 @@ export interface CommandResult {
   stderr: string
   exit_code: number
 }
+
+// Live pane streaming over tmux control mode
+
+export interface PaneOutput {
+  session: string
+  pane_id: string
+  seq: number
+  data: string
+}
+
+export interface PaneSnapshot {
+  session: string
+  pane_id: string
+  /** Last output chunk the snapshot already includes */
+  seq: number
+  content: string
+  cursor_x: number
+  cursor_y: number
+  width: number
+  height: number
+}
+
+export type TmuxNotification = { session: string } & (
+  | { kind: 'window_add' | 'window_close'; window_id: string }
+  | { kind: 'window_renamed'; window_id: string; name: string }
+  | { kind: 'window_pane_changed'; window_id: string; pane_id: string }
+  | { kind: 'layout_change'; window_id: string; layout: string }
+  | { kind: 'pane_mode_changed'; pane_id: string }
+  | { kind: 'session_changed'; session_id: string; name: string }
+  | { kind: 'session_renamed'; name: string }
+  | { kind: 'session_window_changed'; session_id: string; window_id: string }
+  | { kind: 'sessions_changed' }
+  | { kind: 'exit'; reason: string | null }
+  | { kind: 'other'; name: string; args: string }
+)
+
+// Input for a pane; see send_keys_to_pane
+
+export type PaneInput =
+  | { kind: 'text'; text: string }
+  | { kind: 'keys'; keys: string[] }
+  | { kind: 'paste'; text: string }
+  | { kind: 'buffer'; name: string }
+
+// Styled pane capture; see capture_tmux_pane_styled
+
+/** Palette index, or `#rrggbb` for true color */
+export type TerminalColor = number | string
+
+export interface CellStyle {
+  fg?: TerminalColor
+  bg?: TerminalColor
+  bold?: boolean
+  dim?: boolean
+  italic?: boolean
+  underline?: boolean
+  blink?: boolean
+  reverse?: boolean
+  hidden?: boolean
+  strikethrough?: boolean
+}
+
+export interface StyledSpan extends CellStyle {
+  text: string
+}
+
+/** A wide character's second cell has an empty `ch` */
+export interface TerminalCell extends CellStyle {
+  ch: string
+}
+
+export type CaptureLayout = 'raw' | 'spans' | 'cells'
+
+export interface PaneCapture {
+  target: string
+  /** Captured lines with their escape sequences */
+  content: string
+  spans?: StyledSpan[][]
+  cells?: TerminalCell[][]
+  /** Line where the visible screen starts; earlier lines are scrollback */
+  screen_top: number
+  cursor_x: number
+  cursor_y: number
+  width: number
+  height: number
+}
+
+// Terminal opened by attach_tmux_session
+
+export interface TerminalLauncher {
+  id: string
+  name: string
+  program: string
+  args: string[]
+  /** Whether `program` is on PATH */
+  installed: boolean
+}
+
+export interface TerminalConfig {
+  /** A launcher ID, or 'custom'; null auto-detects */
+  launcher: string | null
+  /** e.g. `st -t {session} -e {command}`; run without a shell */
+  custom_command: string | null
+}
+
+export interface TerminalLaunchers {
+  launchers: TerminalLauncher[]
+  /** What auto-detection picks */
+  detected: string | null
+  config: TerminalConfig
+}
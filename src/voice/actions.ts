@@ -7,6 +7,7 @@
 
 import { invoke } from '@tauri-apps/api/core'
 import { getMailbox } from './mail'
+import type { CommandOrigin } from '../hooks/useAuditLog'
 
 // Action types supported by the voice interface
 export type ActionType =
@@ -130,16 +131,20 @@ function parseActionArgs(type: ActionType, argsString: string): string[] {
 }
 
 /**
- * Execute a single parsed action
+ * Execute a single parsed action. Commands it runs are audited under
+ * `origin`, e.g. `self_test` when the self-test runner drives it.
  */
-export async function executeAction(action: ParsedAction): Promise<ActionResult> {
+export async function executeAction(
+  action: ParsedAction,
+  origin: CommandOrigin = 'voice'
+): Promise<ActionResult> {
   try {
     switch (action.type) {
       case 'mail':
-        return await executeMail(action)
+        return await executeMail(action, origin)
 
       case 'sling':
-        return await executeSling(action)
+        return await executeSling(action, origin)
 
       case 'ask_mayor':
         return await executeAskMayor(action)
@@ -151,7 +156,7 @@ export async function executeAction(action: ParsedAction): Promise<ActionResult>
         return await executeDeepQuery(action)
 
       case 'emergency_stop':
-        return await executeEmergencyStop(action)
+        return await executeEmergencyStop(action, origin)
 
       default:
         return {
@@ -170,13 +175,16 @@ export async function executeAction(action: ParsedAction): Promise<ActionResult>
 }
 
 /**
- * Execute all actions in sequence
+ * Execute all actions in sequence, audited under `origin`
  */
-export async function executeActions(actions: ParsedAction[]): Promise<ActionResult[]> {
+export async function executeActions(
+  actions: ParsedAction[],
+  origin: CommandOrigin = 'voice'
+): Promise<ActionResult[]> {
   const results: ActionResult[] = []
 
   for (const action of actions) {
-    const result = await executeAction(action)
+    const result = await executeAction(action, origin)
     results.push(result)
 
     // Stop on first failure if critical
@@ -219,7 +227,7 @@ export function formatResultsForVoice(results: ActionResult[]): string {
 
 // Action Executors
 
-async function executeMail(action: ParsedAction): Promise<ActionResult> {
+async function executeMail(action: ParsedAction, origin: CommandOrigin): Promise<ActionResult> {
   const [recipient, subject, body] = action.args
 
   if (!recipient) {
@@ -248,6 +256,7 @@ async function executeMail(action: ParsedAction): Promise<ActionResult> {
       to: recipient,
       subject: mailSubject,
       body: mailBody,
+      origin,
     })
 
     if (success) {
@@ -272,7 +281,7 @@ async function executeMail(action: ParsedAction): Promise<ActionResult> {
   }
 }
 
-async function executeSling(action: ParsedAction): Promise<ActionResult> {
+async function executeSling(action: ParsedAction, origin: CommandOrigin): Promise<ActionResult> {
   const [beadId, rigName] = action.args
 
   if (!beadId || !rigName) {
@@ -284,8 +293,10 @@ async function executeSling(action: ParsedAction): Promise<ActionResult> {
   }
 
   try {
-    await invoke('run_gt_command', {
-      args: ['sling', beadId, rigName],
+    await invoke('sling_bead', {
+      beadId,
+      target: rigName,
+      origin,
     })
 
     return {
@@ -364,11 +375,21 @@ async function executeDeepQuery(action: ParsedAction): Promise<ActionResult> {
   }
 }
 
-async function executeEmergencyStop(action: ParsedAction): Promise<ActionResult> {
+async function executeEmergencyStop(
+  action: ParsedAction,
+  origin: CommandOrigin
+): Promise<ActionResult> {
   try {
+    // The backend asks the user to confirm in a native dialog
+    const confirmation = await invoke<{ token: string }>('request_command_confirmation', {
+      cmd: 'gt',
+      args: ['stop', '--all'],
+    })
     await invoke('run_gt_command', {
       cmd: 'gt',
       args: ['stop', '--all'],
+      confirmationToken: confirmation.token,
+      origin,
     })
 
     return {
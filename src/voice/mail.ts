@@ -6,6 +6,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/core'
+import type { CommandOrigin } from '../hooks/useAuditLog'
 
 // Mail message structure matching gt mail output
 export interface MailMessage {
@@ -26,6 +27,8 @@ export interface MailSendOptions {
   subject: string
   body: string
   priority?: number
+  /** Audited as coming from here; defaults to voice */
+  origin?: CommandOrigin
 }
 
 // Mail check result
@@ -118,7 +121,7 @@ export class VoiceMailbox {
     try {
       const result = await invoke<{ stdout: string; stderr: string; exit_code: number }>(
         'run_gt_command',
-        { args: ['mail', 'inbox', '--json'] }
+        { args: ['mail', 'inbox', '--json'], origin: 'voice' }
       )
 
       if (result.exit_code !== 0) {
@@ -215,7 +218,7 @@ export class VoiceMailbox {
       try {
         const result = await invoke<{ stdout: string; exit_code: number }>(
           'run_gt_command',
-          { args: ['mail', 'thread', threadId, '--json'] }
+          { args: ['mail', 'thread', threadId, '--json'], origin: 'voice' }
         )
 
         if (result.exit_code === 0) {
@@ -249,6 +252,7 @@ export class VoiceMailbox {
     try {
       await invoke('run_gt_command', {
         args: ['mail', 'read', message.id],
+        origin: 'voice',
       })
     } catch (error) {
       console.error('Failed to mark mail as read:', error)
@@ -273,7 +277,7 @@ export class VoiceMailbox {
 
       const result = await invoke<{ exit_code: number; stderr: string }>(
         'run_gt_command',
-        { args }
+        { args, origin: options.origin ?? 'voice' }
       )
 
       return result.exit_code === 0